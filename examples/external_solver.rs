@@ -0,0 +1,103 @@
+use std::{sync::Arc, time::Duration};
+
+use netaheuristics::{
+    algorithms::sa::{FactorSchedule, SimulatedAnnealing},
+    operators::TimedOperator,
+    selectors::RandomSelector,
+    solutions::permutation::{tour_cost, CostMatrix, Permutation, TwoOptOperator},
+    termination::Terminator,
+    Evaluate, ImprovingHeuristic,
+};
+use rand::{Rng, RngCore, SeedableRng};
+
+fn main() {
+    // create random cities
+    let n = 30;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    let cities: Vec<(f32, f32)> = (0..n)
+        .map(|_| (rng.gen_range(0. ..100.), rng.gen_range(0. ..100.)))
+        .collect();
+    let matrix = CostMatrix::new(
+        cities
+            .iter()
+            .map(|a| cities.iter().map(|b| distance(*a, *b)).collect())
+            .collect(),
+    );
+
+    // the solver runs on its own thread, hence shares the costs by an Arc rather than an Rc
+    let shared = Arc::new(matrix.clone());
+    let solver = TimedOperator::new(
+        "window solver",
+        Duration::from_millis(50),
+        move |tour: &Permutation, rng: &mut dyn RngCore| solve_window(tour, &shared, 6, rng),
+    );
+
+    let cost = tour_cost(matrix);
+    let sa = SimulatedAnnealing::builder()
+        .selector(
+            RandomSelector::new(rng.clone())
+                .option(TwoOptOperator::new(cost.clone()))
+                .option(solver),
+        )
+        .terminator(Terminator::builder().iterations(2000).build())
+        .cooling_schedule(FactorSchedule::new(10., 0.005))
+        .rng(rng)
+        .build();
+
+    let initial = Permutation::identity(n, &cost);
+    println!("initial tour: {:.1}", initial.evaluate());
+    let tour = sa.optimize(initial);
+    println!("optimized tour: {:.1}", tour.evaluate());
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+/// Mock of an external solver: reorder a random window of ```size``` consecutive elements optimally, by brute force.
+///
+/// Like a real solver, it may fail, here when the tour is too short.
+fn solve_window(
+    tour: &Permutation,
+    matrix: &CostMatrix,
+    size: usize,
+    rng: &mut dyn RngCore,
+) -> Result<Permutation, String> {
+    let cost = |permutation: &Permutation| permutation.tour_cost(matrix);
+    if tour.len() <= size {
+        return Err("tour is shorter than the window".to_string());
+    }
+
+    let start = rng.gen_range(0..=tour.len() - size);
+    let mut window = tour.order()[start..start + size].to_vec();
+    window.sort();
+    let mut best = tour.clone();
+    loop {
+        let mut order = tour.order().to_vec();
+        order[start..start + size].copy_from_slice(&window);
+        let candidate = Permutation::new(order, &cost);
+        if candidate.evaluate() < best.evaluate() {
+            best = candidate;
+        }
+        if !next_permutation(&mut window) {
+            return Ok(best);
+        }
+    }
+}
+
+/// Advance ```elements``` to the lexicographically next permutation, and return false once they were the last one.
+fn next_permutation(elements: &mut [usize]) -> bool {
+    let Some(pivot) = (1..elements.len())
+        .rev()
+        .find(|&i| elements[i - 1] < elements[i])
+    else {
+        return false;
+    };
+    let successor = (pivot..elements.len())
+        .rev()
+        .find(|&i| elements[i] > elements[pivot - 1])
+        .unwrap();
+    elements.swap(pivot - 1, successor);
+    elements[pivot..].reverse();
+    true
+}
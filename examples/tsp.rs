@@ -1,5 +1,4 @@
 use std::{
-    collections::{HashMap, HashSet},
     hash::Hash,
     time::{Duration, SystemTime},
 };
@@ -10,6 +9,7 @@ use netaheuristics::{
         sa::{FactorSchedule, SimulatedAnnealing},
         vns::VariableNeighborhoodSearch,
     },
+    ordered_set,
     selectors::{AdaptiveSelector, RandomSelector, SequentialSelector},
     termination::{Terminator, TimeTerminator},
     Evaluate, ImprovingHeuristic, Operator, Outcome,
@@ -41,7 +41,7 @@ fn main() {
     let greedy_outcome = Outcome::new(greedy_tour, duration_greedy);
 
     // optimize with VNS
-    let operator1 = TwoOpt::new(cities.as_slice());
+    let operator1 = Swap::new(cities.as_slice());
     let operator2 = Insertion::new(cities.as_slice());
     let vns = VariableNeighborhoodSearch::builder()
         .selector(
@@ -58,10 +58,10 @@ fn main() {
     let cooling_factor = 0.05;
     let minimum_acceptance_probability = 0.05;
     let schedule = FactorSchedule::new(temperature, cooling_factor);
-    let operator = TwoOpt::new(cities.as_slice());
+    let operator = Swap::new(cities.as_slice());
     let sa = SimulatedAnnealing::builder()
         .selector(RandomSelector::new(rng.clone()).option(operator))
-        .operator(TwoOptRandom)
+        .operator(SwapRandom)
         .cooling_schedule(schedule)
         .terminator(
             Terminator::builder()
@@ -91,7 +91,7 @@ fn main() {
 
     // optimize with adaptive VNS
     let decay = 0.5;
-    let operator1 = TwoOpt::new(cities.as_slice());
+    let operator1 = Swap::new(cities.as_slice());
     let operator2 = Insertion::new(cities.as_slice());
     let adaptive_vns = VariableNeighborhoodSearch::builder()
         .selector(
@@ -125,18 +125,18 @@ struct Tour {
 }
 
 #[derive(Clone)]
-struct TwoOpt {
+struct Swap {
     tour: Option<Tour>,
-    cities: Box<Vec<City>>,
+    cities: Vec<City>,
     index1: usize,
     index2: usize,
 }
 
-struct TwoOptRandom;
+struct SwapRandom;
 
 struct Insertion {
     tour: Option<Tour>,
-    cities: Box<Vec<City>>,
+    cities: Vec<City>,
     index1: usize,
     index2: usize,
 }
@@ -173,21 +173,7 @@ impl TSPDestroyer {
 impl Operator for TSPRepairer {
     type Solution = Tour;
     fn shake(&self, mut solution: Self::Solution, _rng: &mut dyn rand::RngCore) -> Self::Solution {
-        let map: HashMap<City, usize> = self
-            .cities
-            .iter()
-            .enumerate()
-            .map(|(index, city)| (city.clone(), index))
-            .collect();
-        let cities: HashSet<City> = self.cities.iter().map(|x| x.to_owned()).collect();
-        let cities_tour: HashSet<City> = solution.cities.clone().into_iter().collect();
-        let cities_missing: HashSet<City> = &cities - &cities_tour;
-        let mut cities_missing: Vec<City> = cities_missing.into_iter().collect();
-        cities_missing.sort_by(|x, y| {
-            let index_x = map[x];
-            let index_y = map[y];
-            index_x.cmp(&index_y)
-        });
+        let cities_missing = ordered_set::difference(&self.cities, &solution.cities);
 
         for city in cities_missing {
             let index_to_place = closest_city_to(&city, &solution.cities);
@@ -198,11 +184,11 @@ impl Operator for TSPRepairer {
     }
 }
 
-fn closest_city_to<'a>(city: &'a City, city_pool: &'a Vec<City>) -> usize {
+fn closest_city_to<'a>(city: &'a City, city_pool: &'a [City]) -> usize {
     let mut city_closest_index = 0;
     let mut distance_minimum = distance(city, &city_pool[0]);
-    for i in 1..city_pool.len() {
-        let distance = distance(city, &city_pool[i]);
+    for (i, city_other) in city_pool.iter().enumerate().skip(1) {
+        let distance = distance(city, city_other);
         if distance < distance_minimum {
             distance_minimum = distance;
             city_closest_index = i;
@@ -222,7 +208,7 @@ impl Operator for TSPDestroyer {
     }
 }
 
-impl Operator for TwoOptRandom {
+impl Operator for SwapRandom {
     type Solution = Tour;
     fn shake(&self, solution: Tour, rng: &mut dyn rand::RngCore) -> Self::Solution {
         let n = solution.cities.len();
@@ -235,11 +221,11 @@ impl Operator for TwoOptRandom {
     }
 }
 
-impl<'a> TwoOpt {
+impl Swap {
     fn new(cities: &[City]) -> Self {
         Self {
             tour: None,
-            cities: Box::new(cities.to_owned()),
+            cities: cities.to_owned(),
             index1: 0,
             index2: 0,
         }
@@ -272,7 +258,7 @@ impl<'a> TwoOpt {
     }
 }
 
-impl<'a> Iterator for TwoOpt {
+impl Iterator for Swap {
     type Item = Tour;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -290,7 +276,7 @@ impl<'a> Iterator for TwoOpt {
     }
 }
 
-impl Operator for TwoOpt {
+impl Operator for Swap {
     type Solution = Tour;
     fn construct_neighborhood(&self, solution: Tour) -> Box<dyn Iterator<Item = Tour>> {
         let mut neighborhood = Self::new(self.cities.as_ref());
@@ -313,7 +299,7 @@ impl Insertion {
     fn new(cities: &[City]) -> Self {
         Self {
             tour: None,
-            cities: Box::new(cities.to_owned()),
+            cities: cities.to_owned(),
             index1: 0,
             index2: 1,
         }
@@ -356,7 +342,7 @@ impl Iterator for Insertion {
 
         if let Some(tour) = &self.tour {
             let tour = tour.reinsert(self.index1, self.index2);
-            return Some(tour);
+            Some(tour)
         } else {
             None
         }
@@ -436,8 +422,8 @@ impl Evaluate for Tour {
         let mut sum = 0.;
 
         for i in 0..self.cities.len() - 1 {
-            let ref city1 = self.cities[i];
-            let ref city2 = self.cities[i + 1];
+            let city1 = &self.cities[i];
+            let city2 = &self.cities[i + 1];
             sum += distance(city1, city2);
         }
 
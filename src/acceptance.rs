@@ -0,0 +1,424 @@
+//! Criteria which decide whether a candidate replaces the incumbent
+use std::{cell::RefCell, rc::Rc};
+
+use rand::Rng;
+
+use crate::algorithms::sa::CoolingSchedule;
+
+/// Decide whether a candidate is accepted as the next incumbent, based on their objectives.
+pub trait AcceptanceCriterion {
+    /// Test whether a candidate with objective ```candidate``` replaces an incumbent with objective ```incumbent```.
+    fn accept(&self, candidate: f32, incumbent: f32, rng: &mut dyn rand::RngCore) -> bool;
+
+    /// Advance the criterion by one proposal, e.g. to cool down. Called before every acceptance test.
+    fn step(&self) {}
+
+    /// Continue a search paused after ```iterations``` proposals, see [crate::pause::SearchState]. By default, the
+    /// criterion continues with its own state.
+    #[allow(unused_variables)]
+    fn resume(&self, iterations: usize) {}
+}
+
+/// Accept iff the candidate is better than the incumbent.
+pub struct BetterOnly;
+
+/// Metropolis acceptance, as used by _simulated annealing_.
+///
+/// Improvements are always accepted, worse candidates with probability exp(-delta / Temperature).
+/// Alternatively, the probability follows the [AcceptanceRule::Glauber] rule.
+/// Once the cooling schedule is frozen, see [CoolingSchedule::is_frozen], only improvements are accepted.
+pub struct Metropolis {
+    cooling_schedule: Rc<dyn CoolingSchedule>,
+    rule: AcceptanceRule,
+    minimum_acceptance_probability: f32,
+    steps_per_temperature: usize,
+    steps_at_temperature: RefCell<usize>,
+}
+
+/// The rule which computes the acceptance probability of a candidate from the temperature, where
+/// delta = objective candidate - objective incumbent.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum AcceptanceRule {
+    /// exp(-delta / Temperature) for worse candidates, 1 otherwise
+    #[default]
+    Metropolis,
+    /// 1 / (1 + exp(delta / Temperature)), i.e. heat-bath acceptance, which also applies to improvements
+    Glauber,
+}
+
+/// Accept iff the candidate is below the water level, which decreases every proposal.
+pub struct GreatDeluge {
+    level: RefCell<f32>,
+    rain_speed: f32,
+}
+
+/// Accept iff the candidate is within a fixed deviation of the best objective seen so far (the record).
+pub struct RecordToRecord {
+    deviation: f32,
+    record: RefCell<f32>,
+}
+
+/// Accept iff the candidate is worse than the incumbent by less than a threshold, which decays every proposal.
+pub struct ThresholdAccepting {
+    threshold: RefCell<f32>,
+    decay: f32,
+}
+
+/// Accept iff at least one of the criteria accepts, e.g. Metropolis or within a record-to-record deviation.
+///
+/// Every criterion is asked, such that stateful criteria, e.g. [RecordToRecord], observe every proposal.
+pub struct AnyAcceptance {
+    criteria: Vec<Box<dyn AcceptanceCriterion>>,
+}
+
+/// Accept iff all criteria accept.
+///
+/// Every criterion is asked, such that stateful criteria, e.g. [RecordToRecord], observe every proposal.
+pub struct AllAcceptance {
+    criteria: Vec<Box<dyn AcceptanceCriterion>>,
+}
+
+impl AcceptanceCriterion for BetterOnly {
+    fn accept(&self, candidate: f32, incumbent: f32, _rng: &mut dyn rand::RngCore) -> bool {
+        candidate < incumbent
+    }
+}
+
+impl AnyAcceptance {
+    pub fn new(criteria: Vec<Box<dyn AcceptanceCriterion>>) -> Self {
+        Self { criteria }
+    }
+
+    /// Add a criterion.
+    pub fn criterion<T: AcceptanceCriterion + 'static>(mut self, criterion: T) -> Self {
+        self.criteria.push(Box::new(criterion));
+        self
+    }
+}
+
+impl AcceptanceCriterion for AnyAcceptance {
+    fn accept(&self, candidate: f32, incumbent: f32, rng: &mut dyn rand::RngCore) -> bool {
+        accepted_by(&self.criteria, candidate, incumbent, rng) > 0
+    }
+
+    fn step(&self) {
+        self.criteria.iter().for_each(|criterion| criterion.step())
+    }
+
+    fn resume(&self, iterations: usize) {
+        self.criteria
+            .iter()
+            .for_each(|criterion| criterion.resume(iterations))
+    }
+}
+
+impl AllAcceptance {
+    pub fn new(criteria: Vec<Box<dyn AcceptanceCriterion>>) -> Self {
+        Self { criteria }
+    }
+
+    /// Add a criterion.
+    pub fn criterion<T: AcceptanceCriterion + 'static>(mut self, criterion: T) -> Self {
+        self.criteria.push(Box::new(criterion));
+        self
+    }
+}
+
+impl AcceptanceCriterion for AllAcceptance {
+    fn accept(&self, candidate: f32, incumbent: f32, rng: &mut dyn rand::RngCore) -> bool {
+        accepted_by(&self.criteria, candidate, incumbent, rng) == self.criteria.len()
+    }
+
+    fn step(&self) {
+        self.criteria.iter().for_each(|criterion| criterion.step())
+    }
+
+    fn resume(&self, iterations: usize) {
+        self.criteria
+            .iter()
+            .for_each(|criterion| criterion.resume(iterations))
+    }
+}
+
+impl Metropolis {
+    pub fn new<T: CoolingSchedule + 'static>(cooling_schedule: T) -> Self {
+        Self::shared(Rc::new(cooling_schedule))
+    }
+
+    /// Use a cooling schedule which is shared with others, e.g. a termination criterium.
+    pub fn shared(cooling_schedule: Rc<dyn CoolingSchedule>) -> Self {
+        Self {
+            cooling_schedule,
+            rule: AcceptanceRule::Metropolis,
+            minimum_acceptance_probability: 0.,
+            steps_per_temperature: 1,
+            steps_at_temperature: RefCell::new(0),
+        }
+    }
+
+    /// Set the rule which computes the acceptance probability (default: [AcceptanceRule::Metropolis])
+    pub fn rule(mut self, rule: AcceptanceRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Set a lower bound on the acceptance probability of worse candidates (default: 0)
+    pub fn minimum_acceptance_probability(mut self, probability: f32) -> Self {
+        self.minimum_acceptance_probability = probability;
+        self
+    }
+
+    /// Set the number of proposals at each temperature before the schedule cools (default: 1)
+    pub fn steps_per_temperature(mut self, steps: usize) -> Self {
+        self.steps_per_temperature = steps;
+        self
+    }
+
+    /// Return the current temperature.
+    pub fn temperature(&self) -> f32 {
+        self.cooling_schedule.temperature()
+    }
+}
+
+impl AcceptanceCriterion for Metropolis {
+    fn accept(&self, candidate: f32, incumbent: f32, rng: &mut dyn rand::RngCore) -> bool {
+        if self.cooling_schedule.is_frozen() {
+            return candidate < incumbent;
+        }
+        let temperature = self.cooling_schedule.temperature();
+        let r: f32 = rng.gen();
+        let acceptance_probability = self.rule.probability(temperature, incumbent, candidate);
+        r <= acceptance_probability.max(self.minimum_acceptance_probability)
+    }
+
+    /// Cool after every ```steps_per_temperature``` proposals.
+    fn step(&self) {
+        let steps = *self.steps_at_temperature.borrow() + 1;
+        if steps >= self.steps_per_temperature {
+            self.cooling_schedule.cool();
+            self.steps_at_temperature.replace(0);
+        } else {
+            self.steps_at_temperature.replace(steps);
+        }
+    }
+
+    /// Continue counting the proposals at the current temperature, which itself is restored by the heuristic, see
+    /// [CoolingSchedule::set_temperature].
+    fn resume(&self, iterations: usize) {
+        self.steps_at_temperature
+            .replace(iterations % self.steps_per_temperature.max(1));
+    }
+}
+
+impl GreatDeluge {
+    pub fn new(initial_level: f32, rain_speed: f32) -> Self {
+        Self {
+            level: RefCell::new(initial_level),
+            rain_speed,
+        }
+    }
+
+    /// Return the current water level.
+    pub fn level(&self) -> f32 {
+        *self.level.borrow()
+    }
+}
+
+impl AcceptanceCriterion for GreatDeluge {
+    fn accept(&self, candidate: f32, _incumbent: f32, _rng: &mut dyn rand::RngCore) -> bool {
+        candidate <= *self.level.borrow()
+    }
+
+    fn step(&self) {
+        *self.level.borrow_mut() -= self.rain_speed;
+    }
+}
+
+impl RecordToRecord {
+    pub fn new(deviation: f32) -> Self {
+        Self {
+            deviation,
+            record: RefCell::new(f32::INFINITY),
+        }
+    }
+}
+
+impl AcceptanceCriterion for RecordToRecord {
+    fn accept(&self, candidate: f32, incumbent: f32, _rng: &mut dyn rand::RngCore) -> bool {
+        let record = self.record.borrow().min(incumbent).min(candidate);
+        self.record.replace(record);
+        candidate <= record + self.deviation
+    }
+}
+
+impl ThresholdAccepting {
+    pub fn new(initial_threshold: f32, decay: f32) -> Self {
+        Self {
+            threshold: RefCell::new(initial_threshold),
+            decay,
+        }
+    }
+}
+
+impl AcceptanceCriterion for ThresholdAccepting {
+    fn accept(&self, candidate: f32, incumbent: f32, _rng: &mut dyn rand::RngCore) -> bool {
+        candidate - incumbent < *self.threshold.borrow()
+    }
+
+    fn step(&self) {
+        *self.threshold.borrow_mut() *= 1. - self.decay;
+    }
+}
+
+impl AcceptanceRule {
+    /// Compute the probability that the candidate is accepted.
+    pub fn probability(
+        &self,
+        temperature: f32,
+        objective_incumbent: f32,
+        objective_candidate: f32,
+    ) -> f32 {
+        match self {
+            AcceptanceRule::Metropolis => {
+                compute_probability(temperature, objective_incumbent, objective_candidate)
+            }
+            AcceptanceRule::Glauber => {
+                let delta = objective_candidate - objective_incumbent;
+                1. / (1. + (delta / temperature).exp())
+            }
+        }
+    }
+}
+
+/// Count the criteria which accept, asking every criterion.
+fn accepted_by(
+    criteria: &[Box<dyn AcceptanceCriterion>],
+    candidate: f32,
+    incumbent: f32,
+    rng: &mut dyn rand::RngCore,
+) -> usize {
+    criteria
+        .iter()
+        .filter(|criterion| criterion.accept(candidate, incumbent, rng))
+        .count()
+}
+
+pub(crate) fn compute_probability(
+    temperature: f32,
+    objective_incumbent: f32,
+    objective_candidate: f32,
+) -> f32 {
+    let delta = objective_candidate - objective_incumbent;
+    if delta > 0. {
+        (-delta / temperature).exp()
+    } else {
+        1.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rand::SeedableRng;
+
+    use crate::{
+        acceptance::{
+            AcceptanceCriterion, AcceptanceRule, AllAcceptance, AnyAcceptance, BetterOnly,
+            GreatDeluge, Metropolis, RecordToRecord, ThresholdAccepting,
+        },
+        algorithms::sa::FactorSchedule,
+    };
+
+    #[test]
+    fn better_only() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        assert!(BetterOnly.accept(1., 2., &mut rng));
+        assert!(!BetterOnly.accept(2., 2., &mut rng));
+    }
+
+    #[test]
+    fn great_deluge_level_drops() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let criterion = GreatDeluge::new(10., 1.);
+        assert!(criterion.accept(10., 0., &mut rng));
+        criterion.step();
+        assert!(!criterion.accept(10., 0., &mut rng));
+        assert!(criterion.accept(9., 0., &mut rng));
+    }
+
+    #[test]
+    fn record_to_record_deviation() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let criterion = RecordToRecord::new(1.);
+        assert!(criterion.accept(5.5, 5., &mut rng));
+        assert!(!criterion.accept(6.5, 5., &mut rng));
+        // the record drops to 2
+        assert!(criterion.accept(2., 5., &mut rng));
+        assert!(!criterion.accept(3.5, 2., &mut rng));
+    }
+
+    #[test]
+    fn threshold_accepting_decays() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let criterion = ThresholdAccepting::new(2., 0.5);
+        assert!(criterion.accept(6.5, 5., &mut rng));
+        criterion.step();
+        assert!(!criterion.accept(6.5, 5., &mut rng));
+        assert!(criterion.accept(5.5, 5., &mut rng));
+    }
+
+    #[test]
+    fn combined_acceptance() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        // better only, or below the level 5
+        let any = AnyAcceptance::new(vec![])
+            .criterion(BetterOnly)
+            .criterion(GreatDeluge::new(5., 0.));
+        let all = AllAcceptance::new(vec![
+            Box::new(BetterOnly),
+            Box::new(GreatDeluge::new(5., 0.)),
+        ]);
+
+        // both accept
+        assert!(any.accept(4., 6., &mut rng));
+        assert!(all.accept(4., 6., &mut rng));
+        // only better only accepts
+        assert!(any.accept(6., 7., &mut rng));
+        assert!(!all.accept(6., 7., &mut rng));
+        // only great deluge accepts
+        assert!(any.accept(4., 3., &mut rng));
+        assert!(!all.accept(4., 3., &mut rng));
+        // neither accepts
+        assert!(!any.accept(7., 6., &mut rng));
+        assert!(!all.accept(7., 6., &mut rng));
+    }
+
+    #[test]
+    fn metropolis_temperature() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let cold = Metropolis::new(FactorSchedule::new(0.001, 0.));
+        assert!((0..1000).all(|_| !cold.accept(100., 0., &mut rng)));
+        assert!(cold.accept(0., 100., &mut rng));
+
+        // a worsening of 1 at temperature 1 is accepted with probability exp(-1)
+        let hot = Metropolis::new(FactorSchedule::new(1., 0.));
+        let accepted = (0..1000).filter(|_| hot.accept(1., 0., &mut rng)).count();
+        assert!((300..440).contains(&accepted), "{accepted}");
+        assert_approx_eq!(
+            AcceptanceRule::Metropolis.probability(2., 10., 11.),
+            (-0.5f32).exp()
+        );
+        assert_approx_eq!(AcceptanceRule::Metropolis.probability(2., 10., 9.), 1.);
+    }
+
+    #[test]
+    fn glauber_probability() {
+        for (delta, temperature) in [(1., 1.), (-1., 1.), (0., 5.), (3., 2.), (-0.5, 0.1)] {
+            let probability = AcceptanceRule::Glauber.probability(temperature, 10., 10. + delta);
+            let expected = 1. / (1. + f32::exp(delta / temperature));
+            assert_approx_eq!(probability, expected);
+            assert!(probability > 0. && probability < 1.);
+        }
+        assert_approx_eq!(AcceptanceRule::Glauber.probability(1., 0., 0.), 0.5);
+    }
+}
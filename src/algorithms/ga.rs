@@ -0,0 +1,469 @@
+//! _genetic algorithm_
+use std::cell::RefCell;
+
+use rand::Rng;
+
+use crate::{
+    algorithms::sa::CoolingSchedule,
+    default_rng, is_improvement,
+    population::{Generational, Replacement},
+    termination::TerminationCriteria,
+    Evaluate, Operator, Reseed, SearchRng,
+};
+
+/// Combine two parents into a child.
+pub trait Crossover {
+    type Solution: Evaluate;
+    fn crossover(
+        &self,
+        parent1: &Self::Solution,
+        parent2: &Self::Solution,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self::Solution;
+}
+
+/// Evaluates a whole population at once, returning the objectives in order.
+pub type PopulationEvaluator<Solution> = fn(&[Solution]) -> Vec<f32>;
+
+/// Genetic Algorithm implementation.
+///
+/// Every generation, parents are drawn by tournament selection, recombined by the crossover operator,
+/// and mutated by the ```shake``` of the mutation operator. The [Replacement] strategy decides which
+/// individuals survive. Termination is tested on the best solution after every generation.
+pub struct GeneticAlgorithm<Solution> {
+    mutation: Box<dyn Operator<Solution = Solution>>,
+    crossover: Box<dyn Crossover<Solution = Solution>>,
+    replacement: Box<dyn Replacement<Solution>>,
+    terminator: Box<dyn TerminationCriteria<Solution>>,
+    rng: RefCell<SearchRng>,
+    offspring_size: Option<usize>,
+    mutation_rate: f32,
+    crossover_rate: f32,
+    mutation_rate_schedule: Option<Box<dyn CoolingSchedule>>,
+    crossover_rate_schedule: Option<Box<dyn CoolingSchedule>>,
+    tournament_size: usize,
+    reseed: Option<Reseed>,
+    evaluate: PopulationEvaluator<Solution>,
+}
+
+/// Builder design pattern for [GeneticAlgorithm].
+pub struct GABuilder<Solution> {
+    mutation: Option<Box<dyn Operator<Solution = Solution>>>,
+    crossover: Option<Box<dyn Crossover<Solution = Solution>>>,
+    replacement: Option<Box<dyn Replacement<Solution>>>,
+    terminator: Option<Box<dyn TerminationCriteria<Solution>>>,
+    rng: Option<SearchRng>,
+    offspring_size: Option<usize>,
+    mutation_rate: f32,
+    crossover_rate: f32,
+    mutation_rate_schedule: Option<Box<dyn CoolingSchedule>>,
+    crossover_rate_schedule: Option<Box<dyn CoolingSchedule>>,
+    tournament_size: usize,
+    reseed: Option<Reseed>,
+    evaluate: Option<PopulationEvaluator<Solution>>,
+}
+
+impl<Solution> GeneticAlgorithm<Solution> {
+    pub fn builder() -> GABuilder<Solution> {
+        GABuilder {
+            mutation: None,
+            crossover: None,
+            replacement: None,
+            terminator: None,
+            rng: None,
+            offspring_size: None,
+            mutation_rate: 0.1,
+            crossover_rate: 0.9,
+            mutation_rate_schedule: None,
+            crossover_rate_schedule: None,
+            tournament_size: 2,
+            reseed: None,
+            evaluate: None,
+        }
+    }
+
+    /// Evolve ```population``` until termination, and return the best solution ever found.
+    pub fn optimize(self, population: Vec<Solution>) -> Solution
+    where
+        Solution: Clone + Evaluate,
+    {
+        let mut population = population;
+        let mut best_solution = self.best_of(&population).clone();
+        let offspring_size = self.offspring_size.unwrap_or(population.len());
+
+        loop {
+            if let Some(reseed) = &self.reseed {
+                reseed.tick(&self.rng);
+            }
+            let objectives = (self.evaluate)(&population);
+            let offspring: Vec<Solution> = (0..offspring_size)
+                .map(|_| self.breed(&population, &objectives))
+                .collect();
+
+            let objectives_offspring = (self.evaluate)(&offspring);
+            let index_best = index_of_best(&objectives_offspring);
+            if is_improvement(
+                objectives_offspring[index_best],
+                best_solution.evaluate(),
+                0.,
+            ) {
+                best_solution = offspring[index_best].clone();
+            }
+
+            population = self.replacement.replace(population, offspring);
+            if let Some(schedule) = &self.mutation_rate_schedule {
+                schedule.cool();
+            }
+            if let Some(schedule) = &self.crossover_rate_schedule {
+                schedule.cool();
+            }
+
+            if self.terminator.terminate(&best_solution) {
+                break;
+            }
+        }
+        best_solution
+    }
+
+    /// Return the probability that a child is mutated in the current generation.
+    pub fn mutation_rate(&self) -> f32 {
+        match &self.mutation_rate_schedule {
+            Some(schedule) => schedule.temperature(),
+            None => self.mutation_rate,
+        }
+    }
+
+    /// Return the probability that a child is created by crossover in the current generation.
+    pub fn crossover_rate(&self) -> f32 {
+        match &self.crossover_rate_schedule {
+            Some(schedule) => schedule.temperature(),
+            None => self.crossover_rate,
+        }
+    }
+
+    /// Create a child from two parents selected by tournament.
+    fn breed(&self, population: &[Solution], objectives: &[f32]) -> Solution
+    where
+        Solution: Clone + Evaluate,
+    {
+        let parent1 = &population[self.tournament(objectives)];
+        let parent2 = &population[self.tournament(objectives)];
+        let mut rng = self.rng.borrow_mut();
+
+        let child = if rng.gen::<f32>() < self.crossover_rate() {
+            self.crossover.crossover(parent1, parent2, &mut *rng)
+        } else {
+            parent1.clone()
+        };
+
+        if rng.gen::<f32>() < self.mutation_rate() {
+            self.mutation.shake(child, &mut *rng)
+        } else {
+            child
+        }
+    }
+
+    /// Return the index of the best of ```tournament_size``` individuals drawn uniformly at random.
+    fn tournament(&self, objectives: &[f32]) -> usize {
+        let mut rng = self.rng.borrow_mut();
+        let mut winner = rng.gen_range(0..objectives.len());
+        for _ in 1..self.tournament_size {
+            let contender = rng.gen_range(0..objectives.len());
+            if is_improvement(objectives[contender], objectives[winner], 0.) {
+                winner = contender;
+            }
+        }
+        winner
+    }
+
+    fn best_of<'a>(&self, population: &'a [Solution]) -> &'a Solution {
+        &population[index_of_best(&(self.evaluate)(population))]
+    }
+}
+
+impl<Solution: Evaluate + 'static> GABuilder<Solution> {
+    /// Build the configured Genetic Algorithm
+    pub fn build(self) -> GeneticAlgorithm<Solution> {
+        GeneticAlgorithm {
+            mutation: self.mutation.expect("No mutation operator specified"),
+            crossover: self.crossover.expect("No crossover operator specified"),
+            replacement: self.replacement.unwrap_or(Box::new(Generational)),
+            terminator: self.terminator.expect("No termination criteria specified"),
+            rng: RefCell::new(
+                self.rng
+                    .unwrap_or_else(|| SearchRng::Custom(default_rng("GABuilder"))),
+            ),
+            offspring_size: self.offspring_size,
+            mutation_rate: self.mutation_rate,
+            crossover_rate: self.crossover_rate,
+            mutation_rate_schedule: self.mutation_rate_schedule,
+            crossover_rate_schedule: self.crossover_rate_schedule,
+            tournament_size: self.tournament_size,
+            reseed: self.reseed,
+            evaluate: self.evaluate.unwrap_or(Solution::evaluate_batch),
+        }
+    }
+
+    /// Set the operator whose ```shake``` mutates a child
+    pub fn mutation<T: Operator<Solution = Solution> + 'static>(mut self, operator: T) -> Self {
+        self.mutation = Some(Box::new(operator));
+        self
+    }
+
+    /// Set the crossover operator
+    pub fn crossover<T: Crossover<Solution = Solution> + 'static>(mut self, crossover: T) -> Self {
+        self.crossover = Some(Box::new(crossover));
+        self
+    }
+
+    /// Set the replacement strategy (default: [Generational])
+    pub fn replacement<T: Replacement<Solution> + 'static>(mut self, replacement: T) -> Self {
+        self.replacement = Some(Box::new(replacement));
+        self
+    }
+
+    /// Set termination criteria
+    pub fn terminator<T: TerminationCriteria<Solution> + 'static>(mut self, terminator: T) -> Self {
+        self.terminator = Some(Box::new(terminator));
+        self
+    }
+
+    /// Set source of randomness (default: seeded with [crate::DEFAULT_SEED])
+    pub fn rng<T: rand::RngCore + 'static>(mut self, rng: T) -> Self {
+        self.rng = Some(SearchRng::Custom(Box::new(rng)));
+        self
+    }
+
+    /// Reseed the source of randomness from ```seed``` after every ```generations``` generations, e.g. with external entropy in very long runs.
+    ///
+    /// The source of randomness is replaced by a [rand::rngs::StdRng]. Unless ```seed``` is deterministic, this breaks reproducibility, by design.
+    pub fn reseed_every<F: Fn() -> u64 + 'static>(mut self, generations: usize, seed: F) -> Self {
+        self.reseed = Some(Reseed::new(generations, seed));
+        self
+    }
+
+    /// Set the number of offspring per generation (default: the population size)
+    pub fn offspring_size(mut self, offspring_size: usize) -> Self {
+        self.offspring_size = Some(offspring_size);
+        self
+    }
+
+    /// Set the probability that a child is mutated (default: 0.1)
+    pub fn mutation_rate(mut self, rate: f32) -> Self {
+        self.mutation_rate = rate;
+        self
+    }
+
+    /// Set the probability that a child is created by crossover instead of copying a parent (default: 0.9)
+    pub fn crossover_rate(mut self, rate: f32) -> Self {
+        self.crossover_rate = rate;
+        self
+    }
+
+    /// Anneal the mutation rate over the generations, overriding the fixed mutation rate.
+    ///
+    /// The temperature of the schedule is used as rate, and the schedule cools after every generation.
+    pub fn mutation_rate_schedule<T: CoolingSchedule + 'static>(mut self, schedule: T) -> Self {
+        self.mutation_rate_schedule = Some(Box::new(schedule));
+        self
+    }
+
+    /// Anneal the crossover rate over the generations, overriding the fixed crossover rate.
+    ///
+    /// The temperature of the schedule is used as rate, and the schedule cools after every generation.
+    pub fn crossover_rate_schedule<T: CoolingSchedule + 'static>(mut self, schedule: T) -> Self {
+        self.crossover_rate_schedule = Some(Box::new(schedule));
+        self
+    }
+
+    /// Set the number of contenders per tournament (default: 2)
+    pub fn tournament_size(mut self, tournament_size: usize) -> Self {
+        self.tournament_size = tournament_size;
+        self
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<Solution: Evaluate + Send + Sync + 'static> GABuilder<Solution> {
+    /// Evaluate the population and the offspring in parallel (default: false)
+    ///
+    /// Every individual is evaluated on its own by ```evaluate```, hence an overridden ```evaluate_batch``` is bypassed.
+    /// Solutions which are not ```Send + Sync``` cannot enable this, and are always evaluated sequentially.
+    pub fn parallel_evaluation(mut self, enabled: bool) -> Self {
+        self.evaluate = if enabled {
+            Some(crate::parallel::evaluate_population)
+        } else {
+            None
+        };
+        self
+    }
+}
+
+fn index_of_best(objectives: &[f32]) -> usize {
+    let mut index_best = 0;
+    for (index, objective) in objectives.iter().enumerate() {
+        if is_improvement(*objective, objectives[index_best], 0.) {
+            index_best = index;
+        }
+    }
+    index_best
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use assert_approx_eq::assert_approx_eq;
+    use rand::{Rng, SeedableRng};
+
+    use crate::{
+        algorithms::{
+            ga::{Crossover, GeneticAlgorithm},
+            sa::{CoolingSchedule, FactorSchedule},
+        },
+        population::Elitist,
+        termination::IterationTerminator,
+        test::*,
+        Evaluate, Operator,
+    };
+
+    thread_local! {
+        static BATCHES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Counts the number of batch evaluations.
+    #[derive(Clone)]
+    struct Batched(f32);
+
+    impl Evaluate for Batched {
+        fn evaluate(&self) -> f32 {
+            self.0
+        }
+
+        fn evaluate_batch(solutions: &[Self]) -> Vec<f32> {
+            BATCHES.with(|batches| batches.set(batches.get() + 1));
+            solutions.iter().map(|x| x.0).collect()
+        }
+    }
+
+    struct Decrement;
+
+    impl Operator for Decrement {
+        type Solution = Batched;
+
+        fn shake(&self, solution: Batched, _rng: &mut dyn rand::RngCore) -> Batched {
+            Batched(solution.0 - 1.)
+        }
+    }
+
+    struct Average;
+
+    impl Crossover for Average {
+        type Solution = Batched;
+
+        fn crossover(
+            &self,
+            parent1: &Batched,
+            parent2: &Batched,
+            _rng: &mut dyn rand::RngCore,
+        ) -> Batched {
+            Batched((parent1.0 + parent2.0) / 2.)
+        }
+    }
+
+    struct PickParent;
+
+    impl Crossover for PickParent {
+        type Solution = Number;
+
+        fn crossover(
+            &self,
+            parent1: &Number,
+            parent2: &Number,
+            rng: &mut dyn rand::RngCore,
+        ) -> Number {
+            if rng.gen::<bool>() {
+                parent1.clone()
+            } else {
+                parent2.clone()
+            }
+        }
+    }
+
+    #[test]
+    fn ga_finds_optimum() {
+        let numbers = vec![7., 6., 5., 4., 3., 2., 1., 0.];
+        let ga = GeneticAlgorithm::builder()
+            .mutation(NeighborSwap::new(&numbers))
+            .crossover(PickParent)
+            .replacement(Elitist(1))
+            .mutation_rate(0.5)
+            .terminator(IterationTerminator::new(50))
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+
+        let population = (0..4)
+            .map(|index| Number::new(index, numbers[index]))
+            .collect();
+        let solution = ga.optimize(population);
+        assert_eq!(solution.index(), 7);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn ga_parallel_evaluation() {
+        let numbers = vec![7., 6., 5., 4., 3., 2., 1., 0.];
+        let best = |parallel: bool| {
+            let ga = GeneticAlgorithm::builder()
+                .mutation(NeighborSwap::new(&numbers))
+                .crossover(PickParent)
+                .replacement(Elitist(1))
+                .mutation_rate(0.5)
+                .parallel_evaluation(parallel)
+                .terminator(IterationTerminator::new(20))
+                .rng(rand::rngs::StdRng::seed_from_u64(0))
+                .build();
+            let population = (0..4)
+                .map(|index| Number::new(index, numbers[index]))
+                .collect();
+            ga.optimize(population).index()
+        };
+        assert_eq!(best(true), best(false));
+    }
+
+    #[test]
+    fn ga_mutation_rate_schedule() {
+        let numbers = vec![7., 6., 5., 4., 3., 2., 1., 0.];
+        let schedule = Rc::new(FactorSchedule::new(0.8, 0.2));
+        let ga = GeneticAlgorithm::builder()
+            .mutation(NeighborSwap::new(&numbers))
+            .crossover(PickParent)
+            .mutation_rate_schedule(schedule.clone())
+            .terminator(IterationTerminator::new(10))
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+        assert_approx_eq!(ga.mutation_rate(), 0.8);
+        assert_approx_eq!(ga.crossover_rate(), 0.9);
+
+        let population = (0..4)
+            .map(|index| Number::new(index, numbers[index]))
+            .collect();
+        ga.optimize(population);
+        assert_approx_eq!(schedule.temperature(), 0.8 * 0.8_f32.powi(10));
+    }
+
+    #[test]
+    fn ga_evaluates_in_batches() {
+        let ga = GeneticAlgorithm::builder()
+            .mutation(Decrement)
+            .crossover(Average)
+            .terminator(IterationTerminator::new(3))
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+
+        BATCHES.with(|batches| batches.set(0));
+        ga.optimize(vec![Batched(1.), Batched(2.), Batched(3.)]);
+        // the initial best, then the population and the offspring of every generation
+        assert_eq!(BATCHES.with(|batches| batches.get()), 1 + 2 * 3);
+    }
+}
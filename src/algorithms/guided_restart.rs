@@ -0,0 +1,208 @@
+//! _guided restarts_ of a stalled search
+use std::cell::RefCell;
+
+use crate::{
+    default_rng,
+    termination::{ImprovementRateTerminator, StallSignal},
+    Evaluate, ImprovingHeuristic, Operator, OptimizeError,
+};
+
+/// Wraps a heuristic, and restarts it from a perturbed best solution whenever the search stalls.
+///
+/// Stalls are detected by an [ImprovementRateTerminator]. On [StallSignal::Restart] the best solution found so far
+/// is shaken by the perturbation operator, and unconditionally accepted as the new incumbent. The search terminates
+/// on [StallSignal::Terminate], or when the wrapped heuristic terminates.
+pub struct GuidedRestart<Solution> {
+    heuristic: Box<dyn ImprovingHeuristic<Solution>>,
+    detector: ImprovementRateTerminator,
+    perturbation: Box<dyn Operator<Solution = Solution>>,
+    rng: RefCell<Box<dyn rand::RngCore>>,
+    best: RefCell<Option<Solution>>,
+    restarting: RefCell<bool>,
+}
+
+/// Builder design pattern for [GuidedRestart].
+pub struct GuidedRestartBuilder<Solution> {
+    heuristic: Option<Box<dyn ImprovingHeuristic<Solution>>>,
+    detector: Option<ImprovementRateTerminator>,
+    perturbation: Option<Box<dyn Operator<Solution = Solution>>>,
+    rng: Option<Box<dyn rand::RngCore>>,
+}
+
+impl<Solution> GuidedRestart<Solution> {
+    pub fn builder() -> GuidedRestartBuilder<Solution> {
+        GuidedRestartBuilder {
+            heuristic: None,
+            detector: None,
+            perturbation: None,
+            rng: None,
+        }
+    }
+
+    /// Return the number of restarts performed so far.
+    pub fn restarts(&self) -> usize {
+        self.detector.restarts_signalled()
+    }
+}
+
+impl<Solution> GuidedRestartBuilder<Solution> {
+    /// Build the configured guided restart driver
+    pub fn build(self) -> GuidedRestart<Solution> {
+        GuidedRestart {
+            heuristic: self.heuristic.expect("No heuristic specified"),
+            detector: self.detector.expect("No stall detector specified"),
+            perturbation: self
+                .perturbation
+                .expect("No perturbation operator specified"),
+            rng: RefCell::new(
+                self.rng
+                    .unwrap_or_else(|| default_rng("GuidedRestartBuilder")),
+            ),
+            best: RefCell::new(None),
+            restarting: RefCell::new(false),
+        }
+    }
+
+    /// Set the heuristic to be restarted
+    pub fn heuristic<H: ImprovingHeuristic<Solution> + 'static>(mut self, heuristic: H) -> Self {
+        self.heuristic = Some(Box::new(heuristic));
+        self
+    }
+
+    /// Set the stall detector, which also limits the number of restarts
+    pub fn detector(mut self, detector: ImprovementRateTerminator) -> Self {
+        self.detector = Some(detector);
+        self
+    }
+
+    /// Set the operator whose ```shake``` perturbs the best solution on a restart
+    pub fn perturbation<T: Operator<Solution = Solution> + 'static>(mut self, operator: T) -> Self {
+        self.perturbation = Some(Box::new(operator));
+        self
+    }
+
+    /// Set source of randomness (default: seeded with [crate::DEFAULT_SEED])
+    pub fn rng<T: rand::RngCore + 'static>(mut self, rng: T) -> Self {
+        self.rng = Some(Box::new(rng));
+        self
+    }
+}
+
+impl<Solution: Clone + Evaluate> ImprovingHeuristic<Solution> for GuidedRestart<Solution> {
+    /// Accept a restart unconditionally, otherwise defer to the wrapped heuristic.
+    fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool
+    where
+        Solution: Evaluate,
+    {
+        self.restarting.replace(false) || self.heuristic.accept_candidate(candidate, incumbent)
+    }
+
+    /// Perturb the best solution on a restart, otherwise defer to the wrapped heuristic.
+    fn propose_candidate(&self, incumbent: Solution) -> Solution
+    where
+        Solution: Evaluate,
+    {
+        if *self.restarting.borrow() {
+            let best = self.best.borrow().clone().unwrap_or(incumbent);
+            self.perturbation
+                .shake(best, self.rng.borrow_mut().as_mut())
+        } else {
+            self.heuristic.propose_candidate(incumbent)
+        }
+    }
+
+    /// Perturb the best solution on a restart, otherwise defer to the wrapped heuristic.
+    fn try_propose_candidate(&self, incumbent: Solution) -> Option<Solution>
+    where
+        Solution: Evaluate,
+    {
+        if *self.restarting.borrow() {
+            Some(self.propose_candidate(incumbent))
+        } else {
+            self.heuristic.try_propose_candidate(incumbent)
+        }
+    }
+
+    /// Terminate when the wrapped heuristic does, or when the search stalled and no restarts are left.
+    fn should_terminate(&self, incumbent: &Solution) -> bool {
+        if self.heuristic.should_terminate(incumbent) {
+            return true;
+        }
+        match self.detector.signal(incumbent.evaluate()) {
+            StallSignal::Continue => false,
+            StallSignal::Restart => {
+                self.restarting.replace(true);
+                false
+            }
+            StallSignal::Terminate => true,
+        }
+    }
+
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        let initial = self.heuristic.guard_initial(initial)?;
+        self.best.replace(Some(initial.clone()));
+        Ok(initial)
+    }
+
+    fn objective_epsilon(&self) -> f32 {
+        self.heuristic.objective_epsilon()
+    }
+
+    /// Remember the best solution, then defer to the wrapped heuristic.
+    fn callback_new_best(&self, best: &Solution) {
+        self.best.replace(Some(best.clone()));
+        self.heuristic.callback_new_best(best)
+    }
+
+    fn callback_candidate_improved_best(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_improved_best(candidate, incumbent)
+    }
+
+    fn callback_candidate_accepted(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_accepted(candidate, incumbent)
+    }
+
+    fn callback_candidate_rejected(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_rejected(candidate, incumbent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use rand::SeedableRng;
+
+    use crate::{
+        algorithms::{guided_restart::GuidedRestart, vns::VariableNeighborhoodSearch},
+        selectors::SequentialSelector,
+        termination::{ImprovementRateTerminator, IterationTerminator, TerminationCriteria},
+        test::*,
+        ImprovingHeuristic,
+    };
+
+    #[test]
+    fn guided_restart_on_flat_region() {
+        let numbers = vec![5.; 8];
+        let shakes = Rc::new(Cell::new(0));
+        let iterations = Rc::new(IterationTerminator::new(1000));
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(NeighborsUpUntilN::new(&numbers, 1)))
+            .terminator(iterations.clone())
+            .build();
+        let driver = GuidedRestart::builder()
+            .heuristic(vns)
+            .detector(ImprovementRateTerminator::new(3, 0.1).restarts(2))
+            .perturbation(CountingShake::new(shakes.clone()))
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+
+        driver.optimize(Number::new(0, numbers[0]));
+        assert_eq!(shakes.get(), 2);
+        let progress = TerminationCriteria::<Number>::progress(iterations.as_ref()).unwrap();
+        assert!(progress < 1.);
+    }
+}
@@ -0,0 +1,384 @@
+//! _iterated local search_
+use std::cell::RefCell;
+
+use crate::{
+    is_improvement, termination::TerminationCriteria, throttle_callback, try_best_neighbor,
+    warn_default_seed, Callback, Evaluate, ImprovementThreshold, ImprovingHeuristic, InitialGuard,
+    Operator, OptimizeError, ProposalEvaluation, Reseed, SearchRng, DEFAULT_SEED,
+};
+
+/// Iterated Local Search implementation.
+///
+/// Every iteration, the incumbent is perturbed by a number of random moves (the perturbation strength),
+/// after which a local search descends to a local optimum.
+pub struct IteratedLocalSearch<Solution> {
+    perturbation: Box<dyn Operator<Solution = Solution>>,
+    local_search: Box<dyn Operator<Solution = Solution>>,
+    terminator: Box<dyn TerminationCriteria<Solution>>,
+    rng: RefCell<SearchRng>,
+    perturbation_strength: usize,
+    adaptive_perturbation: Option<AdaptivePerturbation>,
+    strength: RefCell<usize>,
+    initial_guard: Option<InitialGuard<Solution>>,
+    on_new_best: Option<Callback<Solution>>,
+    objective_epsilon: f32,
+    max_neighbors_per_step: Option<usize>,
+    reseed: Option<Reseed>,
+    seed: Option<u64>,
+}
+
+/// Builder design pattern for [IteratedLocalSearch].
+pub struct ILSBuilder<Solution> {
+    perturbation: Option<Box<dyn Operator<Solution = Solution>>>,
+    local_search: Option<Box<dyn Operator<Solution = Solution>>>,
+    terminator: Option<Box<dyn TerminationCriteria<Solution>>>,
+    rng: Option<SearchRng>,
+    perturbation_strength: usize,
+    adaptive_perturbation: Option<AdaptivePerturbation>,
+    initial_guard: Option<InitialGuard<Solution>>,
+    on_new_best: Option<Callback<Solution>>,
+    objective_epsilon: f32,
+    max_neighbors_per_step: Option<usize>,
+    reseed: Option<Reseed>,
+    seed: Option<u64>,
+}
+
+/// Grow the perturbation strength after every non-improving iteration.
+struct AdaptivePerturbation {
+    increment: usize,
+    strength_max: usize,
+}
+
+impl<Solution> IteratedLocalSearch<Solution> {
+    pub fn builder() -> ILSBuilder<Solution> {
+        ILSBuilder {
+            perturbation: None,
+            local_search: None,
+            terminator: None,
+            rng: None,
+            perturbation_strength: 1,
+            adaptive_perturbation: None,
+            initial_guard: None,
+            on_new_best: None,
+            objective_epsilon: 0.,
+            max_neighbors_per_step: None,
+            reseed: None,
+            seed: None,
+        }
+    }
+
+    /// Return the number of shakes applied in the next perturbation.
+    pub fn perturbation_strength(&self) -> usize {
+        *self.strength.borrow()
+    }
+
+    fn perturb(&self, mut solution: Solution) -> Solution
+    where
+        Solution: Evaluate,
+    {
+        for _ in 0..*self.strength.borrow() {
+            solution = self
+                .perturbation
+                .shake(solution, &mut *self.rng.borrow_mut());
+        }
+        solution
+    }
+}
+
+impl<Solution> ILSBuilder<Solution> {
+    /// Build the configured Iterated Local Search heuristic
+    pub fn build(mut self) -> IteratedLocalSearch<Solution> {
+        if self.rng.is_none() {
+            warn_default_seed("ILSBuilder");
+            self = self.seed(DEFAULT_SEED);
+        }
+        IteratedLocalSearch {
+            perturbation: self
+                .perturbation
+                .expect("No perturbation operator specified"),
+            local_search: self
+                .local_search
+                .expect("No local search operator specified"),
+            terminator: self.terminator.expect("No termination criteria specified"),
+            rng: RefCell::new(self.rng.expect("source of randomness was defaulted")),
+            perturbation_strength: self.perturbation_strength,
+            adaptive_perturbation: self.adaptive_perturbation,
+            strength: RefCell::new(self.perturbation_strength),
+            initial_guard: self.initial_guard,
+            on_new_best: self.on_new_best,
+            objective_epsilon: self.objective_epsilon,
+            max_neighbors_per_step: self.max_neighbors_per_step,
+            reseed: self.reseed,
+            seed: self.seed,
+        }
+    }
+
+    /// Set the operator whose ```shake``` perturbs the incumbent
+    pub fn perturbation<T: Operator<Solution = Solution> + 'static>(mut self, operator: T) -> Self {
+        self.perturbation = Some(Box::new(operator));
+        self
+    }
+
+    /// Set the operator whose best neighbors are followed down to a local optimum
+    pub fn local_search<T: Operator<Solution = Solution> + 'static>(mut self, operator: T) -> Self {
+        self.local_search = Some(Box::new(operator));
+        self
+    }
+
+    /// Set the number of successive shakes per perturbation (default: 1)
+    pub fn perturbation_strength(mut self, k: usize) -> Self {
+        self.perturbation_strength = k;
+        self
+    }
+
+    /// Increase the perturbation strength by ```increment``` after every non-improving iteration, up to ```strength_max```.
+    ///
+    /// The strength is reset to its initial value whenever the incumbent improves.
+    pub fn adaptive_perturbation(mut self, increment: usize, strength_max: usize) -> Self {
+        self.adaptive_perturbation = Some(AdaptivePerturbation {
+            increment,
+            strength_max,
+        });
+        self
+    }
+
+    /// Set termination criteria
+    pub fn terminator<T: TerminationCriteria<Solution> + 'static>(mut self, terminator: T) -> Self {
+        self.terminator = Some(Box::new(terminator));
+        self
+    }
+
+    /// Set source of randomness (default: seeded with [crate::DEFAULT_SEED])
+    pub fn rng<T: rand::RngCore + 'static>(mut self, rng: T) -> Self {
+        self.rng = Some(SearchRng::Custom(Box::new(rng)));
+        self.seed = None;
+        self
+    }
+
+    /// Seed a [rand::rngs::StdRng] as source of randomness, which is reported by [crate::ImprovingHeuristic::seed],
+    /// e.g. in the [crate::Outcome] of [crate::ImprovingHeuristic::optimize_timed], such that a run can be replayed.
+    ///
+    /// Selectors with their own source of randomness, e.g. [crate::selectors::RandomSelector], are seeded separately.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Some(SearchRng::seeded(seed));
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set a guard which validates, and possibly repairs, the initial solution
+    pub fn initial_guard<F: Fn(Solution) -> Result<Solution, String> + 'static>(
+        mut self,
+        guard: F,
+    ) -> Self {
+        self.initial_guard = Some(Box::new(guard));
+        self
+    }
+
+    /// Register a hook which receives every new best solution, e.g. to persist it
+    pub fn on_new_best<F: Fn(&Solution) + 'static>(mut self, hook: F) -> Self {
+        self.on_new_best = Some(Box::new(hook));
+        self
+    }
+
+    /// Register a hook which only receives the new best solutions that improve on the last reported one by more than
+    /// ```threshold```, e.g. to keep logs meaningful on smooth problems. The search itself still tracks every improvement.
+    pub fn on_new_best_above<F: Fn(&Solution) + 'static>(
+        mut self,
+        threshold: ImprovementThreshold,
+        hook: F,
+    ) -> Self
+    where
+        Solution: Evaluate,
+    {
+        self.on_new_best = Some(throttle_callback(threshold, hook));
+        self
+    }
+
+    /// Require candidates to be better by more than ```epsilon``` to count as improvements (default: 0)
+    pub fn objective_epsilon(mut self, epsilon: f32) -> Self {
+        self.objective_epsilon = epsilon;
+        self
+    }
+
+    /// Evaluate at most ```max_neighbors``` neighbors per descent step, and move to the best of those.
+    pub fn max_neighbors_per_step(mut self, max_neighbors: usize) -> Self {
+        self.max_neighbors_per_step = Some(max_neighbors);
+        self
+    }
+
+    /// Reseed the source of randomness from ```seed``` after every ```iterations``` iterations, e.g. with external entropy in very long runs.
+    ///
+    /// The source of randomness is replaced by a [rand::rngs::StdRng]. Unless ```seed``` is deterministic, this breaks reproducibility, by design.
+    pub fn reseed_every<F: Fn() -> u64 + 'static>(mut self, iterations: usize, seed: F) -> Self {
+        self.reseed = Some(Reseed::new(iterations, seed));
+        self
+    }
+}
+
+impl<Solution: Clone> ImprovingHeuristic<Solution> for IteratedLocalSearch<Solution> {
+    /// Accept iff the candidate is better than the incumbent.
+    fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool
+    where
+        Solution: Evaluate,
+    {
+        is_improvement(
+            candidate.evaluate(),
+            incumbent.evaluate(),
+            self.objective_epsilon,
+        )
+    }
+
+    /// Perturb the incumbent, then descend to a local optimum.
+    fn propose_candidate(&self, incumbent: Solution) -> Solution
+    where
+        Solution: Evaluate,
+    {
+        if let Some(reseed) = &self.reseed {
+            reseed.tick(&self.rng);
+        }
+        let mut current = self.perturb(incumbent);
+        loop {
+            let neighbor = match self.max_neighbors_per_step {
+                Some(max_neighbors) => try_best_neighbor(
+                    self.local_search
+                        .construct_neighborhood(current.clone())
+                        .take(max_neighbors),
+                    self.objective_epsilon,
+                ),
+                None => self
+                    .local_search
+                    .try_find_best_neighbor_epsilon(current.clone(), self.objective_epsilon),
+            };
+            // an empty neighborhood is a local optimum as well
+            match neighbor {
+                Some(neighbor)
+                    if is_improvement(
+                        neighbor.evaluate(),
+                        current.evaluate(),
+                        self.objective_epsilon,
+                    ) =>
+                {
+                    current = neighbor
+                }
+                _ => return current,
+            }
+        }
+    }
+
+    /// Test whether the termination criteria are fulfilled.
+    fn should_terminate(&self, incumbent: &Solution) -> bool {
+        self.terminator.terminate(incumbent)
+    }
+
+    /// Return the progress of the termination criteria.
+    fn progress(&self) -> Option<f32> {
+        self.terminator.progress()
+    }
+
+    /// Return the seed of the source of randomness, if seeded by the builder.
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Apply the initial guard, if specified.
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        crate::guard_initial(&self.initial_guard, initial)
+    }
+
+    /// Return the margin by which a candidate must be better to count as an improvement.
+    fn objective_epsilon(&self) -> f32 {
+        self.objective_epsilon
+    }
+
+    /// Pass the new best solution to the registered hook, if any.
+    fn callback_new_best(&self, best: &Solution) {
+        if let Some(hook) = &self.on_new_best {
+            hook(best);
+        }
+    }
+
+    /// Reset the perturbation strength, and notify the termination criteria.
+    fn callback_candidate_accepted(&self, _candidate: &Solution, _incumbent: &Solution) {
+        self.terminator.feedback(ProposalEvaluation::Accept);
+        if self.adaptive_perturbation.is_some() {
+            self.strength.replace(self.perturbation_strength);
+        }
+    }
+
+    /// Grow the perturbation strength, if adaptive, and notify the termination criteria.
+    fn callback_candidate_rejected(&self, _candidate: &Solution, _incumbent: &Solution) {
+        self.terminator.feedback(ProposalEvaluation::Reject);
+        if let Some(adaptive) = &self.adaptive_perturbation {
+            let strength = *self.strength.borrow() + adaptive.increment;
+            self.strength.replace(strength.min(adaptive.strength_max));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use rand::SeedableRng;
+
+    use crate::{
+        algorithms::ils::IteratedLocalSearch, termination::IterationTerminator, test::*,
+        ImprovingHeuristic,
+    };
+
+    #[test]
+    fn ils_perturbation_strength() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let count = Rc::new(Cell::new(0));
+        let ils = IteratedLocalSearch::builder()
+            .perturbation(CountingShake::new(count.clone()))
+            .local_search(NeighborsUpUntilN::new(&numbers, 1))
+            .perturbation_strength(3)
+            .terminator(IterationTerminator::new(4))
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+
+        let solution = ils.optimize(Number::new(0, numbers[0]));
+        assert_eq!(solution.index(), 2);
+        assert_eq!(count.get(), 4 * 3);
+    }
+
+    #[test]
+    fn ils_adaptive_perturbation_grows() {
+        let numbers = vec![1., 1., 1., 1.];
+        let count = Rc::new(Cell::new(0));
+        let ils = IteratedLocalSearch::builder()
+            .perturbation(CountingShake::new(count.clone()))
+            .local_search(NeighborsUpUntilN::new(&numbers, 1))
+            .perturbation_strength(1)
+            .adaptive_perturbation(1, 3)
+            .terminator(IterationTerminator::new(4))
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+
+        // on a flat landscape no iteration improves, so the strength grows 1, 2, 3, 3
+        ils.optimize(Number::new(0, numbers[0]));
+        assert_eq!(count.get(), 1 + 2 + 3 + 3);
+    }
+
+    #[test]
+    fn ils_adaptive_perturbation_resets() {
+        let numbers = vec![1., 0.];
+        let ils = IteratedLocalSearch::builder()
+            .perturbation(NeighborSwap::new(&numbers))
+            .local_search(NeighborsUpUntilN::new(&numbers, 1))
+            .perturbation_strength(2)
+            .adaptive_perturbation(2, 10)
+            .terminator(IterationTerminator::new(1))
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+
+        let worse = Number::new(0, numbers[0]);
+        let better = Number::new(1, numbers[1]);
+        ils.callback_candidate_rejected(&worse, &better);
+        ils.callback_candidate_rejected(&worse, &better);
+        assert_eq!(ils.perturbation_strength(), 6);
+        ils.callback_candidate_accepted(&better, &worse);
+        assert_eq!(ils.perturbation_strength(), 2);
+    }
+}
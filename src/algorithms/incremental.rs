@@ -0,0 +1,306 @@
+//! _local search_ on solutions which are modified in place
+use std::cell::RefCell;
+
+use rand::Rng;
+
+use crate::{
+    acceptance::{AcceptanceCriterion, BetterOnly},
+    default_rng,
+    diagnostics::MoveLogger,
+    is_improvement,
+    termination::TerminationCriteria,
+    throttle_callback, Callback, ImprovementThreshold, IncrementalSolution, MoveOperator,
+    ProposalEvaluation,
+};
+
+/// Local search which applies a random move every iteration, and undoes it if the [AcceptanceCriterion] rejects it.
+///
+/// The incumbent is never cloned to propose a candidate. Only new best solutions are cloned.
+pub struct IncrementalLocalSearch<Solution: IncrementalSolution> {
+    operators: Vec<Box<dyn MoveOperator<Solution = Solution>>>,
+    acceptance: Box<dyn AcceptanceCriterion>,
+    terminator: Box<dyn TerminationCriteria<Solution>>,
+    rng: RefCell<Box<dyn rand::RngCore>>,
+    on_new_best: Option<Callback<Solution>>,
+    move_log: Option<MoveLogger<Solution::Move>>,
+}
+
+/// Builder design pattern for [IncrementalLocalSearch].
+pub struct IncrementalLocalSearchBuilder<Solution: IncrementalSolution> {
+    operators: Vec<Box<dyn MoveOperator<Solution = Solution>>>,
+    acceptance: Option<Box<dyn AcceptanceCriterion>>,
+    terminator: Option<Box<dyn TerminationCriteria<Solution>>>,
+    rng: Option<Box<dyn rand::RngCore>>,
+    on_new_best: Option<Callback<Solution>>,
+    move_log: Option<MoveLogger<Solution::Move>>,
+}
+
+impl<Solution: IncrementalSolution> IncrementalLocalSearch<Solution> {
+    pub fn builder() -> IncrementalLocalSearchBuilder<Solution> {
+        IncrementalLocalSearchBuilder {
+            operators: vec![],
+            acceptance: None,
+            terminator: None,
+            rng: None,
+            on_new_best: None,
+            move_log: None,
+        }
+    }
+
+    /// Improve ```solution``` in place until termination, and return the best solution ever found.
+    ///
+    /// Every iteration, an operator is drawn uniformly at random, and its move is applied to the incumbent.
+    pub fn optimize(self, mut solution: Solution) -> Solution
+    where
+        Solution: Clone,
+    {
+        let mut best_solution = solution.clone();
+        let mut rng = self.rng.borrow_mut();
+
+        loop {
+            let index = rng.gen_range(0..self.operators.len());
+            let m = self.operators[index].random_move(&solution, rng.as_mut());
+            let objective_incumbent = solution.evaluate();
+            solution.apply_move(&m);
+            let objective_candidate = solution.evaluate();
+            self.acceptance.step();
+
+            if is_improvement(objective_candidate, best_solution.evaluate(), 0.) {
+                best_solution = solution.clone();
+                if let Some(hook) = &self.on_new_best {
+                    hook(&best_solution);
+                }
+            }
+
+            let accepted =
+                self.acceptance
+                    .accept(objective_candidate, objective_incumbent, rng.as_mut());
+            if accepted {
+                self.terminator.feedback(ProposalEvaluation::Accept);
+            } else {
+                solution.undo_move(&m);
+                self.terminator.feedback(ProposalEvaluation::Reject);
+            }
+            if let Some(log) = &self.move_log {
+                log.record(index, m, accepted);
+            }
+
+            if self.terminator.terminate(&solution) {
+                break;
+            }
+        }
+        best_solution
+    }
+}
+
+impl<Solution: IncrementalSolution> IncrementalLocalSearchBuilder<Solution> {
+    /// Build the configured incremental local search heuristic
+    pub fn build(self) -> IncrementalLocalSearch<Solution> {
+        assert!(!self.operators.is_empty(), "No operator specified");
+        IncrementalLocalSearch {
+            operators: self.operators,
+            acceptance: self.acceptance.unwrap_or(Box::new(BetterOnly)),
+            terminator: self.terminator.expect("No termination criteria specified"),
+            rng: RefCell::new(
+                self.rng
+                    .unwrap_or_else(|| default_rng("IncrementalLocalSearchBuilder")),
+            ),
+            on_new_best: self.on_new_best,
+            move_log: self.move_log,
+        }
+    }
+
+    /// Add an operator
+    pub fn operator<T: MoveOperator<Solution = Solution> + 'static>(mut self, operator: T) -> Self {
+        self.operators.push(Box::new(operator));
+        self
+    }
+
+    /// Set acceptance criterion (default: [BetterOnly])
+    pub fn acceptance<T: AcceptanceCriterion + 'static>(mut self, acceptance: T) -> Self {
+        self.acceptance = Some(Box::new(acceptance));
+        self
+    }
+
+    /// Set termination criteria
+    pub fn terminator<T: TerminationCriteria<Solution> + 'static>(mut self, terminator: T) -> Self {
+        self.terminator = Some(Box::new(terminator));
+        self
+    }
+
+    /// Set source of randomness (default: seeded with [crate::DEFAULT_SEED])
+    pub fn rng<T: rand::RngCore + 'static>(mut self, rng: T) -> Self {
+        self.rng = Some(Box::new(rng));
+        self
+    }
+
+    /// Register a hook which receives every new best solution, e.g. to persist it
+    pub fn on_new_best<F: Fn(&Solution) + 'static>(mut self, hook: F) -> Self {
+        self.on_new_best = Some(Box::new(hook));
+        self
+    }
+
+    /// Record the operator, move, and acceptance of every iteration in ```log```, e.g. to replay the run with
+    /// [crate::diagnostics::replay]. Pass a clone, and read the original after the run.
+    pub fn move_log(mut self, log: MoveLogger<Solution::Move>) -> Self {
+        self.move_log = Some(log);
+        self
+    }
+
+    /// Register a hook which only receives the new best solutions that improve on the last reported one by more than
+    /// ```threshold```, e.g. to keep logs meaningful on smooth problems. The search itself still tracks every improvement.
+    pub fn on_new_best_above<F: Fn(&Solution) + 'static>(
+        mut self,
+        threshold: ImprovementThreshold,
+        hook: F,
+    ) -> Self {
+        self.on_new_best = Some(throttle_callback(threshold, hook));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    #[cfg(feature = "serde")]
+    use crate::diagnostics::{replay, MoveRecord};
+    use crate::{
+        acceptance::ThresholdAccepting, algorithms::incremental::IncrementalLocalSearch,
+        diagnostics::MoveLogger, termination::IterationTerminator, Evaluate, IncrementalSolution,
+        MoveOperator,
+    };
+
+    /// Squared distance of integers to their targets, with the objective maintained incrementally.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Targets {
+        values: Vec<i32>,
+        targets: Vec<i32>,
+        objective: f32,
+    }
+
+    /// Add ```delta``` to the value at ```index```.
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    struct Step {
+        index: usize,
+        delta: i32,
+    }
+
+    struct RandomStep;
+
+    impl Targets {
+        fn new(values: Vec<i32>, targets: Vec<i32>) -> Self {
+            let mut solution = Self {
+                values,
+                targets,
+                objective: 0.,
+            };
+            solution.objective = solution.recompute();
+            solution
+        }
+
+        fn recompute(&self) -> f32 {
+            self.values
+                .iter()
+                .zip(&self.targets)
+                .map(|(value, target)| ((value - target) as f32).powi(2))
+                .sum()
+        }
+
+        fn add(&mut self, index: usize, delta: i32) {
+            let before = ((self.values[index] - self.targets[index]) as f32).powi(2);
+            self.values[index] += delta;
+            let after = ((self.values[index] - self.targets[index]) as f32).powi(2);
+            self.objective += after - before;
+        }
+    }
+
+    impl Evaluate for Targets {
+        fn evaluate(&self) -> f32 {
+            self.objective
+        }
+    }
+
+    impl IncrementalSolution for Targets {
+        type Move = Step;
+
+        fn apply_move(&mut self, m: &Step) {
+            self.add(m.index, m.delta);
+        }
+
+        fn undo_move(&mut self, m: &Step) {
+            self.add(m.index, -m.delta);
+        }
+    }
+
+    impl MoveOperator for RandomStep {
+        type Solution = Targets;
+
+        fn random_move(&self, solution: &Targets, rng: &mut dyn rand::RngCore) -> Step {
+            Step {
+                index: rng.gen_range(0..solution.values.len()),
+                delta: if rng.gen::<bool>() { 1 } else { -1 },
+            }
+        }
+    }
+
+    #[test]
+    fn apply_then_undo_restores() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let original = Targets::new(vec![3, -2, 7, 0], vec![1, 1, 1, 1]);
+        let mut solution = original.clone();
+        let moves: Vec<Step> = (0..20)
+            .map(|_| RandomStep.random_move(&solution, &mut rng))
+            .collect();
+        for m in &moves {
+            solution.apply_move(m);
+        }
+        assert_ne!(solution, original);
+        for m in moves.iter().rev() {
+            solution.undo_move(m);
+        }
+        assert_eq!(solution, original);
+        assert_eq!(solution.evaluate(), original.evaluate());
+    }
+
+    #[test]
+    fn incremental_local_search() {
+        let initial = Targets::new(vec![3, -2, 7, 0], vec![1, 1, 1, 1]);
+        let heuristic = IncrementalLocalSearch::builder()
+            .operator(RandomStep)
+            .acceptance(ThresholdAccepting::new(2., 0.05))
+            .terminator(IterationTerminator::new(500))
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+        let best = heuristic.optimize(initial);
+        assert_eq!(best.evaluate(), 0.);
+        assert_eq!(best.evaluate(), best.recompute());
+        assert_eq!(best.values, vec![1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn replay_move_log() {
+        let initial = Targets::new(vec![3, -2, 7, 0], vec![1, 1, 1, 1]);
+        let log = MoveLogger::new();
+        // only improvements are accepted, hence the final incumbent is the best solution
+        let best = IncrementalLocalSearch::builder()
+            .operator(RandomStep)
+            .terminator(IterationTerminator::new(30))
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .move_log(log.clone())
+            .build()
+            .optimize(initial.clone());
+
+        assert_eq!(log.len(), 30);
+        assert!(log.records().iter().any(|record| !record.accepted));
+        assert_eq!(log.replay(initial.clone()), best);
+
+        #[cfg(feature = "serde")]
+        {
+            let serialized = serde_json::to_string(&log.records()).unwrap();
+            let records: Vec<MoveRecord<Step>> = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(replay(initial, &records), best);
+        }
+    }
+}
@@ -1,16 +1,140 @@
 //! _large neighborhood search_
 use std::cell::RefCell;
 
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
 use crate::{
-    selectors::OperatorSelector, termination::TerminationCriteria, Evaluate, ImprovingHeuristic,
+    acceptance::{AcceptanceCriterion, BetterOnly},
+    is_improvement,
+    selectors::OperatorSelector,
+    stream_rng,
+    termination::{IterationTerminator, TerminationCriteria},
+    throttle_callback, warn_default_seed, Callback, Constrained, Evaluate, FeasibilityRetry,
+    ImprovementThreshold, ImprovingHeuristic, InitialGuard, OptimizeError, ProposalEvaluation,
+    DEFAULT_SEED,
 };
 
 /// Large Neighborhood Search implementation.
+///
+/// Destroyers and repairers draw from separate random streams, both derived from the specified RNG,
+/// such that the randomness consumed by one does not affect the other.
+///
+/// Both selectors receive feedback on every proposal: [ProposalEvaluation::ImprovedBest] for a new best solution,
+/// and otherwise whether the repaired solution was accepted, or, see [LNSBuilder::feedback_relative_to_pre_destroy],
+/// whether it improved on the solution before it was destroyed.
 pub struct LargeNeighborhoodSearch<Solution> {
     selector_destroyer: Box<dyn OperatorSelector<Solution>>,
     selector_repairer: Box<dyn OperatorSelector<Solution>>,
     terminator: Box<dyn TerminationCriteria<Solution>>,
-    rng: RefCell<Box<dyn rand::RngCore>>,
+    rng_destroy: RefCell<Box<dyn rand::RngCore>>,
+    rng_repair: RefCell<Box<dyn rand::RngCore>>,
+    destroy_size: Option<DestroySize>,
+    acceptance: Box<dyn AcceptanceCriterion>,
+    rng_acceptance: RefCell<Box<dyn rand::RngCore>>,
+    initial_guard: Option<InitialGuard<Solution>>,
+    on_new_best: Option<Callback<Solution>>,
+    objective_epsilon: f32,
+    feedback_pre_destroy: bool,
+    objectives_proposal: RefCell<Option<(f32, f32)>>,
+    improved_best: RefCell<bool>,
+    feasibility_retry: Option<FeasibilityRetry<Solution>>,
+    seed: Option<u64>,
+}
+
+/// Draws the number of elements to destroy every iteration, from the range ```min..=max```.
+///
+/// In adaptive mode, every size carries a weight which is raised when a candidate destroyed with that size was
+/// accepted, and lowered otherwise. Sizes are drawn proportionally to their weights, which never drop below
+/// ```0.05``` such that every size keeps being explored.
+pub struct DestroySize {
+    min: usize,
+    max: usize,
+    decay: Option<f32>,
+    weights: RefCell<Vec<f32>>,
+    last: RefCell<Option<usize>>,
+}
+
+impl DestroySize {
+    /// Draw uniformly from ```min..=max```.
+    pub fn uniform(min: usize, max: usize) -> Self {
+        Self::new(min, max, None)
+    }
+
+    /// Draw from ```min..=max```, biased toward sizes which produced accepted candidates.
+    pub fn adaptive(min: usize, max: usize, decay: f32) -> Self {
+        Self::new(min, max, Some(decay))
+    }
+
+    fn new(min: usize, max: usize, decay: Option<f32>) -> Self {
+        assert!(min <= max, "empty range of destroy sizes");
+        Self {
+            min,
+            max,
+            decay,
+            weights: RefCell::new(vec![1.; max - min + 1]),
+            last: RefCell::new(None),
+        }
+    }
+
+    /// Draw the next destroy size.
+    pub fn draw(&self, rng: &mut dyn rand::RngCore) -> usize {
+        let weights = self.weights.borrow();
+        let r = rng.gen::<f32>() * weights.iter().sum::<f32>();
+        let mut sum = 0.;
+        let mut index = weights.len() - 1;
+        for (i, weight) in weights.iter().enumerate() {
+            sum += weight;
+            if r <= sum {
+                index = i;
+                break;
+            }
+        }
+        self.last.replace(Some(index));
+        self.min + index
+    }
+
+    /// Give feedback on the last drawn size. Only has an effect in adaptive mode.
+    pub fn feedback(&self, status: ProposalEvaluation) {
+        if let (Some(decay), Some(index)) = (self.decay, *self.last.borrow()) {
+            let reward = match status {
+                ProposalEvaluation::Reject => 0.,
+                _ => 1.,
+            };
+            let mut weights = self.weights.borrow_mut();
+            weights[index] = ((1. - decay) * weights[index] + decay * reward).max(0.05);
+        }
+    }
+
+    /// Return the current weights of the sizes ```min..=max```.
+    pub fn weights(&self) -> Vec<f32> {
+        self.weights.borrow().clone()
+    }
+
+    /// Return the range of sizes.
+    pub fn range(&self) -> (usize, usize) {
+        (self.min, self.max)
+    }
+}
+
+/// Scalar hyperparameters of [LargeNeighborhoodSearch], e.g. to store experiments in a config file.
+///
+/// Selectors cannot be serialized, and are passed to [LargeNeighborhoodSearch::from_config] separately.
+/// The operator identifiers merely record which destroyers and repairers were used.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LnsConfig {
+    /// Maximum number of iterations
+    pub iterations: usize,
+    /// Margin by which candidates must be better to count as improvements
+    pub objective_epsilon: f32,
+    /// Range of destroy sizes drawn uniformly every iteration, if any
+    pub destroy_size: Option<(usize, usize)>,
+    /// Seed of the random number generator
+    pub seed: u64,
+    /// Identifiers of the destroy operators
+    pub destroyers: Vec<String>,
+    /// Identifiers of the repair operators
+    pub repairers: Vec<String>,
 }
 
 /// Builder design pattern for [LargeNeighborhoodSearch].
@@ -19,6 +143,14 @@ pub struct LNSBuilder<Solution> {
     selector_destroyer: Option<Box<dyn OperatorSelector<Solution>>>,
     selector_repairer: Option<Box<dyn OperatorSelector<Solution>>>,
     rng: Option<Box<dyn rand::RngCore>>,
+    destroy_size: Option<DestroySize>,
+    acceptance: Option<Box<dyn AcceptanceCriterion>>,
+    initial_guard: Option<InitialGuard<Solution>>,
+    on_new_best: Option<Callback<Solution>>,
+    objective_epsilon: f32,
+    feedback_pre_destroy: bool,
+    feasibility_retry: Option<FeasibilityRetry<Solution>>,
+    seed: Option<u64>,
 }
 
 impl<Solution> LargeNeighborhoodSearch<Solution> {
@@ -28,13 +160,82 @@ impl<Solution> LargeNeighborhoodSearch<Solution> {
             selector_destroyer: None,
             selector_repairer: None,
             rng: None,
+            destroy_size: None,
+            acceptance: None,
+            initial_guard: None,
+            on_new_best: None,
+            objective_epsilon: 0.,
+            feedback_pre_destroy: false,
+            feasibility_retry: None,
+            seed: None,
+        }
+    }
+
+    /// Construct the heuristic from its hyperparameters, and the operators offered by the selectors.
+    pub fn from_config<D, R>(
+        config: &LnsConfig,
+        selector_destroyer: D,
+        selector_repairer: R,
+    ) -> Self
+    where
+        Solution: 'static,
+        D: OperatorSelector<Solution> + 'static,
+        R: OperatorSelector<Solution> + 'static,
+    {
+        let mut builder = Self::builder()
+            .selector_destroyer(selector_destroyer)
+            .selector_repairer(selector_repairer)
+            .terminator(Box::new(IterationTerminator::new(config.iterations)))
+            .seed(config.seed)
+            .objective_epsilon(config.objective_epsilon);
+        if let Some((min, max)) = config.destroy_size {
+            builder = builder.destroy_size(DestroySize::uniform(min, max));
         }
+        builder.build()
+    }
+
+    /// Return the indices of the destroyer and repairer selected in the last iteration.
+    pub fn last_selected(&self) -> (Option<usize>, Option<usize>) {
+        (
+            self.selector_destroyer.last_selected(),
+            self.selector_repairer.last_selected(),
+        )
+    }
+
+    /// Give both selectors feedback on the last proposal, of which the repaired solution was ```accepted```, or not.
+    fn feedback_selectors(&self, accepted: bool) {
+        let status = if self.improved_best.replace(false) {
+            ProposalEvaluation::ImprovedBest
+        } else if self.feedback_pre_destroy {
+            match *self.objectives_proposal.borrow() {
+                Some((pre_destroy, repaired))
+                    if is_improvement(repaired, pre_destroy, self.objective_epsilon) =>
+                {
+                    ProposalEvaluation::Accept
+                }
+                _ => ProposalEvaluation::Reject,
+            }
+        } else if accepted {
+            ProposalEvaluation::Accept
+        } else {
+            ProposalEvaluation::Reject
+        };
+        self.selector_destroyer.feedback(status);
+        self.selector_repairer.feedback(status);
     }
 }
 
 impl<Solution> LNSBuilder<Solution> {
     /// Build the configured Large Neighborhood Search heuristic
-    pub fn build(self) -> LargeNeighborhoodSearch<Solution> {
+    pub fn build(mut self) -> LargeNeighborhoodSearch<Solution> {
+        if self.rng.is_none() {
+            warn_default_seed("LNSBuilder");
+            self = self.seed(DEFAULT_SEED);
+        }
+        let seed = self
+            .rng
+            .expect("source of randomness was defaulted")
+            .next_u64();
         LargeNeighborhoodSearch {
             selector_destroyer: self
                 .selector_destroyer
@@ -43,7 +244,19 @@ impl<Solution> LNSBuilder<Solution> {
                 .selector_repairer
                 .expect("No repairer selector specified"),
             terminator: self.terminator.expect("No termination criteria specified"),
-            rng: RefCell::new(self.rng.expect("No RNG source specified")),
+            rng_destroy: RefCell::new(Box::new(stream_rng(seed, 0))),
+            rng_repair: RefCell::new(Box::new(stream_rng(seed, 1))),
+            destroy_size: self.destroy_size,
+            acceptance: self.acceptance.unwrap_or(Box::new(BetterOnly)),
+            rng_acceptance: RefCell::new(Box::new(stream_rng(seed, 2))),
+            initial_guard: self.initial_guard,
+            on_new_best: self.on_new_best,
+            objective_epsilon: self.objective_epsilon,
+            feedback_pre_destroy: self.feedback_pre_destroy,
+            objectives_proposal: RefCell::new(None),
+            improved_best: RefCell::new(false),
+            feasibility_retry: self.feasibility_retry,
+            seed: self.seed,
         }
     }
 
@@ -71,24 +284,112 @@ impl<Solution> LNSBuilder<Solution> {
         self
     }
 
-    /// Set source of randomness
+    /// Set source of randomness (default: seeded with [crate::DEFAULT_SEED])
     pub fn rng<T: rand::RngCore + 'static>(mut self, rng: T) -> Self {
         self.rng = Some(Box::new(rng));
+        self.seed = None;
+        self
+    }
+
+    /// Seed a [rand::rngs::StdRng] as source of randomness, which is reported by [crate::ImprovingHeuristic::seed],
+    /// e.g. in the [crate::Outcome] of [crate::ImprovingHeuristic::optimize_timed], such that a run can be replayed.
+    ///
+    /// Selectors with their own source of randomness, e.g. [crate::selectors::RandomSelector], are seeded separately.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Some(Box::new(StdRng::seed_from_u64(seed)));
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Draw the number of destroyed elements every iteration, and pass it to [crate::Operator::destroy] of the destroyer.
+    ///
+    /// Without it, the destroyer's ```shake``` is applied once.
+    pub fn destroy_size(mut self, destroy_size: DestroySize) -> Self {
+        self.destroy_size = Some(destroy_size);
+        self
+    }
+
+    /// Set acceptance criterion (default: [BetterOnly]), e.g. [crate::acceptance::Metropolis] to occasionally
+    /// accept worse repaired solutions
+    pub fn acceptance<T: AcceptanceCriterion + 'static>(mut self, acceptance: T) -> Self {
+        self.acceptance = Some(Box::new(acceptance));
+        self
+    }
+
+    /// Set a guard which validates, and possibly repairs, the initial solution
+    pub fn initial_guard<F: Fn(Solution) -> Result<Solution, String> + 'static>(
+        mut self,
+        guard: F,
+    ) -> Self {
+        self.initial_guard = Some(Box::new(guard));
+        self
+    }
+
+    /// Register a hook which receives every new best solution, e.g. to persist it
+    pub fn on_new_best<F: Fn(&Solution) + 'static>(mut self, hook: F) -> Self {
+        self.on_new_best = Some(Box::new(hook));
+        self
+    }
+
+    /// Register a hook which only receives the new best solutions that improve on the last reported one by more than
+    /// ```threshold```, e.g. to keep logs meaningful on smooth problems. The search itself still tracks every improvement.
+    pub fn on_new_best_above<F: Fn(&Solution) + 'static>(
+        mut self,
+        threshold: ImprovementThreshold,
+        hook: F,
+    ) -> Self
+    where
+        Solution: Evaluate,
+    {
+        self.on_new_best = Some(throttle_callback(threshold, hook));
+        self
+    }
+
+    /// Require candidates to be better by more than ```epsilon``` to count as improvements (default: 0)
+    pub fn objective_epsilon(mut self, epsilon: f32) -> Self {
+        self.objective_epsilon = epsilon;
+        self
+    }
+
+    /// Report [ProposalEvaluation::Accept] to the selectors iff the repaired solution improves on the solution before
+    /// it was destroyed, and [ProposalEvaluation::Reject] otherwise, regardless of the acceptance criterion.
+    ///
+    /// The selectors thus learn from the net effect of each destroy and repair pair, rather than from what a lenient
+    /// acceptance criterion lets through.
+    pub fn feedback_relative_to_pre_destroy(mut self) -> Self {
+        self.feedback_pre_destroy = true;
+        self
+    }
+
+    /// Destroy and repair again, up to ```max_retries``` times, while the repaired solution is infeasible. If all
+    /// retries fail, the incumbent itself is proposed.
+    pub fn retry_until_feasible(mut self, max_retries: usize) -> Self
+    where
+        Solution: Constrained + Clone,
+    {
+        self.feasibility_retry = Some(FeasibilityRetry::new(max_retries));
         self
     }
 }
 
 impl<Solution> ImprovingHeuristic<Solution> for LargeNeighborhoodSearch<Solution> {
-    /// Accept a candidate iff it is an improvement.
+    /// Accept according to the acceptance criterion, by default iff the candidate is an improvement.
+    ///
+    /// A candidate within ```objective_epsilon``` of the incumbent is presented to the criterion as equal to it.
     fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool
     where
         Solution: Evaluate,
     {
-        if candidate.evaluate() < incumbent.evaluate() {
-            true
-        } else {
-            false
+        let objective_incumbent = incumbent.evaluate();
+        let mut objective_candidate = candidate.evaluate();
+        if (objective_candidate - objective_incumbent).abs() <= self.objective_epsilon {
+            objective_candidate = objective_incumbent;
         }
+        self.acceptance.accept(
+            objective_candidate,
+            objective_incumbent,
+            self.rng_acceptance.borrow_mut().as_mut(),
+        )
     }
 
     /// Select a destroy and repair method, then return the destroyed and repaired ```incumbent```.
@@ -98,15 +399,387 @@ impl<Solution> ImprovingHeuristic<Solution> for LargeNeighborhoodSearch<Solution
     {
         let destroyer = self.selector_destroyer.select(&incumbent);
         let repairer = self.selector_repairer.select(&incumbent);
+        let objective_pre_destroy = incumbent.evaluate();
 
-        let destroyed = destroyer.shake(incumbent, self.rng.borrow_mut().as_mut());
-        let repaired = repairer.shake(destroyed, self.rng.borrow_mut().as_mut());
+        let mut rng_destroy = self.rng_destroy.borrow_mut();
+        let mut rng_repair = self.rng_repair.borrow_mut();
+        let mut destroy_and_repair = |solution| {
+            let destroyed = match &self.destroy_size {
+                Some(destroy_size) => {
+                    let size = destroy_size.draw(rng_destroy.as_mut());
+                    destroyer.destroy(solution, size, rng_destroy.as_mut())
+                }
+                None => destroyer.shake(solution, rng_destroy.as_mut()),
+            };
+            repairer.shake(destroyed, rng_repair.as_mut())
+        };
+        let repaired = match &self.feasibility_retry {
+            Some(retry) => retry.shake(incumbent, &mut destroy_and_repair),
+            None => destroy_and_repair(incumbent),
+        };
+        self.acceptance.step();
+        self.objectives_proposal
+            .replace(Some((objective_pre_destroy, repaired.evaluate())));
 
         repaired
     }
 
     /// Terminate iff the termination criteria are satisfied.
     fn should_terminate(&self, incumbent: &Solution) -> bool {
-        self.terminator.terminate(&incumbent)
+        self.terminator.terminate(incumbent)
+    }
+
+    /// Return the progress of the termination criteria.
+    fn progress(&self) -> Option<f32> {
+        self.terminator.progress()
+    }
+
+    /// Return the seed of the source of randomness, if seeded by the builder.
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Apply the initial guard, if specified.
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        crate::guard_initial(&self.initial_guard, initial)
+    }
+
+    /// Return the margin by which a candidate must be better to count as an improvement.
+    fn objective_epsilon(&self) -> f32 {
+        self.objective_epsilon
+    }
+
+    /// Remember to report the new best solution to the selectors.
+    fn callback_candidate_improved_best(&self, _candidate: &Solution, _incumbent: &Solution) {
+        self.improved_best.replace(true);
+    }
+
+    /// Pass the new best solution to the registered hook, if any.
+    fn callback_new_best(&self, best: &Solution) {
+        if let Some(hook) = &self.on_new_best {
+            hook(best);
+        }
+    }
+
+    /// Notify the termination criteria, the destroy size, and the selectors.
+    fn callback_candidate_accepted(&self, _candidate: &Solution, _incumbent: &Solution) {
+        self.terminator.feedback(ProposalEvaluation::Accept);
+        if let Some(destroy_size) = &self.destroy_size {
+            destroy_size.feedback(ProposalEvaluation::Accept);
+        }
+        self.feedback_selectors(true);
+    }
+
+    /// Notify the termination criteria, the destroy size, and the selectors.
+    fn callback_candidate_rejected(&self, _candidate: &Solution, _incumbent: &Solution) {
+        self.terminator.feedback(ProposalEvaluation::Reject);
+        if let Some(destroy_size) = &self.destroy_size {
+            destroy_size.feedback(ProposalEvaluation::Reject);
+        }
+        self.feedback_selectors(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use assert_approx_eq::assert_approx_eq;
+    use rand::{Rng, SeedableRng};
+
+    use crate::{
+        acceptance::Metropolis,
+        algorithms::{
+            lns::{DestroySize, LargeNeighborhoodSearch},
+            sa::FactorSchedule,
+        },
+        selectors::{AdaptiveSelector, OperatorSelector, SequentialSelector},
+        termination::Terminator,
+        test::{HalfInfeasible, NeighborSwap, Number},
+        Constrained, Evaluate, ImprovingHeuristic, Operator, ProposalEvaluation,
+    };
+
+    #[test]
+    fn lns_retry_until_feasible() {
+        let lns = LargeNeighborhoodSearch::builder()
+            .selector_destroyer(SequentialSelector::new().option(HalfInfeasible))
+            .selector_repairer(SequentialSelector::new().option(RecordDraws {
+                draws: 0,
+                record: Rc::new(RefCell::new(vec![])),
+            }))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .retry_until_feasible(2)
+            .build();
+
+        let incumbent = Number::new(0, 5.);
+        for _ in 0..100 {
+            let candidate = lns.propose_candidate(incumbent.clone());
+            assert!(candidate.is_feasible() || candidate.index() == incumbent.index());
+        }
+    }
+
+    /// Record ```draws``` random numbers per shake, leaving the solution untouched.
+    struct RecordDraws {
+        draws: usize,
+        record: Rc<RefCell<Vec<u32>>>,
+    }
+
+    impl Operator for RecordDraws {
+        type Solution = Number;
+
+        fn shake(&self, solution: Number, rng: &mut dyn rand::RngCore) -> Number {
+            for _ in 0..self.draws {
+                self.record.borrow_mut().push(rng.gen());
+            }
+            solution
+        }
+    }
+
+    fn destroyer_stream(draws_repair: usize) -> Vec<u32> {
+        let record = Rc::new(RefCell::new(vec![]));
+        let lns = LargeNeighborhoodSearch::builder()
+            .selector_destroyer(SequentialSelector::new().option(RecordDraws {
+                draws: 1,
+                record: record.clone(),
+            }))
+            .selector_repairer(SequentialSelector::new().option(RecordDraws {
+                draws: draws_repair,
+                record: Rc::new(RefCell::new(vec![])),
+            }))
+            .terminator(Terminator::builder().iterations(10).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+        lns.optimize(Number::new(0, 0.));
+        let stream = record.borrow().clone();
+        stream
+    }
+
+    #[test]
+    fn lns_independent_streams() {
+        let stream = destroyer_stream(1);
+        assert_eq!(stream.len(), 10);
+        assert_eq!(destroyer_stream(3), stream);
+    }
+
+    #[test]
+    fn destroy_size_uniform_in_range() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let destroy_size = DestroySize::uniform(2, 5);
+        let sizes: Vec<usize> = (0..1000).map(|_| destroy_size.draw(&mut rng)).collect();
+        assert!(sizes.iter().all(|size| (2..=5).contains(size)));
+        assert!((2..=5).all(|size| sizes.contains(&size)));
+    }
+
+    #[test]
+    fn destroy_size_adaptive_bias() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let destroy_size = DestroySize::adaptive(1, 4, 0.2);
+        let mut count_successful = 0;
+        for _ in 0..1000 {
+            let size = destroy_size.draw(&mut rng);
+            if size == 3 {
+                count_successful += 1;
+                destroy_size.feedback(ProposalEvaluation::Accept);
+            } else {
+                destroy_size.feedback(ProposalEvaluation::Reject);
+            }
+        }
+        // uniform drawing would select the successful size about 250 times
+        assert!(count_successful > 500);
+        let weights = destroy_size.weights();
+        assert!(weights[2] > weights[0] && weights[2] > weights[1] && weights[2] > weights[3]);
+    }
+
+    #[test]
+    fn lns_metropolis_accepts_worse() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let build = || {
+            LargeNeighborhoodSearch::builder()
+                .selector_destroyer(SequentialSelector::new().option(NeighborSwap::new(&numbers)))
+                .selector_repairer(SequentialSelector::new().option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(1).build())
+                .rng(rand::rngs::StdRng::seed_from_u64(0))
+        };
+        let greedy = build().build();
+        let hot = build()
+            .acceptance(Metropolis::new(FactorSchedule::new(1e6, 0.)))
+            .build();
+        let cold = build()
+            .acceptance(Metropolis::new(FactorSchedule::new(1e-3, 0.)))
+            .build();
+
+        let incumbent = Number::new(7, numbers[7]);
+        let repaired = Number::new(6, numbers[6]);
+        assert!(!greedy.accept_candidate(&repaired, &incumbent));
+        assert!(hot.accept_candidate(&repaired, &incumbent));
+        assert!(!cold.accept_candidate(&repaired, &incumbent));
+        assert!(cold.accept_candidate(&incumbent, &repaired));
+    }
+
+    /// Move to the next number, such that the proposals are known in advance.
+    struct Step {
+        numbers: Vec<f32>,
+    }
+
+    impl Operator for Step {
+        type Solution = Number;
+
+        fn shake(&self, solution: Number, _rng: &mut dyn rand::RngCore) -> Number {
+            let index = solution.index() + 1;
+            Number::new(index, self.numbers[index])
+        }
+    }
+
+    /// Select the only operator, and record the feedback.
+    struct RecordFeedback {
+        operator: Box<dyn Operator<Solution = Number>>,
+        feedback: Rc<RefCell<Vec<ProposalEvaluation>>>,
+    }
+
+    impl OperatorSelector<Number> for RecordFeedback {
+        fn select(&self, _solution: &dyn crate::Evaluate) -> &dyn Operator<Solution = Number> {
+            self.operator.as_ref()
+        }
+
+        fn feedback(&self, status: ProposalEvaluation) {
+            self.feedback.borrow_mut().push(status);
+        }
+    }
+
+    /// Propose 9, 7, 8, 6, and 4, each from the previous number, starting from 5.
+    fn steps() -> Step {
+        Step {
+            numbers: vec![5., 9., 7., 8., 6., 4.],
+        }
+    }
+
+    fn run_lenient<T: OperatorSelector<Number> + 'static>(
+        selector_destroyer: T,
+        pre_destroy: bool,
+    ) {
+        let mut builder = LargeNeighborhoodSearch::builder()
+            .selector_destroyer(selector_destroyer)
+            .selector_repairer(SequentialSelector::new().option(RecordDraws {
+                draws: 0,
+                record: Rc::new(RefCell::new(vec![])),
+            }))
+            .terminator(Terminator::builder().iterations(5).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            // accept every candidate
+            .acceptance(
+                Metropolis::new(FactorSchedule::new(1., 0.)).minimum_acceptance_probability(1.),
+            );
+        if pre_destroy {
+            builder = builder.feedback_relative_to_pre_destroy();
+        }
+        builder.build().optimize(Number::new(0, 5.));
+    }
+
+    fn feedback_lenient(pre_destroy: bool) -> Vec<ProposalEvaluation> {
+        let feedback = Rc::new(RefCell::new(vec![]));
+        let selector = RecordFeedback {
+            operator: Box::new(steps()),
+            feedback: feedback.clone(),
+        };
+        run_lenient(selector, pre_destroy);
+        let feedback = feedback.borrow().clone();
+        feedback
+    }
+
+    fn weight_lenient(pre_destroy: bool) -> f32 {
+        let selector = Rc::new(
+            AdaptiveSelector::default_weights(0.5, rand::rngs::StdRng::seed_from_u64(0))
+                .operator(steps()),
+        );
+        run_lenient(selector.clone(), pre_destroy);
+        selector.weights()[0]
+    }
+
+    #[test]
+    fn lns_feedback_relative_to_pre_destroy() {
+        use ProposalEvaluation::*;
+        // the proposals are 9, 7, 8, 6, and 4, each from the previous number
+        assert_eq!(
+            feedback_lenient(false),
+            vec![Accept, Accept, Accept, Accept, ImprovedBest]
+        );
+        assert_eq!(
+            feedback_lenient(true),
+            vec![Reject, Accept, Reject, Accept, ImprovedBest]
+        );
+
+        // starting from 1, every feedback halves the distance to its reward of 0, 1, or 3
+        assert_approx_eq!(weight_lenient(false), 2.);
+        assert_approx_eq!(weight_lenient(true), 1.84375);
+    }
+
+    /// Add ```shift``` to the number, such that every proposal is either better or worse.
+    struct Shift(f32);
+
+    impl Operator for Shift {
+        type Solution = Number;
+
+        fn shake(&self, solution: Number, _rng: &mut dyn rand::RngCore) -> Number {
+            Number::new(solution.index(), solution.evaluate() + self.0)
+        }
+    }
+
+    #[test]
+    fn lns_removes_rejected_destroyer() {
+        let destroyer = Rc::new(
+            AdaptiveSelector::default_weights(0.5, rand::rngs::StdRng::seed_from_u64(0))
+                .operator(Shift(-1.))
+                .operator(Shift(1.))
+                .removal(0.5, 2),
+        );
+        let lns = LargeNeighborhoodSearch::builder()
+            .selector_destroyer(destroyer.clone())
+            .selector_repairer(SequentialSelector::new().option(Shift(0.)))
+            .terminator(Terminator::builder().iterations(50).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+
+        // every worsening candidate is rejected, and every improving one is a new best
+        lns.optimize(Number::new(0, 0.));
+        assert!(destroyer.is_active(0));
+        assert!(!destroyer.is_active(1));
+        assert!(destroyer.weights()[0] > 1.);
+    }
+
+    #[test]
+    fn lns_round_robin_until_differentiated() {
+        let destroyer = Rc::new(
+            AdaptiveSelector::default_weights(0.5, rand::rngs::StdRng::seed_from_u64(0))
+                .operator(Shift(1.))
+                .operator(Shift(-10.))
+                .operator(Shift(1.))
+                .deterministic_until_differentiated(),
+        );
+        let lns = LargeNeighborhoodSearch::builder()
+            .selector_destroyer(destroyer.clone())
+            .selector_repairer(SequentialSelector::new().option(Shift(0.)))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            // accept every candidate, such that worsening operators keep their initial weight
+            .acceptance(
+                Metropolis::new(FactorSchedule::new(1., 0.)).minimum_acceptance_probability(1.),
+            )
+            .build();
+
+        let mut state = lns.start(Number::new(0, 0.)).unwrap();
+        let selections: Vec<usize> = (0..60)
+            .map(|_| {
+                lns.step(&mut state);
+                lns.last_selected().0.unwrap()
+            })
+            .collect();
+
+        // the first improvement of the best solution ends the round robin
+        assert_eq!(selections[..2], [0, 1]);
+        let round_robin: Vec<usize> = (2..60).map(|i| i % 3).collect();
+        assert_ne!(selections[2..], round_robin);
+        let count = |index| selections.iter().filter(|&&i| i == index).count();
+        assert!(count(1) > count(0) && count(1) > count(2));
     }
 }
@@ -0,0 +1,420 @@
+//! _local search_ driven by a pluggable acceptance criterion
+use std::cell::RefCell;
+
+use crate::{
+    acceptance::{AcceptanceCriterion, BetterOnly},
+    pause::HeuristicState,
+    selectors::OperatorSelector,
+    termination::TerminationCriteria,
+    throttle_callback, warn_default_seed, Callback, Evaluate, FeasibilityRetry,
+    ImprovementThreshold, ImprovingHeuristic, InitialGuard, OptimizeError, ProposalEvaluation,
+    Reseed, ReturnPolicy, SearchRng, DEFAULT_SEED,
+};
+
+/// Local search which draws a random neighbor every iteration, and accepts it according to an [AcceptanceCriterion].
+///
+/// With [crate::acceptance::Metropolis] acceptance this is _simulated annealing_, while
+/// [crate::acceptance::GreatDeluge], [crate::acceptance::RecordToRecord], and
+/// [crate::acceptance::ThresholdAccepting] give their namesake heuristics.
+pub struct LocalSearch<Solution> {
+    pub(crate) selector: Box<dyn OperatorSelector<Solution>>,
+    pub(crate) acceptance: Box<dyn AcceptanceCriterion>,
+    pub(crate) terminator: Box<dyn TerminationCriteria<Solution>>,
+    pub(crate) rng: RefCell<SearchRng>,
+    pub(crate) initial_guard: Option<InitialGuard<Solution>>,
+    pub(crate) on_new_best: Option<Callback<Solution>>,
+    pub(crate) objective_epsilon: f32,
+    pub(crate) return_policy: ReturnPolicy,
+    pub(crate) reseed: Option<Reseed>,
+    pub(crate) feasibility_retry: Option<FeasibilityRetry<Solution>>,
+    pub(crate) seed: Option<u64>,
+}
+
+/// Builder design pattern for [LocalSearch].
+pub struct LocalSearchBuilder<Solution> {
+    selector: Option<Box<dyn OperatorSelector<Solution>>>,
+    acceptance: Option<Box<dyn AcceptanceCriterion>>,
+    terminator: Option<Box<dyn TerminationCriteria<Solution>>>,
+    rng: Option<SearchRng>,
+    initial_guard: Option<InitialGuard<Solution>>,
+    on_new_best: Option<Callback<Solution>>,
+    objective_epsilon: f32,
+    return_policy: ReturnPolicy,
+    reseed: Option<Reseed>,
+    seed: Option<u64>,
+}
+
+impl<Solution> LocalSearch<Solution> {
+    pub fn builder() -> LocalSearchBuilder<Solution> {
+        LocalSearchBuilder {
+            selector: None,
+            acceptance: None,
+            terminator: None,
+            rng: None,
+            initial_guard: None,
+            on_new_best: None,
+            objective_epsilon: 0.,
+            return_policy: ReturnPolicy::BestEver,
+            reseed: None,
+            seed: None,
+        }
+    }
+
+    /// Return the index of the operator selected in the last iteration.
+    pub fn last_selected(&self) -> Option<usize> {
+        self.selector.last_selected()
+    }
+
+    /// Draw ```count``` random neighbors of ```incumbent```, as a single proposal, i.e. the acceptance criterion is
+    /// advanced once.
+    pub(crate) fn propose_candidates(
+        &self,
+        incumbent: &Solution,
+        count: usize,
+        duplicate: fn(&Solution) -> Solution,
+    ) -> Vec<Solution>
+    where
+        Solution: Evaluate,
+    {
+        if let Some(reseed) = &self.reseed {
+            reseed.tick(&self.rng);
+        }
+        let candidates = (0..count)
+            .map(|_| self.shake(duplicate(incumbent)))
+            .collect();
+        self.acceptance.step();
+        candidates
+    }
+
+    /// Select an operator and draw a random neighbor, retrying while infeasible if so configured.
+    fn shake(&self, incumbent: Solution) -> Solution
+    where
+        Solution: Evaluate,
+    {
+        let operator = self.selector.select(&incumbent);
+        let mut rng = self.rng.borrow_mut();
+        match &self.feasibility_retry {
+            Some(retry) => retry.shake(incumbent, &mut |solution| {
+                operator.shake(solution, &mut *rng)
+            }),
+            None => operator.shake(incumbent, &mut *rng),
+        }
+    }
+}
+
+impl<Solution> LocalSearchBuilder<Solution> {
+    /// Build the configured local search heuristic
+    pub fn build(mut self) -> LocalSearch<Solution> {
+        if self.rng.is_none() {
+            warn_default_seed("LocalSearchBuilder");
+            self = self.seed(DEFAULT_SEED);
+        }
+        LocalSearch {
+            selector: self
+                .selector
+                .expect("No operator selection strategy specified"),
+            acceptance: self.acceptance.unwrap_or(Box::new(BetterOnly)),
+            terminator: self.terminator.expect("No termination criteria specified"),
+            rng: RefCell::new(self.rng.expect("source of randomness was defaulted")),
+            initial_guard: self.initial_guard,
+            on_new_best: self.on_new_best,
+            objective_epsilon: self.objective_epsilon,
+            return_policy: self.return_policy,
+            reseed: self.reseed,
+            feasibility_retry: None,
+            seed: self.seed,
+        }
+    }
+
+    /// Set operator selector
+    pub fn selector<T: OperatorSelector<Solution> + 'static>(mut self, selector: T) -> Self {
+        self.selector = Some(Box::new(selector));
+        self
+    }
+
+    /// Set acceptance criterion (default: [BetterOnly])
+    pub fn acceptance<T: AcceptanceCriterion + 'static>(mut self, acceptance: T) -> Self {
+        self.acceptance = Some(Box::new(acceptance));
+        self
+    }
+
+    /// Set termination criteria
+    pub fn terminator(mut self, criterium: Box<dyn TerminationCriteria<Solution>>) -> Self {
+        self.terminator = Some(criterium);
+        self
+    }
+
+    /// Set source of randomness (default: seeded with [crate::DEFAULT_SEED])
+    pub fn rng<T: rand::RngCore + 'static>(mut self, rng: T) -> Self {
+        self.rng = Some(SearchRng::Custom(Box::new(rng)));
+        self.seed = None;
+        self
+    }
+
+    /// Seed a [rand::rngs::StdRng] as source of randomness, which is reported by [crate::ImprovingHeuristic::seed],
+    /// e.g. in the [crate::Outcome] of [crate::ImprovingHeuristic::optimize_timed], such that a run can be replayed.
+    ///
+    /// Selectors with their own source of randomness, e.g. [crate::selectors::RandomSelector], are seeded separately.
+    /// The position of the source of randomness is captured when the search is paused, see [crate::pause::HeuristicState].
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Some(SearchRng::seeded(seed));
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set a guard which validates, and possibly repairs, the initial solution
+    pub fn initial_guard<F: Fn(Solution) -> Result<Solution, String> + 'static>(
+        mut self,
+        guard: F,
+    ) -> Self {
+        self.initial_guard = Some(Box::new(guard));
+        self
+    }
+
+    /// Register a hook which receives every new best solution, e.g. to persist it
+    pub fn on_new_best<F: Fn(&Solution) + 'static>(mut self, hook: F) -> Self {
+        self.on_new_best = Some(Box::new(hook));
+        self
+    }
+
+    /// Register a hook which only receives the new best solutions that improve on the last reported one by more than
+    /// ```threshold```, e.g. to keep logs meaningful on smooth problems. The search itself still tracks every improvement.
+    pub fn on_new_best_above<F: Fn(&Solution) + 'static>(
+        mut self,
+        threshold: ImprovementThreshold,
+        hook: F,
+    ) -> Self
+    where
+        Solution: Evaluate,
+    {
+        self.on_new_best = Some(throttle_callback(threshold, hook));
+        self
+    }
+
+    /// Require candidates to be better by more than ```epsilon``` to count as improvements (default: 0)
+    pub fn objective_epsilon(mut self, epsilon: f32) -> Self {
+        self.objective_epsilon = epsilon;
+        self
+    }
+
+    /// Set which solution is returned once the search terminates (default: [ReturnPolicy::BestEver])
+    pub fn return_policy(mut self, policy: ReturnPolicy) -> Self {
+        self.return_policy = policy;
+        self
+    }
+
+    /// Reseed the source of randomness from ```seed``` after every ```iterations``` iterations, e.g. with external entropy in very long runs.
+    ///
+    /// The source of randomness is replaced by a [rand::rngs::StdRng]. Unless ```seed``` is deterministic, this breaks reproducibility, by design.
+    pub fn reseed_every<F: Fn() -> u64 + 'static>(mut self, iterations: usize, seed: F) -> Self {
+        self.reseed = Some(Reseed::new(iterations, seed));
+        self
+    }
+}
+
+impl<Solution> ImprovingHeuristic<Solution> for LocalSearch<Solution> {
+    /// Accept according to the acceptance criterion.
+    ///
+    /// A candidate within ```objective_epsilon``` of the incumbent is presented to the criterion as equal to it.
+    fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool
+    where
+        Solution: Evaluate,
+    {
+        let objective_incumbent = incumbent.evaluate();
+        let mut objective_candidate = candidate.evaluate();
+        if (objective_candidate - objective_incumbent).abs() <= self.objective_epsilon {
+            objective_candidate = objective_incumbent;
+        }
+        self.acceptance.accept(
+            objective_candidate,
+            objective_incumbent,
+            &mut *self.rng.borrow_mut(),
+        )
+    }
+
+    /// Select an operator and draw a random neighbor, then advance the acceptance criterion.
+    fn propose_candidate(&self, incumbent: Solution) -> Solution
+    where
+        Solution: Evaluate,
+    {
+        if let Some(reseed) = &self.reseed {
+            reseed.tick(&self.rng);
+        }
+        let candidate = self.shake(incumbent);
+        self.acceptance.step();
+        candidate
+    }
+
+    /// Test whether the termination criteria are fulfilled.
+    fn should_terminate(&self, incumbent: &Solution) -> bool {
+        self.terminator.terminate(incumbent)
+    }
+
+    /// Return the progress of the termination criteria.
+    fn progress(&self) -> Option<f32> {
+        self.terminator.progress()
+    }
+
+    /// Return the seed of the source of randomness, if seeded by the builder.
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Capture the position of the source of randomness, if seeded by the builder.
+    fn pause_state(&self) -> HeuristicState {
+        HeuristicState {
+            rng: self.rng.borrow().state(),
+            temperature: None,
+        }
+    }
+
+    /// Restore the source of randomness, if captured, and continue the termination criteria, the acceptance
+    /// criterion, and the reseeding after ```iterations``` iterations.
+    fn resume_state(&self, state: &HeuristicState, iterations: usize) {
+        if let Some(rng) = &state.rng {
+            self.rng.borrow_mut().restore(rng);
+        }
+        self.terminator.resume(iterations);
+        self.acceptance.resume(iterations);
+        if let Some(reseed) = &self.reseed {
+            reseed.resume(iterations);
+        }
+    }
+
+    /// Apply the initial guard, if specified.
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        crate::guard_initial(&self.initial_guard, initial)
+    }
+
+    /// Return the margin by which a candidate must be better to count as an improvement.
+    fn objective_epsilon(&self) -> f32 {
+        self.objective_epsilon
+    }
+
+    /// Return which solution is returned once the search terminates.
+    fn return_policy(&self) -> ReturnPolicy {
+        self.return_policy
+    }
+
+    /// Pass the new best solution to the registered hook, if any.
+    fn callback_new_best(&self, best: &Solution) {
+        if let Some(hook) = &self.on_new_best {
+            hook(best);
+        }
+    }
+
+    /// Notify the termination criteria.
+    fn callback_candidate_accepted(&self, _candidate: &Solution, _incumbent: &Solution) {
+        self.terminator.feedback(ProposalEvaluation::Accept);
+    }
+
+    /// Notify the termination criteria.
+    fn callback_candidate_rejected(&self, _candidate: &Solution, _incumbent: &Solution) {
+        self.terminator.feedback(ProposalEvaluation::Reject);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use rand::SeedableRng;
+
+    use crate::{
+        acceptance::{GreatDeluge, Metropolis},
+        algorithms::{
+            local_search::LocalSearch,
+            sa::{FactorSchedule, SimulatedAnnealing},
+        },
+        selectors::RandomSelector,
+        termination::Terminator,
+        test::{NeighborSwap, Number},
+        ImprovementThreshold, ImprovingHeuristic,
+    };
+
+    #[test]
+    fn on_new_best_above_threshold() {
+        // every step to the right is a micro-improvement of 0.01
+        let numbers: Vec<f32> = (0..100).map(|x| 10. - x as f32 * 0.01).collect();
+        let run = |threshold| {
+            let reported = Rc::new(RefCell::new(vec![]));
+            let hook = reported.clone();
+            let rng = rand::rngs::StdRng::seed_from_u64(0);
+            let best = LocalSearch::builder()
+                .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(1000).build())
+                .rng(rng)
+                .on_new_best_above(threshold, move |best: &Number| {
+                    hook.borrow_mut().push(best.index())
+                })
+                .build()
+                .optimize(Number::new(0, numbers[0]));
+            let reported = reported.borrow().clone();
+            (best, reported)
+        };
+
+        let (best, reported) = run(ImprovementThreshold::Absolute(0.095));
+        assert_eq!(best.index(), 99);
+        // 99 improvements, of which every tenth is reported
+        assert_eq!(reported, (1..100).step_by(10).collect::<Vec<usize>>());
+
+        let (best, reported) = run(ImprovementThreshold::Relative(0.05));
+        assert_eq!(best.index(), 99);
+        assert!(reported.len() > 1 && reported.len() < 99);
+    }
+
+    #[test]
+    fn metropolis_reproduces_sa() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let run = |temperature: f32| {
+            let rng = rand::rngs::StdRng::seed_from_u64(0);
+            let iterations_max = 100;
+            let trace_sa = Rc::new(RefCell::new(vec![]));
+            let trace_local_search = Rc::new(RefCell::new(vec![]));
+
+            let recorder = trace_sa.clone();
+            let sa = SimulatedAnnealing::builder()
+                .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(iterations_max).build())
+                .rng(rng.clone())
+                .cooling_schedule(FactorSchedule::new(temperature, 0.05))
+                .on_new_best(move |best: &Number| recorder.borrow_mut().push(best.index()))
+                .build();
+
+            let recorder = trace_local_search.clone();
+            let local_search = LocalSearch::builder()
+                .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+                .acceptance(Metropolis::new(FactorSchedule::new(temperature, 0.05)))
+                .terminator(Terminator::builder().iterations(iterations_max).build())
+                .rng(rng)
+                .on_new_best(move |best: &Number| recorder.borrow_mut().push(best.index()))
+                .build();
+
+            let sa_solution = sa.optimize(Number::new(0, numbers[0]));
+            let local_search_solution = local_search.optimize(Number::new(0, numbers[0]));
+            assert_eq!(local_search_solution.index(), sa_solution.index());
+            assert_eq!(*trace_local_search.borrow(), *trace_sa.borrow());
+            sa_solution.index()
+        };
+
+        // hot enough to climb over the local minimum at index 2
+        assert_eq!(run(100.), 7);
+        // too cold to ever accept the step up to index 3
+        assert_eq!(run(0.01), 2);
+    }
+
+    #[test]
+    fn great_deluge_descends() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let local_search = LocalSearch::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+            .acceptance(GreatDeluge::new(10., 0.01))
+            .terminator(Terminator::builder().iterations(300).build())
+            .rng(rng)
+            .build();
+
+        let solution = local_search.optimize(Number::new(0, numbers[0]));
+        assert_eq!(solution.index(), 7);
+    }
+}
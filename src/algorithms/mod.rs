@@ -1,4 +1,9 @@
 //! Optimization algorithms
+pub mod ga;
+pub mod guided_restart;
+pub mod ils;
+pub mod incremental;
 pub mod lns;
+pub mod local_search;
 pub mod sa;
 pub mod vns;
@@ -1,20 +1,59 @@
 //! _simulated annealing_.
-use std::{cell::RefCell, ops::MulAssign};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    ops::MulAssign,
+    rc::Rc,
+};
+
+use rand::Rng;
 
 use crate::{
-    selectors::OperatorSelector, termination::TerminationCriteria, Evaluate, ImprovingHeuristic,
-    Operator,
+    acceptance::{AcceptanceRule, Metropolis},
+    algorithms::local_search::LocalSearch,
+    pause::HeuristicState,
+    selectors::OperatorSelector,
+    termination::{TerminationCriteria, Terminator},
+    throttle_callback, warn_default_seed, Callback, Constrained, Evaluate, FeasibilityRetry,
+    ImprovementThreshold, ImprovingHeuristic, InitialGuard, Objective, Operator, OptimizeError,
+    Reseed, ReturnPolicy, SearchRng, DEFAULT_SEED,
 };
 
-use rand::Rng;
+/// Prepares the search, e.g. its cooling schedule, given the operators, the source of randomness, and the initial
+/// solution.
+type WarmUp<Solution> =
+    Box<dyn Fn(&dyn OperatorSelector<Solution>, &mut dyn rand::RngCore, &Solution)>;
+
+/// Number of random neighbors of the initial solution from which [SimulatedAnnealing::auto] derives the initial
+/// temperature.
+const AUTO_TEMPERATURE_SAMPLES: usize = 100;
 
 /// Simulated Annealing implementation.
+///
+/// This is a [LocalSearch] with [Metropolis] acceptance.
 pub struct SimulatedAnnealing<Solution> {
-    selector: Box<dyn OperatorSelector<Solution>>,
-    terminator: Box<dyn TerminationCriteria<Solution>>,
-    rng: RefCell<Box<dyn rand::RngCore>>,
-    cooling_schedule: Box<dyn CoolingSchedule>,
-    minimum_acceptance_probability: f32,
+    search: LocalSearch<Solution>,
+    cooling_schedule: Rc<dyn CoolingSchedule>,
+    terminate_when_frozen: bool,
+    acceptance_window: usize,
+    acceptances: RefCell<VecDeque<bool>>,
+    warm_up: Option<WarmUp<Solution>>,
+    batch: Option<Batch<Solution>>,
+}
+
+/// Proposes several candidates per iteration, see [SABuilder::batch_size].
+struct Batch<Solution> {
+    size: usize,
+    duplicate: fn(&Solution) -> Solution,
+    decision: Cell<Option<BatchDecision>>,
+}
+
+/// The acceptance decision on the proposed candidate of a batch, which only holds for the objectives it was taken on.
+#[derive(Clone, Copy)]
+struct BatchDecision {
+    candidate: f32,
+    incumbent: f32,
+    accepted: bool,
 }
 
 /// Builder design pattern for [SimulatedAnnealing].
@@ -22,21 +61,130 @@ pub struct SABuilder<Solution> {
     selector: Option<Box<dyn OperatorSelector<Solution>>>,
     terminator: Option<Box<dyn TerminationCriteria<Solution>>>,
     operators: Vec<Box<dyn Operator<Solution = Solution>>>,
-    rng: Option<Box<dyn rand::RngCore>>,
+    rng: Option<SearchRng>,
     cooling_schedule: Option<Box<dyn CoolingSchedule>>,
     minimum_acceptance_probability: Option<f32>,
+    acceptance_rule: AcceptanceRule,
+    steps_per_temperature: usize,
+    initial_guard: Option<InitialGuard<Solution>>,
+    on_new_best: Option<Callback<Solution>>,
+    objective_epsilon: f32,
+    return_policy: ReturnPolicy,
+    reseed: Option<Reseed>,
+    terminate_when_frozen: bool,
+    feasibility_retry: Option<FeasibilityRetry<Solution>>,
+    acceptance_window: usize,
+    seed: Option<u64>,
+    warm_up: Option<WarmUp<Solution>>,
+    batch: Option<Batch<Solution>>,
+}
+
+/// Scalar hyperparameters of [SimulatedAnnealing] with a [FactorSchedule], e.g. to store experiments in a config file.
+///
+/// Operators and selectors cannot be serialized, and are passed to [SimulatedAnnealing::from_config] separately.
+/// The operator identifiers merely record which operators were used.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SaConfig {
+    /// Temperature at the start of the search
+    pub initial_temperature: f32,
+    /// Fraction by which the temperature decreases every cooling step
+    pub cooling_factor: f32,
+    /// Maximum number of iterations
+    pub iterations: usize,
+    /// Number of proposals at each temperature
+    pub steps_per_temperature: usize,
+    /// Lower bound on the acceptance probability of worse candidates
+    pub minimum_acceptance_probability: f32,
+    /// Margin by which candidates must be better to count as improvements
+    pub objective_epsilon: f32,
+    /// Seed of the random number generator
+    pub seed: u64,
+    /// Identifiers of the operators
+    pub operators: Vec<String>,
 }
 
 /// Cool the system according to a schedule
 pub trait CoolingSchedule {
     fn cool(&self);
     fn temperature(&self) -> f32;
+
+    /// Test whether the system is frozen, i.e. so cold that worse candidates are practically never accepted.
+    ///
+    /// A frozen system only accepts improvements, see [Metropolis]. By default, a system never freezes.
+    fn is_frozen(&self) -> bool {
+        false
+    }
+
+    /// Observe whether a candidate was ```accepted```, and the progress of the search, if known, e.g. to adapt the
+    /// temperature online. By default, observations are ignored.
+    #[allow(unused_variables)]
+    fn observe(&self, accepted: bool, progress: Option<f32>) {}
+
+    /// Continue from ```temperature```, e.g. when resuming a paused search, see [crate::pause::SearchState]. By
+    /// default, the temperature is kept, e.g. as it follows the progress of the search.
+    #[allow(unused_variables)]
+    fn set_temperature(&self, temperature: f32) {}
+}
+
+/// Share a cooling schedule, e.g. with a termination criterium or an observer.
+impl<T: CoolingSchedule + ?Sized> CoolingSchedule for Rc<T> {
+    fn cool(&self) {
+        self.as_ref().cool()
+    }
+
+    fn temperature(&self) -> f32 {
+        self.as_ref().temperature()
+    }
+
+    fn is_frozen(&self) -> bool {
+        self.as_ref().is_frozen()
+    }
+
+    fn observe(&self, accepted: bool, progress: Option<f32>) {
+        self.as_ref().observe(accepted, progress)
+    }
+
+    fn set_temperature(&self, temperature: f32) {
+        self.as_ref().set_temperature(temperature)
+    }
 }
 
 /// Cool, every iteration, using a constant factor
 pub struct FactorSchedule {
     temperature: RefCell<f32>,
     cooling_factor: f32,
+    freeze_below: f32,
+}
+
+/// Derive the temperature from the progress of a (shared) termination criterium, such that the system
+/// is cooled down exactly when the search terminates.
+///
+/// Termination criteria without progress, e.g. custom ones, are treated as not progressing.
+pub struct ScheduleByProgress<Solution> {
+    terminator: Rc<dyn TerminationCriteria<Solution>>,
+    initial_temperature: f32,
+    decay: ProgressDecay,
+}
+
+/// Adapt the temperature online, such that the acceptance ratio tracks a target, which decreases linearly from
+/// ```target_ratio``` to 0 with the progress of the search.
+///
+/// After every proposal, the temperature is raised if the smoothed acceptance ratio is below the target, and lowered
+/// otherwise, by the adaptation rate. Early on, the search thus accepts about ```target_ratio``` of its candidates, while
+/// it cools towards a local search as it approaches termination. Termination criteria without progress keep the target
+/// constant. The initial temperature can be derived from sample deltas, see [AdaptiveSchedule::calibrate].
+pub struct AdaptiveSchedule {
+    temperature: Cell<f32>,
+    target_ratio: f32,
+    adaptation_rate: f32,
+    smoothing: f32,
+    ratio: Cell<f32>,
+}
+
+enum ProgressDecay {
+    Linear,
+    Exponential { final_temperature: f32 },
 }
 
 impl FactorSchedule {
@@ -44,8 +192,15 @@ impl FactorSchedule {
         Self {
             temperature: RefCell::new(initial_temperature),
             cooling_factor: decay,
+            freeze_below: 0.,
         }
     }
+
+    /// Freeze the system once the temperature drops below ```epsilon``` (default: 0, i.e. never)
+    pub fn freeze_below(mut self, epsilon: f32) -> Self {
+        self.freeze_below = epsilon;
+        self
+    }
 }
 
 impl CoolingSchedule for FactorSchedule {
@@ -58,6 +213,131 @@ impl CoolingSchedule for FactorSchedule {
     fn temperature(&self) -> f32 {
         *self.temperature.borrow()
     }
+
+    fn is_frozen(&self) -> bool {
+        self.temperature() < self.freeze_below
+    }
+
+    fn set_temperature(&self, temperature: f32) {
+        self.temperature.replace(temperature);
+    }
+}
+
+impl AdaptiveSchedule {
+    /// Track ```target_ratio```, starting from a temperature of 1.
+    pub fn new(target_ratio: f32) -> Self {
+        Self {
+            temperature: Cell::new(1.),
+            target_ratio,
+            adaptation_rate: 0.05,
+            smoothing: 0.05,
+            ratio: Cell::new(target_ratio),
+        }
+    }
+
+    /// Set the initial temperature (default: 1)
+    pub fn initial_temperature(self, temperature: f32) -> Self {
+        self.temperature.set(temperature);
+        self
+    }
+
+    /// Set the relative change of the temperature per proposal (default: 0.05)
+    pub fn adaptation_rate(mut self, rate: f32) -> Self {
+        self.adaptation_rate = rate;
+        self
+    }
+
+    /// Set the weight of the latest proposal in the smoothed acceptance ratio (default: 0.05)
+    pub fn smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Set the temperature at which the mean worsening of ```deltas```, candidate minus incumbent, is accepted with a
+    /// probability of ```target_ratio```. Improving deltas are ignored; without worsening ones, nothing changes.
+    pub fn calibrate(&self, deltas: &[f32]) {
+        let worsening: Vec<f32> = deltas.iter().copied().filter(|delta| *delta > 0.).collect();
+        if worsening.is_empty() {
+            return;
+        }
+        let mean = worsening.iter().sum::<f32>() / worsening.len() as f32;
+        self.temperature.set(-mean / self.target_ratio.ln());
+    }
+
+    /// Return the smoothed acceptance ratio.
+    pub fn acceptance_ratio(&self) -> f32 {
+        self.ratio.get()
+    }
+}
+
+impl CoolingSchedule for AdaptiveSchedule {
+    /// The temperature follows the observations, hence cooling is a no-op.
+    fn cool(&self) {}
+
+    fn temperature(&self) -> f32 {
+        self.temperature.get()
+    }
+
+    fn observe(&self, accepted: bool, progress: Option<f32>) {
+        let observation = if accepted { 1. } else { 0. };
+        let ratio = self.ratio.get() + self.smoothing * (observation - self.ratio.get());
+        self.ratio.set(ratio);
+
+        let target = self.target_ratio * (1. - progress.unwrap_or(0.));
+        let factor = if ratio < target {
+            1. + self.adaptation_rate
+        } else {
+            1. - self.adaptation_rate
+        };
+        self.temperature.set(self.temperature.get() * factor);
+    }
+
+    fn set_temperature(&self, temperature: f32) {
+        self.temperature.set(temperature);
+    }
+}
+
+impl<Solution> ScheduleByProgress<Solution> {
+    /// Cool linearly: T = T0 * (1 - progress).
+    pub fn linear(
+        initial_temperature: f32,
+        terminator: Rc<dyn TerminationCriteria<Solution>>,
+    ) -> Self {
+        Self {
+            terminator,
+            initial_temperature,
+            decay: ProgressDecay::Linear,
+        }
+    }
+
+    /// Cool exponentially from T0 to ```final_temperature```: T = T0 * (T_final / T0)^progress.
+    pub fn exponential(
+        initial_temperature: f32,
+        final_temperature: f32,
+        terminator: Rc<dyn TerminationCriteria<Solution>>,
+    ) -> Self {
+        Self {
+            terminator,
+            initial_temperature,
+            decay: ProgressDecay::Exponential { final_temperature },
+        }
+    }
+}
+
+impl<Solution> CoolingSchedule for ScheduleByProgress<Solution> {
+    /// The temperature follows the termination criterium, hence cooling is a no-op.
+    fn cool(&self) {}
+
+    fn temperature(&self) -> f32 {
+        let progress = self.terminator.progress().unwrap_or(0.);
+        match self.decay {
+            ProgressDecay::Linear => self.initial_temperature * (1. - progress),
+            ProgressDecay::Exponential { final_temperature } => {
+                self.initial_temperature
+                    * (final_temperature / self.initial_temperature).powf(progress)
+            }
+        }
+    }
 }
 
 impl<Solution> SimulatedAnnealing<Solution> {
@@ -69,25 +349,192 @@ impl<Solution> SimulatedAnnealing<Solution> {
             rng: None,
             cooling_schedule: None,
             minimum_acceptance_probability: None,
+            acceptance_rule: AcceptanceRule::Metropolis,
+            steps_per_temperature: 1,
+            initial_guard: None,
+            on_new_best: None,
+            objective_epsilon: 0.,
+            return_policy: ReturnPolicy::BestEver,
+            reseed: None,
+            terminate_when_frozen: false,
+            feasibility_retry: None,
+            acceptance_window: 100,
+            seed: None,
+            warm_up: None,
+            batch: None,
+        }
+    }
+
+    /// Return the current temperature of the cooling schedule.
+    pub fn temperature(&self) -> f32 {
+        self.cooling_schedule.temperature()
+    }
+
+    /// Return the fraction of accepted candidates among the proposals of the last iterations, see
+    /// [SABuilder::acceptance_window], e.g. to tune the temperature during a run.
+    ///
+    /// Returns 0 before the first proposal.
+    pub fn acceptance_ratio(&self) -> f32 {
+        let acceptances = self.acceptances.borrow();
+        if acceptances.is_empty() {
+            return 0.;
+        }
+        let accepted = acceptances.iter().filter(|&&accepted| accepted).count();
+        accepted as f32 / acceptances.len() as f32
+    }
+
+    /// Record whether the last candidate was ```accepted```, forgetting proposals outside the window.
+    fn record_acceptance(&self, accepted: bool) {
+        let mut acceptances = self.acceptances.borrow_mut();
+        if acceptances.len() == self.acceptance_window {
+            acceptances.pop_front();
         }
+        acceptances.push_back(accepted);
+    }
+
+    /// Test whether the cooling schedule is frozen, in which case only improvements are accepted.
+    pub fn is_frozen(&self) -> bool {
+        self.cooling_schedule.is_frozen()
+    }
+
+    /// Return the index of the operator selected in the last iteration.
+    pub fn last_selected(&self) -> Option<usize> {
+        self.search.last_selected()
+    }
+
+    /// Draw a batch of candidates, and test them for acceptance from best to worst: the first accepted candidate is
+    /// proposed, or, if all are rejected, the best one. The decision is kept for the acceptance test of the step.
+    fn propose_batch(&self, batch: &Batch<Solution>, incumbent: Solution) -> Solution
+    where
+        Solution: Evaluate,
+    {
+        batch.decision.set(None);
+        let mut candidates =
+            self.search
+                .propose_candidates(&incumbent, batch.size, batch.duplicate);
+        let objectives = Solution::evaluate_batch(&candidates);
+        let mut order: Vec<usize> = (0..candidates.len()).collect();
+        order.sort_by_key(|index| Objective(objectives[*index]));
+        let accepted = order.iter().copied().find(|index| {
+            self.search
+                .accept_candidate(&candidates[*index], &incumbent)
+        });
+        let candidate = candidates.swap_remove(accepted.unwrap_or(order[0]));
+        batch.decision.set(Some(BatchDecision {
+            candidate: candidate.evaluate(),
+            incumbent: incumbent.evaluate(),
+            accepted: accepted.is_some(),
+        }));
+        candidate
+    }
+}
+
+impl<Solution: Evaluate + Clone + 'static> SimulatedAnnealing<Solution> {
+    /// Return a builder for a Simulated Annealing which tunes itself, and only needs ```selector``` and
+    /// ```terminator```.
+    ///
+    /// The initial temperature is derived from random neighbors of the initial solution, drawn from the operators
+    /// of ```selector``` without selecting them, see [OperatorSelector::operator], such that about 40% of
+    /// worse candidates are accepted at first, after which an [AdaptiveSchedule] holds the acceptance ratio near a
+    /// target which decreases to 0 as the termination criteria progress. The search is seeded with 0, which can be
+    /// overridden on the builder, like every other setting.
+    pub fn auto<T: OperatorSelector<Solution> + 'static>(
+        selector: T,
+        terminator: Box<dyn TerminationCriteria<Solution>>,
+    ) -> SABuilder<Solution> {
+        let schedule = Rc::new(AdaptiveSchedule::new(0.4));
+        let calibrated = schedule.clone();
+        let mut builder = Self::builder()
+            .selector(selector)
+            .terminator(terminator)
+            .cooling_schedule(schedule)
+            .seed(0);
+        builder.warm_up = Some(Box::new(
+            move |selector: &dyn OperatorSelector<Solution>,
+                  rng: &mut dyn rand::RngCore,
+                  initial: &Solution| {
+                // draw from the operators directly, such that the state of the selector is left as is
+                let operators: Vec<&dyn Operator<Solution = Solution>> =
+                    (0..selector.operator_count().unwrap_or(0))
+                        .map_while(|index| selector.operator(index))
+                        .collect();
+                if operators.is_empty() {
+                    log::warn!("SimulatedAnnealing::auto: the selector does not expose its operators, so the temperature is not calibrated");
+                    return;
+                }
+                let objective = initial.evaluate();
+                let deltas: Vec<f32> = (0..AUTO_TEMPERATURE_SAMPLES)
+                    .map(|_| {
+                        let operator = operators[rng.gen_range(0..operators.len())];
+                        operator.shake(initial.clone(), rng).evaluate() - objective
+                    })
+                    .collect();
+                calibrated.calibrate(&deltas);
+            },
+        ));
+        builder
+    }
+}
+
+impl<Solution: 'static> SimulatedAnnealing<Solution> {
+    /// Construct the heuristic from its hyperparameters, and the operators offered by ```selector```.
+    pub fn from_config<T: OperatorSelector<Solution> + 'static>(
+        config: &SaConfig,
+        selector: T,
+    ) -> Self {
+        Self::builder()
+            .selector(selector)
+            .terminator(Terminator::builder().iterations(config.iterations).build())
+            .seed(config.seed)
+            .cooling_schedule(FactorSchedule::new(
+                config.initial_temperature,
+                config.cooling_factor,
+            ))
+            .steps_per_temperature(config.steps_per_temperature)
+            .minimum_acceptance_probability(config.minimum_acceptance_probability)
+            .objective_epsilon(config.objective_epsilon)
+            .build()
     }
 }
 
 impl<Solution> SABuilder<Solution> {
     /// Build the configured Simulated Annealing heuristic
-    pub fn build(self) -> SimulatedAnnealing<Solution> {
-        SimulatedAnnealing {
-            rng: RefCell::new(self.rng.expect("No RNG source specified")),
-            selector: self
-                .selector
-                .expect("No operator selection strategy specified"),
-            terminator: self.terminator.expect("No termination criteria specified"),
-            cooling_schedule: self
-                .cooling_schedule
+    pub fn build(mut self) -> SimulatedAnnealing<Solution> {
+        if self.rng.is_none() {
+            warn_default_seed("SABuilder");
+            self = self.seed(DEFAULT_SEED);
+        }
+        let cooling_schedule: Rc<dyn CoolingSchedule> = Rc::from(
+            self.cooling_schedule
                 .expect("No cooling schedule specified"),
-            minimum_acceptance_probability: self
-                .minimum_acceptance_probability
-                .expect("No minimum acceptance probability specified"),
+        );
+        let acceptance = Metropolis::shared(cooling_schedule.clone())
+            .rule(self.acceptance_rule)
+            .minimum_acceptance_probability(self.minimum_acceptance_probability.unwrap_or(0.))
+            .steps_per_temperature(self.steps_per_temperature);
+
+        SimulatedAnnealing {
+            search: LocalSearch {
+                selector: self
+                    .selector
+                    .expect("No operator selection strategy specified"),
+                acceptance: Box::new(acceptance),
+                terminator: self.terminator.expect("No termination criteria specified"),
+                rng: RefCell::new(self.rng.expect("source of randomness was defaulted")),
+                initial_guard: self.initial_guard,
+                on_new_best: self.on_new_best,
+                objective_epsilon: self.objective_epsilon,
+                return_policy: self.return_policy,
+                reseed: self.reseed,
+                feasibility_retry: self.feasibility_retry,
+                seed: self.seed,
+            },
+            cooling_schedule,
+            terminate_when_frozen: self.terminate_when_frozen,
+            acceptance_window: self.acceptance_window,
+            acceptances: RefCell::new(VecDeque::with_capacity(self.acceptance_window)),
+            warm_up: self.warm_up,
+            batch: self.batch,
         }
     }
 
@@ -109,9 +556,21 @@ impl<Solution> SABuilder<Solution> {
         self
     }
 
-    /// Set source of randomness
+    /// Set source of randomness (default: seeded with [crate::DEFAULT_SEED])
     pub fn rng<T: rand::RngCore + 'static>(mut self, rng: T) -> Self {
-        self.rng = Some(Box::new(rng));
+        self.rng = Some(SearchRng::Custom(Box::new(rng)));
+        self.seed = None;
+        self
+    }
+
+    /// Seed a [rand::rngs::StdRng] as source of randomness, which is reported by [crate::ImprovingHeuristic::seed],
+    /// e.g. in the [crate::Outcome] of [crate::ImprovingHeuristic::optimize_timed], such that a run can be replayed.
+    ///
+    /// Selectors with their own source of randomness, e.g. [crate::selectors::RandomSelector], are seeded separately.
+    /// The position of the source of randomness is captured when the search is paused, see [crate::pause::HeuristicState].
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Some(SearchRng::seeded(seed));
+        self.seed = Some(seed);
         self
     }
 
@@ -121,75 +580,507 @@ impl<Solution> SABuilder<Solution> {
         self
     }
 
+    /// Set a lower bound on the acceptance probability of worse candidates (default: 0)
     pub fn minimum_acceptance_probability(mut self, probability: f32) -> Self {
         self.minimum_acceptance_probability = Some(probability);
         self
     }
+
+    /// Set the rule which computes the acceptance probability (default: [AcceptanceRule::Metropolis])
+    pub fn acceptance_rule(mut self, rule: AcceptanceRule) -> Self {
+        self.acceptance_rule = rule;
+        self
+    }
+
+    /// Set the number of proposals at each temperature before the schedule cools (default: 1)
+    pub fn steps_per_temperature(mut self, steps: usize) -> Self {
+        self.steps_per_temperature = steps;
+        self
+    }
+
+    /// Set a guard which validates, and possibly repairs, the initial solution
+    pub fn initial_guard<F: Fn(Solution) -> Result<Solution, String> + 'static>(
+        mut self,
+        guard: F,
+    ) -> Self {
+        self.initial_guard = Some(Box::new(guard));
+        self
+    }
+
+    /// Register a hook which receives every new best solution, e.g. to persist it
+    pub fn on_new_best<F: Fn(&Solution) + 'static>(mut self, hook: F) -> Self {
+        self.on_new_best = Some(Box::new(hook));
+        self
+    }
+
+    /// Register a hook which only receives the new best solutions that improve on the last reported one by more than
+    /// ```threshold```, e.g. to keep logs meaningful on smooth problems. The search itself still tracks every improvement.
+    pub fn on_new_best_above<F: Fn(&Solution) + 'static>(
+        mut self,
+        threshold: ImprovementThreshold,
+        hook: F,
+    ) -> Self
+    where
+        Solution: Evaluate,
+    {
+        self.on_new_best = Some(throttle_callback(threshold, hook));
+        self
+    }
+
+    /// Require candidates to be better by more than ```epsilon``` to count as improvements (default: 0)
+    pub fn objective_epsilon(mut self, epsilon: f32) -> Self {
+        self.objective_epsilon = epsilon;
+        self
+    }
+
+    /// Set which solution is returned once the search terminates (default: [ReturnPolicy::BestEver])
+    pub fn return_policy(mut self, policy: ReturnPolicy) -> Self {
+        self.return_policy = policy;
+        self
+    }
+
+    /// Reseed the source of randomness from ```seed``` after every ```iterations``` iterations, e.g. with external entropy in very long runs.
+    ///
+    /// The source of randomness is replaced by a [rand::rngs::StdRng]. Unless ```seed``` is deterministic, this breaks reproducibility, by design.
+    pub fn reseed_every<F: Fn() -> u64 + 'static>(mut self, iterations: usize, seed: F) -> Self {
+        self.reseed = Some(Reseed::new(iterations, seed));
+        self
+    }
+
+    /// Shake again, up to ```max_retries``` times, while the candidate is infeasible. If all retries fail, the
+    /// incumbent itself is proposed.
+    pub fn retry_until_feasible(mut self, max_retries: usize) -> Self
+    where
+        Solution: Constrained + Clone,
+    {
+        self.feasibility_retry = Some(FeasibilityRetry::new(max_retries));
+        self
+    }
+
+    /// Set the number of most recent iterations over which the acceptance ratio is computed (default: 100)
+    pub fn acceptance_window(mut self, iterations: usize) -> Self {
+        assert!(iterations > 0, "acceptance window must be positive");
+        self.acceptance_window = iterations;
+        self
+    }
+
+    /// Terminate once the cooling schedule is frozen, as the search then degenerates to a local search
+    pub fn terminate_when_frozen(mut self) -> Self {
+        self.terminate_when_frozen = true;
+        self
+    }
+
+    /// Propose ```size``` candidates of the incumbent per iteration, and move to the best one which passes the
+    /// acceptance test (default: 1).
+    ///
+    /// The candidates are evaluated at once by [Evaluate::evaluate_batch], e.g. vectorized, and count as a single
+    /// iteration, towards both termination and cooling.
+    pub fn batch_size(mut self, size: usize) -> Self
+    where
+        Solution: Clone,
+    {
+        assert!(size > 0, "batch size must be positive");
+        self.batch = Some(Batch {
+            size,
+            duplicate: Solution::clone,
+            decision: Cell::new(None),
+        });
+        self
+    }
 }
 
 impl<Solution> ImprovingHeuristic<Solution> for SimulatedAnnealing<Solution> {
     /// Accept iff the ```candidate``` is better than the ```incumbent```, or otherwise with a probabilty equal to the acceptance probability.
     ///
-    /// The acceptance probability is calculated as exp(-delta / Temperature).
+    /// The acceptance probability is calculated as exp(-delta / Temperature). A candidate of a batch, see
+    /// [SABuilder::batch_size], was already tested when it was proposed, hence the decision is reused once, if it
+    /// was taken on the same objectives. Any other pair is tested afresh.
     fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool
     where
         Solution: Evaluate,
     {
-        let temperature = self.cooling_schedule.temperature();
-        let r: f32 = self.rng.borrow_mut().gen();
-        let acceptance_probability =
-            compute_probability(temperature, incumbent.evaluate(), candidate.evaluate());
-        if candidate.evaluate() < incumbent.evaluate() {
-            true
-        } else if r <= acceptance_probability.max(self.minimum_acceptance_probability) {
-            true
-        } else {
-            false
+        let decision = self
+            .batch
+            .as_ref()
+            .and_then(|batch| batch.decision.take())
+            .filter(|decision| {
+                decision.candidate.to_bits() == candidate.evaluate().to_bits()
+                    && decision.incumbent.to_bits() == incumbent.evaluate().to_bits()
+            });
+        match decision {
+            Some(decision) => decision.accepted,
+            None => self.search.accept_candidate(candidate, incumbent),
         }
     }
 
-    /// Select an operator and draw a random neighbor.
+    /// Select an operator and draw a random neighbor. The system is cooled after every ```steps_per_temperature``` proposals.
     fn propose_candidate(&self, incumbent: Solution) -> Solution
     where
         Solution: Evaluate,
     {
-        let operator = self.selector.select(&incumbent);
-        let candidate = operator.shake(incumbent, self.rng.borrow_mut().as_mut());
-        self.cooling_schedule.cool();
-        candidate
+        match &self.batch {
+            Some(batch) => self.propose_batch(batch, incumbent),
+            None => self.search.propose_candidate(incumbent),
+        }
     }
 
-    /// Test whether the termination criteria are fulfilled.
+    /// Test whether the termination criteria are fulfilled, or, if so configured, the cooling schedule is frozen.
     fn should_terminate(&self, incumbent: &Solution) -> bool {
-        self.terminator.terminate(&incumbent)
+        self.search.should_terminate(incumbent)
+            || (self.terminate_when_frozen && self.cooling_schedule.is_frozen())
+    }
+
+    /// Return the progress of the termination criteria.
+    fn progress(&self) -> Option<f32> {
+        self.search.progress()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.search.seed()
+    }
+
+    /// Capture the state of the local search, see [LocalSearch::pause_state], and the temperature.
+    fn pause_state(&self) -> HeuristicState {
+        HeuristicState {
+            temperature: Some(self.cooling_schedule.temperature()),
+            ..self.search.pause_state()
+        }
+    }
+
+    /// Restore the state of the local search, see [LocalSearch::resume_state], and the temperature, if captured.
+    fn resume_state(&self, state: &HeuristicState, iterations: usize) {
+        self.search.resume_state(state, iterations);
+        if let Some(temperature) = state.temperature {
+            self.cooling_schedule.set_temperature(temperature);
+        }
+    }
+
+    /// Apply the initial guard, if specified, then warm up, e.g. calibrate the temperature, see
+    /// [SimulatedAnnealing::auto].
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        let initial = self.search.guard_initial(initial)?;
+        if let Some(warm_up) = &self.warm_up {
+            warm_up(
+                self.search.selector.as_ref(),
+                &mut *self.search.rng.borrow_mut(),
+                &initial,
+            );
+        }
+        Ok(initial)
     }
-}
 
-fn compute_probability(
-    temperature: f32,
-    objective_incumbent: f32,
-    objective_candidate: f32,
-) -> f32 {
-    let delta = objective_incumbent - objective_candidate;
-    if delta < 0. {
-        (-delta / temperature).exp()
-    } else {
-        1.
+    fn objective_epsilon(&self) -> f32 {
+        self.search.objective_epsilon()
+    }
+
+    fn return_policy(&self) -> ReturnPolicy {
+        self.search.return_policy()
+    }
+
+    /// Pass the new best solution to the registered hook, if any.
+    fn callback_new_best(&self, best: &Solution) {
+        self.search.callback_new_best(best)
+    }
+
+    /// Count the acceptance towards the acceptance ratio, and pass it to the cooling schedule.
+    fn callback_candidate_accepted(&self, candidate: &Solution, incumbent: &Solution) {
+        self.record_acceptance(true);
+        self.cooling_schedule.observe(true, self.search.progress());
+        self.search
+            .callback_candidate_accepted(candidate, incumbent)
+    }
+
+    /// Count the rejection towards the acceptance ratio, and pass it to the cooling schedule.
+    fn callback_candidate_rejected(&self, candidate: &Solution, incumbent: &Solution) {
+        self.record_acceptance(false);
+        self.cooling_schedule.observe(false, self.search.progress());
+        self.search
+            .callback_candidate_rejected(candidate, incumbent)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use assert_approx_eq::assert_approx_eq;
     use rand::SeedableRng;
 
     use crate::{
-        algorithms::sa::{FactorSchedule, SimulatedAnnealing},
-        selectors::RandomSelector,
-        termination::Terminator,
-        test::{NeighborSwap, Number},
-        ImprovingHeuristic,
+        algorithms::sa::{
+            AdaptiveSchedule, CoolingSchedule, FactorSchedule, SaConfig, ScheduleByProgress,
+            SimulatedAnnealing,
+        },
+        selectors::{RandomSelector, SequentialSelector},
+        termination::{
+            IterationTerminator, TemperatureTerminator, TerminationCriteria, Terminator,
+        },
+        test::{CountingShake, HalfInfeasible, NeighborSwap, Number, RecordingShake},
+        Constrained, ImprovingHeuristic, ReturnPolicy,
     };
 
+    #[test]
+    #[cfg(feature = "std-time")]
+    fn sa_seed_replays_run() {
+        use crate::Evaluate;
+
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0., 3., 1., 4., 6.];
+        let build = |seed: u64| {
+            SimulatedAnnealing::builder()
+                .selector(SequentialSelector::new().option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(50).build())
+                .cooling_schedule(FactorSchedule::new(5., 0.9))
+                .seed(seed)
+                .build()
+        };
+
+        let sa = build(42);
+        assert_eq!(sa.seed(), Some(42));
+        let outcome = sa.optimize_timed(Number::new(0, numbers[0]));
+        let seed = outcome.seed().expect("seed was not recorded");
+        assert_eq!(seed, 42);
+
+        let replay = build(seed).optimize_timed(Number::new(0, numbers[0]));
+        assert_eq!(replay.solution().index(), outcome.solution().index());
+        assert_eq!(replay.solution().evaluate(), outcome.solution().evaluate());
+
+        // an explicit source of randomness has no known seed
+        let sa = SimulatedAnnealing::builder()
+            .selector(SequentialSelector::new().option(NeighborSwap::new(&numbers)))
+            .terminator(Terminator::builder().iterations(50).build())
+            .cooling_schedule(FactorSchedule::new(5., 0.9))
+            .seed(42)
+            .rng(rand::rngs::StdRng::seed_from_u64(42))
+            .build();
+        assert_eq!(sa.seed(), None);
+    }
+
+    #[test]
+    fn sa_without_rng_is_reproducible() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0., 3., 1., 4., 6.];
+        let run = || {
+            let sa = SimulatedAnnealing::builder()
+                .selector(SequentialSelector::new().option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(50).build())
+                .cooling_schedule(FactorSchedule::new(5., 0.9))
+                .build();
+            assert_eq!(sa.seed(), Some(crate::DEFAULT_SEED));
+            let trace = Rc::new(RefCell::new(vec![]));
+            let recorder = trace.clone();
+            let best = sa
+                .with_callbacks()
+                .on_accepted(move |candidate, _| recorder.borrow_mut().push(candidate.index()))
+                .optimize(Number::new(0, numbers[0]));
+            let trace = trace.borrow().clone();
+            (best.index(), trace)
+        };
+
+        let (best, trace) = run();
+        assert!(!trace.is_empty());
+        assert_eq!(run(), (best, trace));
+    }
+
+    #[test]
+    fn sa_batch_size() {
+        use crate::{operators::FnOperator, Operator};
+
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0., 3., 1., 4., 6.];
+        let run = |batch_size: Option<usize>| {
+            let shakes = Rc::new(Cell::new(0));
+            let counter = shakes.clone();
+            let swap = NeighborSwap::new(&numbers);
+            let operator = FnOperator::new("counting swap", move |solution, rng| {
+                counter.set(counter.get() + 1);
+                swap.shake(solution, rng)
+            });
+            let mut builder = SimulatedAnnealing::builder()
+                .selector(SequentialSelector::new().option(operator))
+                .terminator(Terminator::builder().iterations(30).build())
+                .cooling_schedule(FactorSchedule::new(5., 0.9))
+                .seed(1);
+            if let Some(size) = batch_size {
+                builder = builder.batch_size(size);
+            }
+
+            let iterations = Rc::new(Cell::new(0));
+            let trace = Rc::new(RefCell::new(vec![]));
+            let (counter, recorder) = (iterations.clone(), trace.clone());
+            let rejections = iterations.clone();
+            let best = builder
+                .build()
+                .with_callbacks()
+                .on_accepted(move |candidate, _| {
+                    counter.set(counter.get() + 1);
+                    recorder.borrow_mut().push(candidate.index());
+                })
+                .on_rejected(move |_, _| rejections.set(rejections.get() + 1))
+                .optimize(Number::new(0, numbers[0]));
+            let trace = trace.borrow().clone();
+            (best.index(), trace, iterations.get(), shakes.get())
+        };
+
+        // a batch of one is the plain search
+        let plain = run(None);
+        assert_eq!(plain.2, 30);
+        assert_eq!(plain.3, 30);
+        assert_eq!(run(Some(1)), plain);
+
+        // four candidates per iteration, where the termination criteria count iterations
+        let (_, _, iterations, shakes) = run(Some(4));
+        assert_eq!(iterations, 30);
+        assert_eq!(shakes, 4 * 30);
+    }
+
+    #[test]
+    fn sa_batch_decision_is_not_stale() {
+        use crate::Evaluate;
+
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let sa = SimulatedAnnealing::builder()
+            .selector(SequentialSelector::new().option(NeighborSwap::new(&numbers)))
+            .terminator(Terminator::builder().iterations(10).build())
+            .cooling_schedule(FactorSchedule::new(1e-6, 0.9))
+            .seed(0)
+            .batch_size(3)
+            .build();
+
+        // every neighbor of the local optimum at index 2 is worse, and rejected when cold
+        let incumbent = Number::new(2, numbers[2]);
+        let candidate = sa.propose_candidate(incumbent.clone());
+        assert!(candidate.evaluate() > incumbent.evaluate());
+
+        // a different pair is tested afresh, rather than by the rejection of the batch
+        let better = Number::new(7, numbers[7]);
+        assert!(sa.accept_candidate(&better, &incumbent));
+
+        // the proposed candidate itself is still rejected
+        let candidate = sa.propose_candidate(incumbent.clone());
+        assert!(!sa.accept_candidate(&candidate, &incumbent));
+    }
+
+    #[test]
+    fn sa_acceptance_ratio() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let sa = SimulatedAnnealing::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rng)
+            .cooling_schedule(FactorSchedule::new(1., 0.))
+            .acceptance_window(4)
+            .build();
+
+        let solution = Number::new(0, numbers[0]);
+        assert_approx_eq!(sa.acceptance_ratio(), 0.);
+        let mut ratios = vec![];
+        for accepted in [true, false, true, true, false, false, false] {
+            if accepted {
+                sa.callback_candidate_accepted(&solution, &solution);
+            } else {
+                sa.callback_candidate_rejected(&solution, &solution);
+            }
+            ratios.push(sa.acceptance_ratio());
+        }
+        // the window fills up after 4 proposals, after which the oldest proposal drops out
+        let expected = [1., 0.5, 2. / 3., 0.75, 0.5, 0.5, 0.25];
+        for (ratio, expected) in ratios.into_iter().zip(expected) {
+            assert_approx_eq!(ratio, expected);
+        }
+    }
+
+    #[test]
+    fn sa_retry_until_feasible() {
+        let sa = SimulatedAnnealing::builder()
+            .selector(SequentialSelector::new().option(HalfInfeasible))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .cooling_schedule(FactorSchedule::new(1., 0.))
+            .retry_until_feasible(2)
+            .build();
+
+        let incumbent = Number::new(0, 5.);
+        let candidates: Vec<Number> = (0..100)
+            .map(|_| sa.propose_candidate(incumbent.clone()))
+            .collect();
+        assert!(candidates
+            .iter()
+            .all(|candidate| candidate.is_feasible() || candidate.index() == incumbent.index()));
+        // with 3 attempts, about 1 in 8 proposals falls back to the incumbent
+        assert!(candidates.iter().any(|candidate| candidate.index() == 0));
+        assert!(candidates.iter().any(|candidate| candidate.index() == 1));
+    }
+
+    #[test]
+    fn factor_schedule_freezes() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let sa = SimulatedAnnealing::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rng)
+            .cooling_schedule(FactorSchedule::new(1., 0.5).freeze_below(0.1))
+            .minimum_acceptance_probability(1.)
+            .build();
+
+        let better = Number::new(7, 0.);
+        let worse = Number::new(0, 9.);
+        assert!(!sa.is_frozen());
+        assert!(sa.accept_candidate(&worse, &better));
+
+        let mut solution = Number::new(0, numbers[0]);
+        for _ in 0..4 {
+            solution = sa.propose_candidate(solution);
+        }
+        assert!(sa.is_frozen());
+        // the minimum acceptance probability no longer applies
+        assert!(!sa.accept_candidate(&worse, &better));
+        assert!(sa.accept_candidate(&better, &worse));
+    }
+
+    #[test]
+    fn sa_terminate_when_frozen() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let schedule = Rc::new(FactorSchedule::new(1., 0.5).freeze_below(0.1));
+        let sa = SimulatedAnnealing::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rng)
+            .cooling_schedule(schedule.clone())
+            .terminate_when_frozen()
+            .build();
+
+        sa.optimize(Number::new(0, numbers[0]));
+        // the search stops at the first frozen temperature
+        assert!(schedule.is_frozen());
+        assert_approx_eq!(schedule.temperature(), 0.0625);
+    }
+
+    #[test]
+    fn sa_temperature_terminator() {
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let count = Rc::new(Cell::new(0));
+        let schedule = Rc::new(FactorSchedule::new(1., 0.5));
+        let sa = SimulatedAnnealing::builder()
+            .selector(SequentialSelector::new().option(CountingShake::new(count.clone())))
+            .terminator(
+                Terminator::builder()
+                    .criterium(TemperatureTerminator::new(schedule.clone(), 0.1))
+                    .build(),
+            )
+            .rng(rng)
+            .cooling_schedule(schedule.clone())
+            .build();
+
+        sa.optimize(Number::new(0, 1.));
+        // 0.5, 0.25, 0.125, and the first temperature below 0.1
+        assert_eq!(count.get(), 4);
+        assert_approx_eq!(schedule.temperature(), 0.0625);
+    }
+
     #[test]
     fn sa_single_operator() {
         let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
@@ -210,4 +1101,282 @@ mod tests {
         let sa_solution = sa.optimize(initial_solution);
         assert_eq!(sa_solution.index(), 7);
     }
+
+    #[test]
+    fn sa_auto() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let sa = SimulatedAnnealing::auto(
+            RandomSelector::new(rng).option(NeighborSwap::new(&numbers)),
+            Terminator::builder().iterations(100).build(),
+        )
+        .build();
+
+        // start from a local optimum, such that the initial temperature is calibrated on worse neighbors
+        let solution = sa.optimize(Number::new(2, numbers[2]));
+        assert_eq!(solution.index(), 7);
+    }
+
+    #[test]
+    fn sa_auto_leaves_selector_untouched() {
+        use crate::acceptance::AcceptanceRule;
+
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let sa = SimulatedAnnealing::auto(
+            SequentialSelector::new()
+                .option(NeighborSwap::new(&numbers))
+                .option(NeighborSwap::new(&numbers)),
+            Terminator::builder().iterations(100).build(),
+        )
+        .build();
+
+        let uncalibrated = sa.temperature();
+        let mut state = sa.start(Number::new(2, numbers[2])).unwrap();
+        assert_ne!(sa.temperature(), uncalibrated);
+        assert_eq!(sa.last_selected(), None);
+        // both neighbors worsen by 1, which is accepted with the target probability
+        assert_approx_eq!(
+            AcceptanceRule::Metropolis.probability(sa.temperature(), 7., 8.),
+            0.4
+        );
+
+        // the search starts from the first operator
+        sa.step(&mut state);
+        assert_eq!(sa.last_selected(), Some(0));
+    }
+
+    #[test]
+    fn sa_auto_tracks_target_ratio() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let sa = SimulatedAnnealing::auto(
+            SequentialSelector::new().option(NeighborSwap::new(&numbers)),
+            Terminator::builder().iterations(10_000).build(),
+        )
+        .acceptance_window(500)
+        .build();
+
+        // early on, the target is hardly below its initial value of 0.4
+        let mut state = sa.start(Number::new(2, numbers[2])).unwrap();
+        for _ in 0..1000 {
+            sa.step(&mut state);
+        }
+        let early = sa.acceptance_ratio();
+        assert!((0.3..0.45).contains(&early), "{early}");
+
+        // the target decreases to 0 towards termination
+        for _ in 1000..9500 {
+            sa.step(&mut state);
+        }
+        let late = sa.acceptance_ratio();
+        assert!(late < 0.1, "{late}");
+    }
+
+    #[test]
+    fn adaptive_schedule() {
+        let schedule = AdaptiveSchedule::new(0.5).adaptation_rate(0.1);
+        // worsening by 2 on average is accepted with probability 0.5
+        schedule.calibrate(&[-5., 1., 3.]);
+        assert_approx_eq!(schedule.temperature(), 2. / 2_f32.ln());
+
+        // too many rejections heat the system up, too many acceptances cool it down
+        let initial = schedule.temperature();
+        schedule.observe(false, Some(0.));
+        assert_approx_eq!(schedule.temperature(), initial * 1.1);
+        schedule.observe(true, Some(0.));
+        assert_approx_eq!(schedule.temperature(), initial * 1.1 * 0.9);
+        // at the end of the search, the target vanishes, hence the system cools even on rejections
+        schedule.observe(false, Some(1.));
+        assert_approx_eq!(schedule.temperature(), initial * 1.1 * 0.9 * 0.9);
+    }
+
+    #[test]
+    fn sa_steps_per_temperature() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let schedule = FactorSchedule::new(100., 0.5);
+
+        let operator = NeighborSwap::new(&numbers);
+        let sa = SimulatedAnnealing::builder()
+            .selector(RandomSelector::new(rng.clone()).option(operator))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rng)
+            .cooling_schedule(schedule)
+            .steps_per_temperature(5)
+            .build();
+
+        let mut solution = Number::new(0, numbers[0]);
+        let mut temperatures = vec![];
+        for _ in 0..15 {
+            solution = sa.propose_candidate(solution);
+            temperatures.push(sa.cooling_schedule.temperature());
+        }
+
+        let expected: Vec<f32> = [100., 50., 25.]
+            .iter()
+            .flat_map(|&temperature| vec![temperature; 4].into_iter().chain([temperature / 2.]))
+            .collect();
+        assert_eq!(temperatures, expected);
+    }
+
+    #[test]
+    fn sa_temperature() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let sa = SimulatedAnnealing::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rng)
+            .cooling_schedule(FactorSchedule::new(100., 0.05))
+            .build();
+
+        assert_approx_eq!(sa.temperature(), 100.);
+        let mut solution = Number::new(0, numbers[0]);
+        for _ in 0..10 {
+            solution = sa.propose_candidate(solution);
+        }
+        assert_approx_eq!(sa.temperature(), 100. * 0.95_f32.powi(10), 1e-3);
+    }
+
+    #[test]
+    fn schedule_by_progress() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let iterations = Rc::new(IterationTerminator::new(10));
+        let schedule = ScheduleByProgress::<Number>::linear(100., iterations.clone());
+        let sa = SimulatedAnnealing::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+            .terminator(Box::new(iterations.clone()))
+            .rng(rng)
+            .cooling_schedule(schedule)
+            .build();
+
+        let solution = Number::new(0, numbers[0]);
+        for _ in 0..5 {
+            sa.should_terminate(&solution);
+        }
+        assert_approx_eq!(sa.temperature(), 50.);
+        for _ in 0..5 {
+            sa.should_terminate(&solution);
+        }
+        assert_approx_eq!(sa.temperature(), 0.);
+    }
+
+    #[test]
+    fn schedule_by_progress_exponential() {
+        let iterations = Rc::new(IterationTerminator::new(2));
+        let schedule = ScheduleByProgress::<Number>::exponential(100., 1., iterations.clone());
+        assert_approx_eq!(schedule.temperature(), 100.);
+        TerminationCriteria::<Number>::terminate(iterations.as_ref(), &Number::new(0, 0.));
+        assert_approx_eq!(schedule.temperature(), 10., 1e-3);
+        TerminationCriteria::<Number>::terminate(iterations.as_ref(), &Number::new(0, 0.));
+        assert_approx_eq!(schedule.temperature(), 1., 1e-3);
+    }
+
+    #[test]
+    fn sa_return_policy() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let build = |policy| {
+            let rng = rand::rngs::StdRng::seed_from_u64(0);
+            SimulatedAnnealing::builder()
+                .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(1).build())
+                .rng(rng)
+                .cooling_schedule(FactorSchedule::new(1e6, 0.))
+                .return_policy(policy)
+                .build()
+        };
+
+        // starting at the optimum, the hot system moves away to its only neighbor
+        let best = build(ReturnPolicy::BestEver).optimize(Number::new(7, numbers[7]));
+        let last = build(ReturnPolicy::FinalIncumbent).optimize(Number::new(7, numbers[7]));
+        assert_eq!(best.index(), 7);
+        assert_eq!(last.index(), 6);
+    }
+
+    fn config() -> SaConfig {
+        SaConfig {
+            initial_temperature: 100.,
+            cooling_factor: 0.05,
+            iterations: 100,
+            steps_per_temperature: 1,
+            minimum_acceptance_probability: 0.,
+            objective_epsilon: 0.,
+            seed: 0,
+            operators: vec!["neighbor-swap".to_string()],
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn sa_config_round_trip() {
+        let config = config();
+        let serialized = serde_json::to_string(&config).unwrap();
+        let deserialized: SaConfig = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, config);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn sa_from_deserialized_config() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let serialized = serde_json::to_string(&config()).unwrap();
+        let config: SaConfig = serde_json::from_str(&serialized).unwrap();
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let sa = SimulatedAnnealing::from_config(
+            &config,
+            RandomSelector::new(rng).option(NeighborSwap::new(&numbers)),
+        );
+
+        let solution = sa.optimize(Number::new(0, numbers[0]));
+        assert_eq!(solution.index(), 7);
+    }
+
+    #[test]
+    fn sa_from_config_matches_builder() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let from_config = SimulatedAnnealing::from_config(
+            &config(),
+            RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)),
+        );
+        let built = SimulatedAnnealing::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rng)
+            .cooling_schedule(FactorSchedule::new(100., 0.05))
+            .build();
+
+        let solution = from_config.optimize(Number::new(0, numbers[0]));
+        let expected = built.optimize(Number::new(0, numbers[0]));
+        assert_eq!(solution.index(), expected.index());
+    }
+
+    /// Return the random numbers drawn by the shakes of a run which reseeds from ```seed``` every 10 iterations.
+    fn draws_reseeded(seed: u64) -> Vec<u64> {
+        let draws = Rc::new(RefCell::new(vec![]));
+        let sa = SimulatedAnnealing::builder()
+            .selector(SequentialSelector::new().option(RecordingShake::new(draws.clone())))
+            .terminator(Terminator::builder().iterations(30).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .cooling_schedule(FactorSchedule::new(1., 0.1))
+            .reseed_every(10, move || seed)
+            .build();
+        sa.optimize(Number::new(0, 0.));
+        let draws = draws.borrow().clone();
+        draws
+    }
+
+    #[test]
+    fn sa_reseed_every() {
+        let reseeded = draws_reseeded(1);
+        assert_eq!(reseeded.len(), 30);
+        assert_eq!(draws_reseeded(1), reseeded);
+
+        // the runs agree until the first reseed, after which their streams differ
+        let other = draws_reseeded(2);
+        assert_eq!(other[..10], reseeded[..10]);
+        assert_ne!(other[10..], reseeded[10..]);
+        // reseeding restarts the stream, so it repeats with a fixed seed
+        assert_eq!(reseeded[10..20], reseeded[20..30]);
+    }
 }
@@ -1,12 +1,83 @@
 //! _variable neighborhood search_
+use std::cell::{Cell, RefCell};
+
 use crate::{
-    selectors::OperatorSelector, termination::TerminationCriteria, Evaluate, ImprovingHeuristic,
+    default_rng,
+    diagnostics::OperatorReport,
+    is_improvement,
+    selectors::{OperatorSelector, SequentialSelector},
+    termination::{IterationTerminator, TerminationCriteria},
+    throttle_callback, try_best_neighbor, try_first_improving, Callback, Evaluate,
+    ImprovementThreshold, ImprovingHeuristic, InitialGuard, Operator, OptimizeError, OptimizeState,
+    ProposalEvaluation,
 };
 
+/// Prepares the selector for the search, given the initial solution.
+type WarmUp<Solution, Selector> = Box<dyn Fn(&Selector, &Solution)>;
+
+/// Replaces the incumbent by a perturbation of the best solution.
+type Perturbation<Solution> = Box<dyn Fn(&mut OptimizeState<Solution>, &mut dyn rand::RngCore)>;
+
+/// Restart from a perturbed best solution once every neighborhood failed to improve the incumbent, see
+/// [VNSBuilder::on_exhaustion].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Restart {
+    /// Number of shakes applied to the best solution
+    pub strength: usize,
+    /// Maximum number of restarts per run
+    pub max_restarts: usize,
+}
+
+/// How a neighborhood is searched for the next candidate.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum ImprovementStrategy {
+    /// Scan the whole neighborhood, and propose the best neighbor
+    #[default]
+    BestImprovement,
+    /// Propose the first neighbor which improves on the incumbent, or the best neighbor if none does, see
+    /// [crate::Operator::find_first_improving]. Much faster on large neighborhoods, and often as good.
+    FirstImprovement,
+}
+
 /// Implementation of _variable neighborhood search_ according to [here](https://en.wikipedia.org/wiki/Variable_neighborhood_search)
 pub struct VariableNeighborhoodSearch<Solution, Selector: OperatorSelector<Solution>> {
     selector: Selector,
+    warm_up: Option<WarmUp<Solution, Selector>>,
     terminator: Box<dyn TerminationCriteria<Solution>>,
+    initial_guard: Option<InitialGuard<Solution>>,
+    on_new_best: Option<Callback<Solution>>,
+    objective_epsilon: f32,
+    stop_when_exhausted: bool,
+    max_neighbors_per_step: Option<usize>,
+    strategy: ImprovementStrategy,
+    tried_since_improvement: RefCell<Vec<bool>>,
+    max_sideways: Option<usize>,
+    sideways: Cell<usize>,
+    report: Option<OperatorReport>,
+    best_objective: Cell<Option<f32>>,
+    restart: Option<Restart>,
+    perturbation: Option<Perturbation<Solution>>,
+    restarts: Cell<usize>,
+    rng: Option<RefCell<Box<dyn rand::RngCore>>>,
+}
+
+/// Scalar hyperparameters of [VariableNeighborhoodSearch], e.g. to store experiments in a config file.
+///
+/// The selector cannot be serialized, and is passed to [VariableNeighborhoodSearch::from_config] separately.
+/// The operator identifiers merely record which operators were used.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VnsConfig {
+    /// Maximum number of iterations
+    pub iterations: usize,
+    /// Margin by which candidates must be better to count as improvements
+    pub objective_epsilon: f32,
+    /// Maximum number of neighbors evaluated per iteration, if any
+    pub max_neighbors_per_step: Option<usize>,
+    /// Whether to terminate once every operator failed to improve the incumbent
+    pub stop_when_exhausted: bool,
+    /// Identifiers of the operators
+    pub operators: Vec<String>,
 }
 
 /// Builder pattern to construct a _variable neighborhood search_ heuristic
@@ -14,9 +85,20 @@ pub struct VNSBuilder<Solution, Selector> {
     selector: Option<Selector>,
     terminator: Option<Box<dyn TerminationCriteria<Solution>>>,
     rng: Option<Box<dyn rand::RngCore>>,
+    initial_guard: Option<InitialGuard<Solution>>,
+    on_new_best: Option<Callback<Solution>>,
+    objective_epsilon: f32,
+    stop_when_exhausted: bool,
+    max_neighbors_per_step: Option<usize>,
+    strategy: ImprovementStrategy,
+    warm_up: Option<WarmUp<Solution, Selector>>,
+    max_sideways: Option<usize>,
+    report: Option<OperatorReport>,
+    restart: Option<Restart>,
+    perturbation: Option<Perturbation<Solution>>,
 }
 
-impl<'a, Solution, Selector: OperatorSelector<Solution>> VNSBuilder<Solution, Selector> {
+impl<Solution, Selector: OperatorSelector<Solution>> VNSBuilder<Solution, Selector> {
     /// Set operator selector
     pub fn selector(mut self, selector: Selector) -> Self {
         self.selector = Some(selector);
@@ -29,24 +111,172 @@ impl<'a, Solution, Selector: OperatorSelector<Solution>> VNSBuilder<Solution, Se
         self
     }
 
-    /// Set source of randomness
+    /// Set the source of randomness of the restarts, see [VNSBuilder::on_exhaustion] (default: seeded with
+    /// [crate::DEFAULT_SEED]).
+    ///
+    /// The search itself is deterministic, so without restarts the source of randomness is unused, and dropped with a
+    /// warning.
     pub fn rng<T: rand::RngCore + 'static>(mut self, rng: T) -> Self {
         self.rng = Some(Box::new(rng));
         self
     }
 
+    /// Set a guard which validates, and possibly repairs, the initial solution
+    pub fn initial_guard<F: Fn(Solution) -> Result<Solution, String> + 'static>(
+        mut self,
+        guard: F,
+    ) -> Self {
+        self.initial_guard = Some(Box::new(guard));
+        self
+    }
+
+    /// Register a hook which receives every new best solution, e.g. to persist it
+    pub fn on_new_best<F: Fn(&Solution) + 'static>(mut self, hook: F) -> Self {
+        self.on_new_best = Some(Box::new(hook));
+        self
+    }
+
+    /// Register a hook which only receives the new best solutions that improve on the last reported one by more than
+    /// ```threshold```, e.g. to keep logs meaningful on smooth problems. The search itself still tracks every improvement.
+    pub fn on_new_best_above<F: Fn(&Solution) + 'static>(
+        mut self,
+        threshold: ImprovementThreshold,
+        hook: F,
+    ) -> Self
+    where
+        Solution: Evaluate,
+    {
+        self.on_new_best = Some(throttle_callback(threshold, hook));
+        self
+    }
+
+    /// Require candidates to be better by more than ```epsilon``` to count as improvements (default: 0)
+    pub fn objective_epsilon(mut self, epsilon: f32) -> Self {
+        self.objective_epsilon = epsilon;
+        self
+    }
+
+    /// Terminate once every operator failed to improve the incumbent since the last improvement, i.e. when the
+    /// incumbent is a local optimum with respect to all neighborhoods.
+    ///
+    /// Requires the selector to report its number of operators.
+    pub fn stop_when_exhausted(mut self) -> Self {
+        self.stop_when_exhausted = true;
+        self
+    }
+
+    /// Evaluate at most ```max_neighbors``` neighbors per iteration, and propose the best of those.
+    ///
+    /// Prevents a single huge neighborhood from dominating the time budget.
+    pub fn max_neighbors_per_step(mut self, max_neighbors: usize) -> Self {
+        self.max_neighbors_per_step = Some(max_neighbors);
+        self
+    }
+
+    /// Set how neighborhoods are searched (default: [ImprovementStrategy::BestImprovement])
+    pub fn strategy(mut self, strategy: ImprovementStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Accept candidates as good as the incumbent, i.e. sideways moves, to escape plateaus, but at most ```n```
+    /// in a row: after ```n``` consecutive sideways moves, only improvements are accepted, until the next one.
+    pub fn max_sideways(mut self, n: usize) -> Self {
+        self.max_sideways = Some(n);
+        self
+    }
+
+    /// Fill ```report``` during the search, with the selections and improvements of every operator. Pass a clone,
+    /// and read the original after the run.
+    pub fn report(mut self, report: OperatorReport) -> Self {
+        self.report = Some(report);
+        self
+    }
+
     /// Construct the specified heuristic.
     pub fn build(self) -> VariableNeighborhoodSearch<Solution, Selector> {
+        let rng = match self.restart {
+            Some(_) => Some(RefCell::new(
+                self.rng.unwrap_or_else(|| default_rng("VNSBuilder")),
+            )),
+            None => {
+                if self.rng.is_some() {
+                    log::warn!("VNSBuilder: source of randomness is unused without on_exhaustion");
+                }
+                None
+            }
+        };
         VariableNeighborhoodSearch {
             selector: self.selector.expect("Did not specify an operator selector"),
+            warm_up: self.warm_up,
             terminator: self
                 .terminator
                 .expect("Did not specify termination criteria"),
+            initial_guard: self.initial_guard,
+            on_new_best: self.on_new_best,
+            objective_epsilon: self.objective_epsilon,
+            stop_when_exhausted: self.stop_when_exhausted,
+            max_neighbors_per_step: self.max_neighbors_per_step,
+            strategy: self.strategy,
+            tried_since_improvement: RefCell::new(vec![]),
+            max_sideways: self.max_sideways,
+            sideways: Cell::new(0),
+            report: self.report,
+            best_objective: Cell::new(None),
+            restart: self.restart,
+            perturbation: self.perturbation,
+            restarts: Cell::new(0),
+            rng,
         }
     }
 }
 
-impl<'a, Solution, Selector: OperatorSelector<Solution>>
+impl<Solution: Clone + 'static, Selector: OperatorSelector<Solution>>
+    VNSBuilder<Solution, Selector>
+{
+    /// Once every neighborhood failed to improve the incumbent, replace it by the best solution shaken
+    /// ```restart.strength``` times by ```perturbation```, and continue, i.e. an iterated local search. After
+    /// ```restart.max_restarts``` restarts, exhaustion is left to [VNSBuilder::stop_when_exhausted], if enabled.
+    ///
+    /// Requires the selector to report its number of operators. Shakes draw from [VNSBuilder::rng].
+    pub fn on_exhaustion<T: Operator<Solution = Solution> + 'static>(
+        mut self,
+        restart: Restart,
+        perturbation: T,
+    ) -> Self {
+        self.restart = Some(restart);
+        self.perturbation = Some(Box::new(
+            move |state: &mut OptimizeState<Solution>, rng: &mut dyn rand::RngCore| {
+                let mut solution = state.best().clone();
+                for _ in 0..restart.strength {
+                    solution = perturbation.shake(solution, rng);
+                }
+                state.set_incumbent(solution);
+            },
+        ));
+        self
+    }
+}
+
+impl<Solution: Evaluate + Clone> VNSBuilder<Solution, SequentialSelector<Solution>> {
+    /// Before the search, order the operators from most to least promising, by their average improvement of
+    /// ```samples``` incumbents, see [SequentialSelector::order_by_improvement].
+    ///
+    /// The incumbents are sampled by a random walk from the initial solution, which moves to a random neighbor,
+    /// of a random operator, every step.
+    pub fn auto_order<R: rand::RngCore + 'static>(mut self, samples: usize, rng: R) -> Self {
+        let rng = RefCell::new(rng);
+        self.warm_up = Some(Box::new(
+            move |selector: &SequentialSelector<Solution>, initial: &Solution| {
+                let incumbents = selector.random_walk(initial, samples, &mut *rng.borrow_mut());
+                selector.order_by_improvement(&incumbents);
+            },
+        ));
+        self
+    }
+}
+
+impl<Solution, Selector: OperatorSelector<Solution>>
     VariableNeighborhoodSearch<Solution, Selector>
 {
     /// Return a builder to simplify the specification.
@@ -55,6 +285,56 @@ impl<'a, Solution, Selector: OperatorSelector<Solution>>
             selector: None,
             rng: None,
             terminator: None,
+            initial_guard: None,
+            on_new_best: None,
+            objective_epsilon: 0.,
+            stop_when_exhausted: false,
+            max_neighbors_per_step: None,
+            strategy: ImprovementStrategy::default(),
+            warm_up: None,
+            max_sideways: None,
+            report: None,
+            restart: None,
+            perturbation: None,
+        }
+    }
+
+    /// Construct the heuristic from its hyperparameters, and the operators offered by ```selector```.
+    pub fn from_config(config: &VnsConfig, selector: Selector) -> Self
+    where
+        Solution: 'static,
+    {
+        let mut builder = Self::builder()
+            .selector(selector)
+            .terminator(IterationTerminator::new(config.iterations))
+            .objective_epsilon(config.objective_epsilon);
+        if let Some(max_neighbors) = config.max_neighbors_per_step {
+            builder = builder.max_neighbors_per_step(max_neighbors);
+        }
+        if config.stop_when_exhausted {
+            builder = builder.stop_when_exhausted();
+        }
+        builder.build()
+    }
+
+    /// Return the index of the operator selected in the last iteration.
+    pub fn last_selected(&self) -> Option<usize> {
+        self.selector.last_selected()
+    }
+
+    /// Return the number of restarts performed so far, see [VNSBuilder::on_exhaustion].
+    pub fn restarts(&self) -> usize {
+        self.restarts.get()
+    }
+
+    /// Test whether all operators failed to improve the incumbent since the last improvement.
+    pub fn is_exhausted(&self) -> bool {
+        match self.selector.operator_count() {
+            Some(count) => {
+                let tried = self.tried_since_improvement.borrow();
+                count > 0 && (0..count).all(|i| tried.get(i).copied().unwrap_or(false))
+            }
+            None => false,
         }
     }
 }
@@ -64,36 +344,384 @@ impl<Solution, Selector> ImprovingHeuristic<Solution>
 where
     Selector: OperatorSelector<Solution>,
 {
-    /// Accept iff candidate is better than the incumbent.
+    /// Accept iff candidate is better than the incumbent, or, if enabled, a sideways move within the limit,
+    /// see [VNSBuilder::max_sideways].
     fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool
     where
         Solution: Evaluate,
     {
-        candidate.evaluate() < incumbent.evaluate()
+        let (objective_candidate, objective_incumbent) =
+            (candidate.evaluate(), incumbent.evaluate());
+        if is_improvement(
+            objective_candidate,
+            objective_incumbent,
+            self.objective_epsilon,
+        ) {
+            self.sideways.set(0);
+            return true;
+        }
+        let sideways = !is_improvement(
+            objective_incumbent,
+            objective_candidate,
+            self.objective_epsilon,
+        );
+        match self.max_sideways {
+            Some(max_sideways) if sideways && self.sideways.get() < max_sideways => {
+                self.sideways.set(self.sideways.get() + 1);
+                true
+            }
+            _ => false,
+        }
     }
 
     /// Test whether the termination criteria are fulfilled.
     fn should_terminate(&self, incumbent: &Solution) -> bool {
-        self.terminator.terminate(incumbent)
+        self.terminator.terminate(incumbent) || (self.stop_when_exhausted && self.is_exhausted())
+    }
+
+    /// Return the progress of the termination criteria.
+    fn progress(&self) -> Option<f32> {
+        self.terminator.progress()
+    }
+
+    /// Apply the initial guard, if specified, then warm up the selector, e.g. see [VNSBuilder::auto_order].
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        let initial = crate::guard_initial(&self.initial_guard, initial)?;
+        if let Some(warm_up) = &self.warm_up {
+            warm_up(&self.selector, &initial);
+        }
+        Ok(initial)
+    }
+
+    /// Return the margin by which a candidate must be better to count as an improvement.
+    fn objective_epsilon(&self) -> f32 {
+        self.objective_epsilon
+    }
+
+    /// Pass the new best solution to the registered hook, if any.
+    fn callback_new_best(&self, best: &Solution) {
+        if let Some(hook) = &self.on_new_best {
+            hook(best);
+        }
+    }
+
+    /// Restart from a perturbed best solution if every neighborhood is exhausted and restarts are left, see
+    /// [VNSBuilder::on_exhaustion].
+    fn on_iteration_end(&self, state: &mut OptimizeState<Solution>) {
+        if let (Some(restart), Some(perturbation), Some(rng)) =
+            (&self.restart, &self.perturbation, &self.rng)
+        {
+            if self.restarts.get() < restart.max_restarts && self.is_exhausted() {
+                perturbation(state, rng.borrow_mut().as_mut());
+                self.restarts.set(self.restarts.get() + 1);
+                self.tried_since_improvement.borrow_mut().clear();
+                self.sideways.set(0);
+            }
+        }
+    }
+
+    /// Notify the termination criteria, and forget which operators were tried.
+    fn callback_candidate_accepted(&self, _candidate: &Solution, _incumbent: &Solution) {
+        self.terminator.feedback(ProposalEvaluation::Accept);
+        self.tried_since_improvement.borrow_mut().clear();
+    }
+
+    /// Notify the termination criteria, and remember that the selected operator failed.
+    fn callback_candidate_rejected(&self, _candidate: &Solution, _incumbent: &Solution) {
+        self.terminator.feedback(ProposalEvaluation::Reject);
+        if let Some(index) = self.selector.last_selected() {
+            let mut tried = self.tried_since_improvement.borrow_mut();
+            if tried.len() <= index {
+                tried.resize(index + 1, false);
+            }
+            tried[index] = true;
+        }
     }
 
     /// Select operator and get the best neighbor if ```solution```.
+    ///
+    /// Panics if the neighborhood is empty, see [VariableNeighborhoodSearch::try_propose_candidate].
     fn propose_candidate(&self, solution: Solution) -> Solution
+    where
+        Solution: Evaluate,
+    {
+        self.try_propose_candidate(solution)
+            .expect("neighborhood was empty")
+    }
+
+    /// Select operator and get the best, or first improving, neighbor of ```solution```, depending on the
+    /// [ImprovementStrategy], or ```None``` if the neighborhood is empty.
+    ///
+    /// The search then continues as if the neighborhood did not improve, i.e. with the next neighborhood.
+    fn try_propose_candidate(&self, solution: Solution) -> Option<Solution>
     where
         Solution: Evaluate,
     {
         let operator = self.selector.select(&solution);
-        operator.find_best_neighbor(solution)
+        let epsilon = self.objective_epsilon;
+        let index = self.selector.last_selected();
+        if let (Some(report), Some(index)) = (&self.report, index) {
+            report.record_selection(index, operator.name());
+            if self.best_objective.get().is_none() {
+                self.best_objective.set(Some(solution.evaluate()));
+            }
+        }
+        let candidate = match (self.max_neighbors_per_step, self.strategy) {
+            (Some(max_neighbors), ImprovementStrategy::BestImprovement) => try_best_neighbor(
+                operator
+                    .construct_neighborhood(solution)
+                    .take(max_neighbors),
+                epsilon,
+            ),
+            (Some(max_neighbors), ImprovementStrategy::FirstImprovement) => {
+                let reference = solution.evaluate();
+                try_first_improving(
+                    operator
+                        .construct_neighborhood(solution)
+                        .take(max_neighbors),
+                    reference,
+                    epsilon,
+                )
+            }
+            (None, ImprovementStrategy::BestImprovement) => {
+                operator.try_find_best_neighbor_epsilon(solution, epsilon)
+            }
+            (None, ImprovementStrategy::FirstImprovement) => {
+                operator.find_first_improving(solution, epsilon)
+            }
+        };
+
+        // attribute an improvement of the best solution to the operator
+        if let (Some(report), Some(index), Some(best), Some(candidate)) =
+            (&self.report, index, self.best_objective.get(), &candidate)
+        {
+            let objective = candidate.evaluate();
+            if is_improvement(objective, best, epsilon) {
+                report.record_improvement(index, best - objective);
+                self.best_objective.set(Some(objective));
+            }
+        }
+        candidate
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use assert_approx_eq::assert_approx_eq;
+    use rand::SeedableRng;
+
     use crate::{
-        algorithms::vns::VariableNeighborhoodSearch, selectors::SequentialSelector,
-        termination::IterationTerminator, test::*, ImprovingHeuristic,
+        algorithms::vns::{ImprovementStrategy, Restart, VariableNeighborhoodSearch},
+        diagnostics::OperatorReport,
+        operators::FnOperator,
+        selectors::SequentialSelector,
+        termination::{IterationTerminator, NoAcceptTerminator},
+        test::*,
+        Evaluate, ImprovingHeuristic, Operator, OptimizeError, ProposalEvaluation,
     };
 
+    #[test]
+    fn vns_auto_order() {
+        let numbers: Vec<f32> = (0..20).rev().map(|x| x as f32).collect();
+        // staying put never improves, while the neighbors one step away usually do
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(
+                SequentialSelector::new()
+                    .option(NeighborsUpUntilN::new(&numbers, 0))
+                    .option(NeighborsUpUntilN::new(&numbers, 1)),
+            )
+            .terminator(IterationTerminator::new(1))
+            .auto_order(5, rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+        assert_eq!(vns.selector.order(), vec![0, 1]);
+
+        let solution = vns.guard_initial(Number::new(0, numbers[0])).unwrap();
+        assert_eq!(vns.selector.order(), vec![1, 0]);
+        // the promising operator is tried first, and immediately improves
+        let candidate = vns.propose_candidate(solution);
+        assert_eq!(vns.last_selected(), Some(1));
+        assert_eq!(candidate.index(), 1);
+    }
+
+    #[test]
+    fn vns_empty_neighborhood() {
+        // the first operator is stuck at the initial solution
+        struct DeadEndAtStart {
+            operator: NeighborsUpUntilN,
+        }
+
+        impl Operator for DeadEndAtStart {
+            type Solution = Number;
+
+            fn construct_neighborhood(&self, solution: Number) -> Box<dyn Iterator<Item = Number>> {
+                match solution.index() {
+                    0 => Box::new(std::iter::empty()),
+                    _ => self.operator.construct_neighborhood(solution),
+                }
+            }
+        }
+
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let dead_end = DeadEndAtStart {
+            operator: NeighborsUpUntilN::new(&numbers, 1),
+        };
+        assert!(dead_end
+            .try_find_best_neighbor(Number::new(0, numbers[0]))
+            .is_none());
+
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(
+                SequentialSelector::new()
+                    .option(dead_end)
+                    .option(NeighborsUpUntilN::new(&numbers, 1)),
+            )
+            .terminator(IterationTerminator::new(10))
+            .build();
+
+        // the empty neighborhood is no improvement, and the next one is tried
+        assert!(vns
+            .try_propose_candidate(Number::new(0, numbers[0]))
+            .is_none());
+        let vns_solution = vns.optimize(Number::new(0, numbers[0]));
+        assert_eq!(vns_solution.index(), 2)
+    }
+
+    #[test]
+    fn vns_first_improvement() {
+        // every number is a neighbor
+        struct Anywhere {
+            numbers: Vec<f32>,
+        }
+
+        impl Operator for Anywhere {
+            type Solution = Number;
+
+            fn construct_neighborhood(&self, solution: Number) -> Box<dyn Iterator<Item = Number>> {
+                let numbers = self.numbers.clone();
+                Box::new(
+                    (0..numbers.len())
+                        .filter(move |index| *index != solution.index())
+                        .map(move |index| Number::new(index, numbers[index])),
+                )
+            }
+        }
+
+        let numbers: Vec<f32> = (0..10).rev().map(|x| x as f32).collect();
+        // return the final solution, and the number of improvements
+        let run = |strategy: ImprovementStrategy| {
+            let improvements = Rc::new(Cell::new(0));
+            let counter = improvements.clone();
+            let solution = VariableNeighborhoodSearch::builder()
+                .selector(SequentialSelector::new().option(Anywhere {
+                    numbers: numbers.clone(),
+                }))
+                .terminator(NoAcceptTerminator::new(1))
+                .strategy(strategy)
+                .on_new_best(move |_| counter.set(counter.get() + 1))
+                .build()
+                .optimize(Number::new(0, numbers[0]));
+            (solution, improvements.get())
+        };
+
+        // best improvement jumps to the optimum at once, first improvement descends step by step
+        let (best, best_improvements) = run(ImprovementStrategy::BestImprovement);
+        let (first, first_improvements) = run(ImprovementStrategy::FirstImprovement);
+        assert_eq!(best.index(), 9);
+        assert_eq!(first.index(), 9);
+        assert_eq!(best_improvements, 1);
+        assert_eq!(first_improvements, 9);
+    }
+
+    #[test]
+    fn vns_operator_report() {
+        // both operators are needed to descend to the optimum at index 7
+        let numbers = vec![9., 8., 7., 8., 5., 4., 6., 0.];
+        let report = OperatorReport::new();
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(
+                SequentialSelector::new()
+                    .option(NeighborsUpUntilN::new(&numbers, 1))
+                    .option(NeighborsUpUntilN::new(&numbers, 2)),
+            )
+            .terminator(IterationTerminator::new(20))
+            .report(report.clone())
+            .build();
+        let best = vns.optimize(Number::new(0, numbers[0]));
+        assert_eq!(best.index(), 7);
+
+        let contributions = report.contributions();
+        assert_eq!(contributions.len(), 2);
+        assert_eq!(
+            contributions.iter().map(|x| x.selections()).sum::<usize>(),
+            20
+        );
+        assert!(contributions.iter().all(|x| x.improvements() > 0));
+        assert_approx_eq!(report.total_improvement(), numbers[0] - best.evaluate());
+
+        let table = report.to_string();
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.starts_with("operator"));
+    }
+
+    #[test]
+    fn vns_max_sideways() {
+        // moves to the right, on a plateau which ends in the optimum
+        struct Right {
+            numbers: Vec<f32>,
+        }
+
+        impl Operator for Right {
+            type Solution = Number;
+
+            fn construct_neighborhood(&self, solution: Number) -> Box<dyn Iterator<Item = Number>> {
+                let index = (solution.index() + 1).min(self.numbers.len() - 1);
+                Box::new(std::iter::once(Number::new(index, self.numbers[index])))
+            }
+        }
+
+        let mut numbers = vec![3.; 10];
+        numbers.push(0.);
+        // return the number of consecutive sideways moves of every plateau walk, and the final solution
+        let walk = |max_sideways: Option<usize>| {
+            let mut builder = VariableNeighborhoodSearch::builder()
+                .selector(SequentialSelector::new().option(Right {
+                    numbers: numbers.clone(),
+                }))
+                .terminator(IterationTerminator::new(20));
+            if let Some(max_sideways) = max_sideways {
+                builder = builder.max_sideways(max_sideways);
+            }
+            let vns = builder.build();
+            let mut state = vns.start(Number::new(0, numbers[0])).unwrap();
+            let mut runs = vec![0];
+            while !state.is_terminated() {
+                match vns.step(&mut state) {
+                    ProposalEvaluation::Accept => *runs.last_mut().unwrap() += 1,
+                    _ => runs.push(0),
+                }
+            }
+            (runs, state.into_solution(vns.return_policy()))
+        };
+
+        let (runs, solution) = walk(None);
+        assert!(runs.iter().all(|run| *run == 0));
+        assert_eq!(solution.index(), 0);
+
+        let (runs, solution) = walk(Some(3));
+        assert_eq!(runs.iter().max(), Some(&3));
+        assert_eq!(solution.index(), 0);
+
+        // enough sideways moves cross the plateau
+        let (runs, solution) = walk(Some(10));
+        assert!(runs.iter().all(|run| *run <= 10));
+        assert_eq!(solution.index(), 10);
+    }
+
     #[test]
     fn vns_single_operator1() {
         let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
@@ -171,4 +799,289 @@ mod tests {
 
         assert_eq!(vns_solution.index(), 7)
     }
+
+    #[test]
+    fn vns_initial_guard_repairs() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let iterations_max = 1;
+
+        // the first number is deemed infeasible, and is repaired by jumping to the sixth
+        let repaired = Number::new(6, numbers[6]);
+        let operator = NeighborsUpUntilN::new(&numbers, 1);
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(operator))
+            .terminator(IterationTerminator::new(iterations_max))
+            .initial_guard(move |solution: Number| {
+                if solution.index() == 0 {
+                    Ok(repaired.clone())
+                } else {
+                    Ok(solution)
+                }
+            })
+            .build();
+
+        let initial_solution = Number::new(0, numbers[0]);
+        let vns_solution = vns.optimize(initial_solution);
+
+        assert_eq!(vns_solution.index(), 7)
+    }
+
+    #[test]
+    fn vns_new_best_hook() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let iterations_max = 10;
+        let recorded = Rc::new(RefCell::new(vec![]));
+        let recorder = recorded.clone();
+
+        let operator1 = NeighborsUpUntilN::new(&numbers, 1);
+        let operator2 = NeighborsUpUntilN::new(&numbers, 4);
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(
+                SequentialSelector::new()
+                    .option(operator1)
+                    .option(operator2),
+            )
+            .terminator(IterationTerminator::new(iterations_max))
+            .on_new_best(move |best: &Number| recorder.borrow_mut().push(best.evaluate()))
+            .build();
+
+        let vns_solution = vns.optimize(Number::new(0, numbers[0]));
+
+        let recorded = recorded.borrow();
+        assert_eq!(recorded.as_slice(), &[8., 7., 5., 0.]);
+        assert!(recorded.windows(2).all(|pair| pair[1] < pair[0]));
+        assert_eq!(*recorded.last().unwrap(), vns_solution.evaluate());
+    }
+
+    #[test]
+    fn vns_initial_guard_rejects() {
+        let numbers = vec![9., 8., 7.];
+        let operator = NeighborsUpUntilN::new(&numbers, 1);
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(operator))
+            .terminator(IterationTerminator::new(1))
+            .initial_guard(|_: Number| Err("infeasible".to_string()))
+            .build();
+
+        let result = vns.try_optimize(Number::new(0, numbers[0]));
+        assert_eq!(
+            result.err(),
+            Some(OptimizeError::InitialSolutionRejected(
+                "infeasible".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std-time")]
+    fn vns_try_optimize_timed_rejects() {
+        let numbers = vec![9., 8., 7.];
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(NeighborsUpUntilN::new(&numbers, 1)))
+            .terminator(IterationTerminator::new(1))
+            .initial_guard(|_: Number| Err("infeasible".to_string()))
+            .build();
+
+        let result = vns.try_optimize_timed(Number::new(0, numbers[0]));
+        assert!(matches!(
+            result,
+            Err(OptimizeError::InitialSolutionRejected(_))
+        ));
+    }
+
+    #[test]
+    fn vns_objective_epsilon() {
+        let numbers = vec![1., 1. - 1e-6];
+        let records = Rc::new(RefCell::new(vec![]));
+        let recorder = records.clone();
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(NeighborsUpUntilN::new(&numbers, 1)))
+            .terminator(IterationTerminator::new(5))
+            .objective_epsilon(1e-4)
+            .on_new_best(move |best: &Number| recorder.borrow_mut().push(best.index()))
+            .build();
+
+        let (first, second) = (Number::new(0, numbers[0]), Number::new(1, numbers[1]));
+        assert!(!vns.accept_candidate(&second, &first));
+        assert!(!vns.accept_candidate(&first, &second));
+
+        let solution = vns.optimize(first);
+        assert_eq!(solution.index(), 0);
+        assert!(records.borrow().is_empty());
+    }
+
+    #[test]
+    fn vns_no_accept_terminator() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let improvements = Rc::new(RefCell::new(0));
+        let counter = improvements.clone();
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(NeighborsUpUntilN::new(&numbers, 1)))
+            .terminator(NoAcceptTerminator::new(2))
+            .on_new_best(move |_: &Number| *counter.borrow_mut() += 1)
+            .build();
+
+        let solution = vns.optimize(Number::new(0, numbers[0]));
+        assert_eq!(solution.index(), 2);
+        assert_eq!(*improvements.borrow(), 2);
+    }
+
+    #[test]
+    fn vns_stops_when_exhausted() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let iterations = Rc::new(IterationTerminator::new(1000));
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(
+                SequentialSelector::new()
+                    .option(NeighborsUpUntilN::new(&numbers, 1))
+                    .option(NeighborsUpUntilN::new(&numbers, 2)),
+            )
+            .terminator(iterations.clone())
+            .stop_when_exhausted()
+            .build();
+
+        let solution = vns.optimize(Number::new(0, numbers[0]));
+        assert_eq!(solution.index(), 2);
+        // two improvements, then both neighborhoods fail
+        let progress =
+            crate::termination::TerminationCriteria::<Number>::progress(iterations.as_ref());
+        assert_eq!(progress, Some(4. / 1000.));
+    }
+
+    /// Every shake skips the next number.
+    struct SkipOne {
+        numbers: Vec<f32>,
+    }
+
+    impl Operator for SkipOne {
+        type Solution = Number;
+
+        fn shake(&self, solution: Number, _rng: &mut dyn rand::RngCore) -> Number {
+            let index = (solution.index() + 2).min(self.numbers.len() - 1);
+            Number::new(index, self.numbers[index])
+        }
+    }
+
+    #[test]
+    fn vns_restarts_when_exhausted() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(
+                SequentialSelector::new()
+                    .option(NeighborsUpUntilN::new(&numbers, 1))
+                    .option(NeighborsUpUntilN::new(&numbers, 2)),
+            )
+            .terminator(IterationTerminator::new(1000))
+            .stop_when_exhausted()
+            .on_exhaustion(
+                Restart {
+                    strength: 2,
+                    max_restarts: 3,
+                },
+                SkipOne {
+                    numbers: numbers.clone(),
+                },
+            )
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+
+        let mut state = vns.start(Number::new(0, numbers[0])).unwrap();
+        let mut incumbents = vec![];
+        while !state.is_terminated() {
+            vns.step(&mut state);
+            incumbents.push(state.incumbent().index());
+        }
+        // the local optimum at index 2 is shaken to index 6, from where the global optimum is found
+        assert_eq!(incumbents[..6], [1, 2, 2, 6, 7, 7]);
+        assert_eq!(state.into_solution(vns.return_policy()).index(), 7);
+        // the global optimum is shaken onto itself, until no restarts are left
+        assert_eq!(vns.restarts(), 3);
+    }
+
+    #[test]
+    fn vns_restarts_in_place() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let restarts = Rc::new(Cell::new(0));
+        let counter = restarts.clone();
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(
+                SequentialSelector::new()
+                    .option(NeighborsUpUntilN::new(&numbers, 1))
+                    .option(NeighborsUpUntilN::new(&numbers, 2)),
+            )
+            .terminator(IterationTerminator::new(1000))
+            .stop_when_exhausted()
+            .on_exhaustion(
+                Restart {
+                    strength: 2,
+                    max_restarts: 3,
+                },
+                FnOperator::new("skip one", move |solution: Number, rng| {
+                    counter.set(counter.get() + 1);
+                    SkipOne {
+                        numbers: vec![9., 8., 7., 8., 9., 7., 5., 0.],
+                    }
+                    .shake(solution, rng)
+                }),
+            )
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+
+        // the local optimum at index 2 is only left by a restart
+        let best = vns
+            .optimize_in_place(
+                Number::new(0, numbers[0]),
+                |solution: &Number| solution.index(),
+                |index: &usize| Number::new(*index, numbers[*index]),
+            )
+            .unwrap();
+        assert_eq!(best.index(), 7);
+        // three restarts of strength 2
+        assert_eq!(restarts.get(), 6);
+    }
+
+    /// Every other number is a neighbor, and drawn neighbors are counted.
+    struct CountingLargeNeighborhood {
+        numbers: Vec<f32>,
+        count: Rc<Cell<usize>>,
+    }
+
+    impl Operator for CountingLargeNeighborhood {
+        type Solution = Number;
+
+        fn construct_neighborhood(&self, solution: Number) -> Box<dyn Iterator<Item = Number>> {
+            let count = self.count.clone();
+            let neighbors: Vec<Number> = self
+                .numbers
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != solution.index())
+                .map(|(index, value)| Number::new(index, *value))
+                .collect();
+            Box::new(
+                neighbors
+                    .into_iter()
+                    .inspect(move |_| count.set(count.get() + 1)),
+            )
+        }
+    }
+
+    #[test]
+    fn vns_max_neighbors_per_step() {
+        // descending, such that the best neighbor is the last one
+        let numbers: Vec<f32> = (0..1000).map(|x| (1000 - x) as f32).collect();
+        let count = Rc::new(Cell::new(0));
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(CountingLargeNeighborhood {
+                numbers: numbers.clone(),
+                count: count.clone(),
+            }))
+            .terminator(IterationTerminator::new(1))
+            .max_neighbors_per_step(10)
+            .build();
+
+        let solution = vns.optimize(Number::new(0, numbers[0]));
+        assert_eq!(count.get(), 10);
+        assert_eq!(solution.index(), 10);
+    }
 }
@@ -0,0 +1,190 @@
+//! Attach closures to the callbacks of a heuristic, without implementing [ImprovingHeuristic] for a wrapper
+use std::marker::PhantomData;
+
+use crate::{
+    pause::HeuristicState, Callback, Evaluate, ImprovingHeuristic, OptimizeError, OptimizeState,
+    ReturnPolicy,
+};
+
+/// Observes a candidate together with the incumbent it was compared to.
+pub type CandidateCallback<Solution> = Box<dyn Fn(&Solution, &Solution)>;
+
+/// Decorates a heuristic with closures, which are called right after the corresponding callbacks of the
+/// heuristic itself. Everything else is forwarded to the heuristic.
+///
+/// Obtained by [ImprovingHeuristic::with_callbacks], e.g.
+/// ```sa.with_callbacks().on_accepted(|candidate, incumbent| ...).on_new_best(|best| ...)```.
+pub struct WithCallbacks<Solution, H> {
+    heuristic: H,
+    on_improved_best: Option<CandidateCallback<Solution>>,
+    on_new_best: Option<Callback<Solution>>,
+    on_accepted: Option<CandidateCallback<Solution>>,
+    on_rejected: Option<CandidateCallback<Solution>>,
+    solution: PhantomData<Solution>,
+}
+
+impl<Solution, H> WithCallbacks<Solution, H> {
+    pub fn new(heuristic: H) -> Self {
+        Self {
+            heuristic,
+            on_improved_best: None,
+            on_new_best: None,
+            on_accepted: None,
+            on_rejected: None,
+            solution: PhantomData,
+        }
+    }
+
+    /// Call ```callback``` with every candidate which improves on the best solution, and the incumbent
+    pub fn on_improved_best<F: Fn(&Solution, &Solution) + 'static>(mut self, callback: F) -> Self {
+        self.on_improved_best = Some(Box::new(callback));
+        self
+    }
+
+    /// Call ```callback``` with every new best solution
+    pub fn on_new_best<F: Fn(&Solution) + 'static>(mut self, callback: F) -> Self {
+        self.on_new_best = Some(Box::new(callback));
+        self
+    }
+
+    /// Call ```callback``` with every accepted candidate, and the incumbent it replaces
+    pub fn on_accepted<F: Fn(&Solution, &Solution) + 'static>(mut self, callback: F) -> Self {
+        self.on_accepted = Some(Box::new(callback));
+        self
+    }
+
+    /// Call ```callback``` with every rejected candidate, and the incumbent it was compared to
+    pub fn on_rejected<F: Fn(&Solution, &Solution) + 'static>(mut self, callback: F) -> Self {
+        self.on_rejected = Some(Box::new(callback));
+        self
+    }
+
+    /// Return the wrapped heuristic.
+    pub fn into_inner(self) -> H {
+        self.heuristic
+    }
+}
+
+impl<Solution: Evaluate, H: ImprovingHeuristic<Solution>> ImprovingHeuristic<Solution>
+    for WithCallbacks<Solution, H>
+{
+    fn propose_candidate(&self, incumbent: Solution) -> Solution {
+        self.heuristic.propose_candidate(incumbent)
+    }
+
+    fn try_propose_candidate(&self, incumbent: Solution) -> Option<Solution> {
+        self.heuristic.try_propose_candidate(incumbent)
+    }
+
+    fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool {
+        self.heuristic.accept_candidate(candidate, incumbent)
+    }
+
+    fn should_terminate(&self, incumbent: &Solution) -> bool {
+        self.heuristic.should_terminate(incumbent)
+    }
+
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        self.heuristic.guard_initial(initial)
+    }
+
+    fn objective_epsilon(&self) -> f32 {
+        self.heuristic.objective_epsilon()
+    }
+
+    fn return_policy(&self) -> ReturnPolicy {
+        self.heuristic.return_policy()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.heuristic.progress()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.heuristic.seed()
+    }
+
+    fn pause_state(&self) -> HeuristicState {
+        self.heuristic.pause_state()
+    }
+
+    fn resume_state(&self, state: &HeuristicState, iterations: usize) {
+        self.heuristic.resume_state(state, iterations)
+    }
+
+    fn callback_candidate_improved_best(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_improved_best(candidate, incumbent);
+        if let Some(callback) = &self.on_improved_best {
+            callback(candidate, incumbent);
+        }
+    }
+
+    fn callback_new_best(&self, best: &Solution) {
+        self.heuristic.callback_new_best(best);
+        if let Some(callback) = &self.on_new_best {
+            callback(best);
+        }
+    }
+
+    fn callback_candidate_accepted(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_accepted(candidate, incumbent);
+        if let Some(callback) = &self.on_accepted {
+            callback(candidate, incumbent);
+        }
+    }
+
+    fn callback_candidate_rejected(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_rejected(candidate, incumbent);
+        if let Some(callback) = &self.on_rejected {
+            callback(candidate, incumbent);
+        }
+    }
+
+    fn on_iteration_end(&self, state: &mut OptimizeState<Solution>) {
+        self.heuristic.on_iteration_end(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use rand::SeedableRng;
+
+    use crate::{
+        algorithms::local_search::LocalSearch, selectors::RandomSelector, termination::Terminator,
+        test::*, ImprovingHeuristic,
+    };
+
+    #[test]
+    fn with_callbacks_counts() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let counts: Vec<Rc<Cell<usize>>> = (0..4).map(|_| Rc::new(Cell::new(0))).collect();
+        let count = |i: usize| {
+            let count = counts[i].clone();
+            move || count.set(count.get() + 1)
+        };
+        let (improved, new_best, accepted, rejected) = (count(0), count(1), count(2), count(3));
+
+        let best = LocalSearch::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rng)
+            .build()
+            .with_callbacks()
+            .on_improved_best(move |_, _| improved())
+            .on_new_best(move |_| new_best())
+            .on_accepted(move |_, _| accepted())
+            .on_rejected(move |_, _| rejected())
+            .optimize(Number::new(0, numbers[0]));
+
+        // descend from index 0 to the local optimum at index 2, where every neighbor is rejected
+        assert_eq!(best.index(), 2);
+        let counts: Vec<usize> = counts.iter().map(|count| count.get()).collect();
+        assert_eq!(counts, vec![2, 2, 2, 98]);
+    }
+}
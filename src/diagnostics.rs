@@ -0,0 +1,1043 @@
+//! Instruments which observe a heuristic while it runs, e.g. to tune its parameters
+#[cfg(feature = "clone-audit")]
+use std::cell::Cell;
+#[cfg(feature = "profiling")]
+use std::time::{Duration, Instant};
+use std::{cell::RefCell, rc::Rc};
+
+#[cfg(feature = "profiling")]
+use crate::Operator;
+use crate::{
+    pause::HeuristicState, Evaluate, ImprovingHeuristic, IncrementalSolution, OptimizeError,
+    OptimizeState, ReturnPolicy, TryEvaluate, Validate,
+};
+
+/// Records the objective delta, candidate minus incumbent, of every rejected candidate of the wrapped heuristic.
+///
+/// For minimization, large deltas relative to the temperature of, e.g., simulated annealing, indicate that the
+/// temperature is too low. Candidates whose evaluation failed are not recorded.
+///
+/// Optimize through a reference, such that the deltas can be read after the run:
+/// ```(&recorder).optimize(initial)```, followed by ```recorder.rejected_deltas()```.
+pub struct RejectionRecorder<H> {
+    heuristic: H,
+    deltas: RefCell<Vec<f32>>,
+}
+
+impl<H> RejectionRecorder<H> {
+    pub fn new(heuristic: H) -> Self {
+        Self {
+            heuristic,
+            deltas: RefCell::new(vec![]),
+        }
+    }
+
+    /// Return the deltas of all rejected candidates so far, in order of rejection.
+    pub fn rejected_deltas(&self) -> Vec<f32> {
+        self.deltas.borrow().clone()
+    }
+
+    /// Return the wrapped heuristic.
+    pub fn into_inner(self) -> H {
+        self.heuristic
+    }
+}
+
+impl<Solution: Evaluate, H: ImprovingHeuristic<Solution>> ImprovingHeuristic<Solution>
+    for &RejectionRecorder<H>
+{
+    fn propose_candidate(&self, incumbent: Solution) -> Solution {
+        self.heuristic.propose_candidate(incumbent)
+    }
+
+    fn try_propose_candidate(&self, incumbent: Solution) -> Option<Solution> {
+        self.heuristic.try_propose_candidate(incumbent)
+    }
+
+    fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool {
+        self.heuristic.accept_candidate(candidate, incumbent)
+    }
+
+    fn should_terminate(&self, incumbent: &Solution) -> bool {
+        self.heuristic.should_terminate(incumbent)
+    }
+
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        self.heuristic.guard_initial(initial)
+    }
+
+    fn objective_epsilon(&self) -> f32 {
+        self.heuristic.objective_epsilon()
+    }
+
+    fn return_policy(&self) -> ReturnPolicy {
+        self.heuristic.return_policy()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.heuristic.progress()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.heuristic.seed()
+    }
+
+    fn pause_state(&self) -> HeuristicState {
+        self.heuristic.pause_state()
+    }
+
+    fn resume_state(&self, state: &HeuristicState, iterations: usize) {
+        self.heuristic.resume_state(state, iterations)
+    }
+
+    fn callback_candidate_improved_best(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_improved_best(candidate, incumbent)
+    }
+
+    fn callback_new_best(&self, best: &Solution) {
+        self.heuristic.callback_new_best(best)
+    }
+
+    fn callback_candidate_accepted(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_accepted(candidate, incumbent)
+    }
+
+    /// Record the delta, then forward to the wrapped heuristic.
+    fn callback_candidate_rejected(&self, candidate: &Solution, incumbent: &Solution) {
+        if candidate.try_evaluate().is_ok() {
+            self.deltas
+                .borrow_mut()
+                .push(candidate.evaluate() - incumbent.evaluate());
+        }
+        self.heuristic
+            .callback_candidate_rejected(candidate, incumbent)
+    }
+
+    fn on_iteration_end(&self, state: &mut OptimizeState<Solution>) {
+        self.heuristic.on_iteration_end(state)
+    }
+}
+
+/// Validates every candidate of the wrapped heuristic in debug builds, and panics with the message of the first
+/// invalid one, e.g. a tour which lost a city. Turns silent corruption by a buggy operator into an immediate failure.
+///
+/// Obtained by [ImprovingHeuristic::validated]. In release builds, candidates are not validated.
+pub struct Validated<H> {
+    heuristic: H,
+}
+
+impl<H> Validated<H> {
+    pub fn new(heuristic: H) -> Self {
+        Self { heuristic }
+    }
+
+    /// Return the wrapped heuristic.
+    pub fn into_inner(self) -> H {
+        self.heuristic
+    }
+}
+
+/// Panic if ```candidate``` is invalid, in debug builds only.
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+fn debug_validate<Solution: Validate>(candidate: &Solution) {
+    #[cfg(debug_assertions)]
+    if let Err(message) = candidate.validate() {
+        panic!("invalid candidate: {}", message);
+    }
+}
+
+impl<Solution: Evaluate + Validate, H: ImprovingHeuristic<Solution>> ImprovingHeuristic<Solution>
+    for Validated<H>
+{
+    fn propose_candidate(&self, incumbent: Solution) -> Solution {
+        let candidate = self.heuristic.propose_candidate(incumbent);
+        debug_validate(&candidate);
+        candidate
+    }
+
+    fn try_propose_candidate(&self, incumbent: Solution) -> Option<Solution> {
+        let candidate = self.heuristic.try_propose_candidate(incumbent);
+        if let Some(candidate) = &candidate {
+            debug_validate(candidate);
+        }
+        candidate
+    }
+
+    fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool {
+        self.heuristic.accept_candidate(candidate, incumbent)
+    }
+
+    fn should_terminate(&self, incumbent: &Solution) -> bool {
+        self.heuristic.should_terminate(incumbent)
+    }
+
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        self.heuristic.guard_initial(initial)
+    }
+
+    fn objective_epsilon(&self) -> f32 {
+        self.heuristic.objective_epsilon()
+    }
+
+    fn return_policy(&self) -> ReturnPolicy {
+        self.heuristic.return_policy()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.heuristic.progress()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.heuristic.seed()
+    }
+
+    fn pause_state(&self) -> HeuristicState {
+        self.heuristic.pause_state()
+    }
+
+    fn resume_state(&self, state: &HeuristicState, iterations: usize) {
+        self.heuristic.resume_state(state, iterations)
+    }
+
+    fn callback_candidate_improved_best(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_improved_best(candidate, incumbent)
+    }
+
+    fn callback_new_best(&self, best: &Solution) {
+        self.heuristic.callback_new_best(best)
+    }
+
+    fn callback_candidate_accepted(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_accepted(candidate, incumbent)
+    }
+
+    fn callback_candidate_rejected(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_rejected(candidate, incumbent)
+    }
+
+    fn on_iteration_end(&self, state: &mut OptimizeState<Solution>) {
+        self.heuristic.on_iteration_end(state)
+    }
+}
+
+/// Counts the clones of [Counted] solutions by the optimize loop, and warns via ```log``` at termination if the
+/// number of clones per iteration exceeds a threshold.
+///
+/// Wrap the initial solution with [CloneAudit::wrap], and optimize through a reference:
+/// ```(&audit).optimize(audit.wrap(initial))```. Only clones of the wrapper are counted, i.e. not clones which
+/// operators make of the inner solution.
+#[cfg(feature = "clone-audit")]
+pub struct CloneAudit<H> {
+    heuristic: H,
+    clones: Rc<Cell<usize>>,
+    iterations: Cell<usize>,
+    max_clones_per_iteration: f32,
+}
+
+/// A solution whose clones are counted by a [CloneAudit].
+#[cfg(feature = "clone-audit")]
+pub struct Counted<Solution> {
+    solution: Solution,
+    clones: Rc<Cell<usize>>,
+}
+
+#[cfg(feature = "clone-audit")]
+impl<H> CloneAudit<H> {
+    pub fn new(heuristic: H, max_clones_per_iteration: f32) -> Self {
+        Self {
+            heuristic,
+            clones: Rc::new(Cell::new(0)),
+            iterations: Cell::new(0),
+            max_clones_per_iteration,
+        }
+    }
+
+    /// Wrap ```solution```, such that its clones are counted.
+    pub fn wrap<Solution>(&self, solution: Solution) -> Counted<Solution> {
+        Counted {
+            solution,
+            clones: self.clones.clone(),
+        }
+    }
+
+    /// Return the number of clones so far.
+    pub fn clones(&self) -> usize {
+        self.clones.get()
+    }
+
+    /// Return the number of iterations so far.
+    pub fn iterations(&self) -> usize {
+        self.iterations.get()
+    }
+
+    /// Return the number of clones per iteration, including the clones before the first iteration.
+    pub fn clones_per_iteration(&self) -> f32 {
+        self.clones() as f32 / self.iterations().max(1) as f32
+    }
+}
+
+#[cfg(feature = "clone-audit")]
+impl<Solution> Counted<Solution> {
+    /// Return the wrapped solution.
+    pub fn into_inner(self) -> Solution {
+        self.solution
+    }
+
+    /// Return a reference to the wrapped solution.
+    pub fn solution(&self) -> &Solution {
+        &self.solution
+    }
+}
+
+#[cfg(feature = "clone-audit")]
+impl<Solution: Clone> Clone for Counted<Solution> {
+    fn clone(&self) -> Self {
+        self.clones.set(self.clones.get() + 1);
+        Self {
+            solution: self.solution.clone(),
+            clones: self.clones.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "clone-audit")]
+impl<Solution: Evaluate> Evaluate for Counted<Solution> {
+    fn evaluate(&self) -> f32 {
+        self.solution.evaluate()
+    }
+}
+
+#[cfg(feature = "clone-audit")]
+impl<Solution: Evaluate, H: ImprovingHeuristic<Solution>> ImprovingHeuristic<Counted<Solution>>
+    for &CloneAudit<H>
+{
+    /// Count the iteration, and propose with the wrapped solution.
+    fn propose_candidate(&self, incumbent: Counted<Solution>) -> Counted<Solution> {
+        self.iterations.set(self.iterations.get() + 1);
+        let clones = incumbent.clones;
+        Counted {
+            solution: self.heuristic.propose_candidate(incumbent.solution),
+            clones,
+        }
+    }
+
+    /// Count the iteration, and try to propose with the wrapped solution.
+    fn try_propose_candidate(&self, incumbent: Counted<Solution>) -> Option<Counted<Solution>> {
+        self.iterations.set(self.iterations.get() + 1);
+        let clones = incumbent.clones;
+        let solution = self.heuristic.try_propose_candidate(incumbent.solution)?;
+        Some(Counted { solution, clones })
+    }
+
+    fn accept_candidate(
+        &self,
+        candidate: &Counted<Solution>,
+        incumbent: &Counted<Solution>,
+    ) -> bool {
+        self.heuristic
+            .accept_candidate(&candidate.solution, &incumbent.solution)
+    }
+
+    /// Warn if the search terminates with too many clones per iteration.
+    fn should_terminate(&self, incumbent: &Counted<Solution>) -> bool {
+        let terminate = self.heuristic.should_terminate(&incumbent.solution);
+        if terminate && self.clones_per_iteration() > self.max_clones_per_iteration {
+            log::warn!(
+                "{} clones in {} iterations exceed the budget of {} clones per iteration",
+                self.clones(),
+                self.iterations(),
+                self.max_clones_per_iteration
+            );
+        }
+        terminate
+    }
+
+    fn guard_initial(
+        &self,
+        initial: Counted<Solution>,
+    ) -> Result<Counted<Solution>, OptimizeError> {
+        let clones = initial.clones;
+        Ok(Counted {
+            solution: self.heuristic.guard_initial(initial.solution)?,
+            clones,
+        })
+    }
+
+    fn objective_epsilon(&self) -> f32 {
+        self.heuristic.objective_epsilon()
+    }
+
+    fn return_policy(&self) -> ReturnPolicy {
+        self.heuristic.return_policy()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.heuristic.progress()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.heuristic.seed()
+    }
+
+    fn pause_state(&self) -> HeuristicState {
+        self.heuristic.pause_state()
+    }
+
+    fn resume_state(&self, state: &HeuristicState, iterations: usize) {
+        self.heuristic.resume_state(state, iterations)
+    }
+
+    fn callback_candidate_improved_best(
+        &self,
+        candidate: &Counted<Solution>,
+        incumbent: &Counted<Solution>,
+    ) {
+        self.heuristic
+            .callback_candidate_improved_best(&candidate.solution, &incumbent.solution)
+    }
+
+    fn callback_new_best(&self, best: &Counted<Solution>) {
+        self.heuristic.callback_new_best(&best.solution)
+    }
+
+    fn callback_candidate_accepted(
+        &self,
+        candidate: &Counted<Solution>,
+        incumbent: &Counted<Solution>,
+    ) {
+        self.heuristic
+            .callback_candidate_accepted(&candidate.solution, &incumbent.solution)
+    }
+
+    fn callback_candidate_rejected(
+        &self,
+        candidate: &Counted<Solution>,
+        incumbent: &Counted<Solution>,
+    ) {
+        self.heuristic
+            .callback_candidate_rejected(&candidate.solution, &incumbent.solution)
+    }
+}
+
+/// Per operator: how often it was selected, how often its candidate improved the best solution, and the total
+/// improvement of the best objective attributed to it, e.g. to prune operators which never contribute.
+///
+/// Clones refer to the same report, such that it can be read, or printed as a table, after the heuristic which
+/// fills it was consumed, see e.g. [crate::algorithms::vns::VNSBuilder::report].
+#[derive(Clone, Default)]
+pub struct OperatorReport {
+    contributions: Rc<RefCell<Vec<OperatorContribution>>>,
+}
+
+/// The contribution of a single operator to a run, see [OperatorReport].
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperatorContribution {
+    name: String,
+    selections: usize,
+    improvements: usize,
+    improvement: f32,
+}
+
+impl OperatorReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the contribution of every operator so far, by operator index.
+    pub fn contributions(&self) -> Vec<OperatorContribution> {
+        self.contributions.borrow().clone()
+    }
+
+    /// Return the improvement of the best objective attributed to all operators together.
+    pub fn total_improvement(&self) -> f32 {
+        self.contributions
+            .borrow()
+            .iter()
+            .map(|contribution| contribution.improvement)
+            .sum()
+    }
+
+    /// Record that operator ```index```, named ```name```, was selected.
+    pub(crate) fn record_selection(&self, index: usize, name: &str) {
+        let mut contributions = self.contributions.borrow_mut();
+        while contributions.len() <= index {
+            contributions.push(OperatorContribution {
+                name: String::new(),
+                selections: 0,
+                improvements: 0,
+                improvement: 0.,
+            });
+        }
+        let contribution = &mut contributions[index];
+        if contribution.name.is_empty() {
+            contribution.name = name.to_string();
+        }
+        contribution.selections += 1;
+    }
+
+    /// Attribute an improvement of the best objective by ```improvement``` to operator ```index```.
+    pub(crate) fn record_improvement(&self, index: usize, improvement: f32) {
+        if let Some(contribution) = self.contributions.borrow_mut().get_mut(index) {
+            contribution.improvements += 1;
+            contribution.improvement += improvement;
+        }
+    }
+}
+
+/// A table with a row per operator.
+impl std::fmt::Display for OperatorReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let contributions = self.contributions.borrow();
+        let width = contributions
+            .iter()
+            .map(|contribution| contribution.name.len())
+            .chain(std::iter::once("operator".len()))
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "{:<width$}  {:>10}  {:>12}  {:>11}",
+            "operator", "selections", "improvements", "improvement"
+        )?;
+        for contribution in contributions.iter() {
+            writeln!(
+                f,
+                "{:<width$}  {:>10}  {:>12}  {:>11.4}",
+                contribution.name,
+                contribution.selections,
+                contribution.improvements,
+                contribution.improvement
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl OperatorContribution {
+    /// Return the name of the operator.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return how often the operator was selected.
+    pub fn selections(&self) -> usize {
+        self.selections
+    }
+
+    /// Return how often a candidate of the operator improved the best solution.
+    pub fn improvements(&self) -> usize {
+        self.improvements
+    }
+
+    /// Return the total improvement of the best objective by candidates of the operator.
+    pub fn improvement(&self) -> f32 {
+        self.improvement
+    }
+}
+
+/// Records the convergence of a run as points (iteration, objective), e.g. of the best solution every iteration, to
+/// plot it. Feed it from a hook, e.g. [ImprovingHeuristic::on_iteration_end], or a loop over
+/// [ImprovingHeuristic::step].
+///
+/// An unbounded recorder keeps every point. A [VecRecorder::bounded] recorder keeps every point at first; whenever
+/// it is full, every other point is dropped, and from then on only every other point is recorded, i.e. the stride
+/// doubles. The points thus stay evenly spread over the whole run, within a fixed amount of memory, while the
+/// latest point is always kept. Clones refer to the same recorder.
+#[derive(Clone, Default)]
+pub struct VecRecorder {
+    state: Rc<RefCell<RecorderState>>,
+}
+
+struct RecorderState {
+    points: Vec<(usize, f32)>,
+    latest: Option<(usize, f32)>,
+    max_points: Option<usize>,
+    stride: usize,
+    recorded: usize,
+}
+
+impl Default for RecorderState {
+    fn default() -> Self {
+        Self {
+            points: vec![],
+            latest: None,
+            max_points: None,
+            stride: 1,
+            recorded: 0,
+        }
+    }
+}
+
+impl VecRecorder {
+    /// Keep every point.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most ```max_points``` points, which must be at least 2, by thinning as the run grows.
+    pub fn bounded(max_points: usize) -> Self {
+        assert!(
+            max_points >= 2,
+            "a bounded recorder needs at least 2 points"
+        );
+        let recorder = Self::new();
+        recorder.state.borrow_mut().max_points = Some(max_points);
+        recorder
+    }
+
+    /// Record ```objective``` at ```iteration```.
+    // `usize::is_multiple_of` requires Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn record(&self, iteration: usize, objective: f32) {
+        let mut state = self.state.borrow_mut();
+        let point = (iteration, objective);
+        state.latest = Some(point);
+        let stride = state.stride;
+        if state.recorded % stride == 0 {
+            state.points.push(point);
+            // leave room for the latest point
+            if state.max_points == Some(state.points.len()) {
+                let mut index = 0;
+                state.points.retain(|_| {
+                    index += 1;
+                    index % 2 == 1
+                });
+                state.stride = 2 * stride;
+            }
+        }
+        state.recorded += 1;
+    }
+
+    /// Return the recorded points in order, ending with the latest one.
+    pub fn points(&self) -> Vec<(usize, f32)> {
+        let state = self.state.borrow();
+        let mut points = state.points.clone();
+        if let Some(latest) = state.latest {
+            if points.last() != Some(&latest) {
+                points.push(latest);
+            }
+        }
+        points
+    }
+
+    /// Return the number of points held, which a bounded recorder caps.
+    pub fn len(&self) -> usize {
+        self.points().len()
+    }
+
+    /// Test whether nothing was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.state.borrow().latest.is_none()
+    }
+}
+
+/// Records every iteration of a move-based search, such that the run can be inspected, or replayed by [replay],
+/// offline, see e.g. [crate::algorithms::incremental::IncrementalLocalSearchBuilder::move_log].
+///
+/// Clones refer to the same log, such that it can be read after the heuristic which fills it was consumed.
+pub struct MoveLogger<Move> {
+    records: Rc<RefCell<Vec<MoveRecord<Move>>>>,
+}
+
+/// A single iteration of a move-based search: the index of the selected operator, the move it proposed, and whether
+/// the move was accepted.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MoveRecord<Move> {
+    pub operator: usize,
+    #[cfg_attr(feature = "serde", serde(rename = "move"))]
+    pub m: Move,
+    pub accepted: bool,
+}
+
+impl<Move> MoveLogger<Move> {
+    pub fn new() -> Self {
+        Self {
+            records: Rc::new(RefCell::new(vec![])),
+        }
+    }
+
+    /// Return the records of all iterations so far, in order.
+    pub fn records(&self) -> Vec<MoveRecord<Move>>
+    where
+        Move: Clone,
+    {
+        self.records.borrow().clone()
+    }
+
+    /// Return the number of recorded iterations.
+    pub fn len(&self) -> usize {
+        self.records.borrow().len()
+    }
+
+    /// Test whether no iteration was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.records.borrow().is_empty()
+    }
+
+    /// Replay the recorded run from ```initial```, see [replay].
+    pub fn replay<Solution: IncrementalSolution<Move = Move>>(
+        &self,
+        initial: Solution,
+    ) -> Solution {
+        replay(initial, &self.records.borrow())
+    }
+
+    /// Record that operator ```operator``` proposed ```m```, which was ```accepted``` or not.
+    pub(crate) fn record(&self, operator: usize, m: Move, accepted: bool) {
+        self.records.borrow_mut().push(MoveRecord {
+            operator,
+            m,
+            accepted,
+        });
+    }
+}
+
+impl<Move> Clone for MoveLogger<Move> {
+    fn clone(&self) -> Self {
+        Self {
+            records: self.records.clone(),
+        }
+    }
+}
+
+impl<Move> Default for MoveLogger<Move> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reconstruct the final incumbent of a logged run from its ```initial``` solution, by applying the accepted moves
+/// of ```records``` in order, see [MoveLogger].
+pub fn replay<Solution: IncrementalSolution>(
+    mut initial: Solution,
+    records: &[MoveRecord<Solution::Move>],
+) -> Solution {
+    for record in records.iter().filter(|record| record.accepted) {
+        initial.apply_move(&record.m);
+    }
+    initial
+}
+
+/// Accumulates the time spent in every operator wrapped by [OperatorProfiler::wrap], e.g. to find the bottleneck.
+///
+/// Operators are profiled where they are added to a selector, as a selector cannot observe the calls on the
+/// operators it selects: ```SequentialSelector::new().option(profiler.wrap(operator))```. Operators are indexed in
+/// the order in which they were wrapped, i.e. in option order if they are wrapped as they are added.
+///
+/// The calls ```shake```, ```destroy```, and the ```find_best_neighbor``` variants are timed. Neighborhoods
+/// are constructed lazily, hence iterating a neighborhood directly is not.
+#[cfg(feature = "profiling")]
+pub struct OperatorProfiler {
+    times: Rc<RefCell<Vec<OperatorTime>>>,
+}
+
+/// The calls to an operator, and the time spent in them.
+#[cfg(feature = "profiling")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperatorTime {
+    name: String,
+    calls: usize,
+    total: Duration,
+}
+
+/// An operator whose calls are timed by an [OperatorProfiler].
+#[cfg(feature = "profiling")]
+pub struct Profiled<Op> {
+    operator: Op,
+    index: usize,
+    times: Rc<RefCell<Vec<OperatorTime>>>,
+}
+
+#[cfg(feature = "profiling")]
+impl OperatorProfiler {
+    pub fn new() -> Self {
+        Self {
+            times: Rc::new(RefCell::new(vec![])),
+        }
+    }
+
+    /// Wrap ```operator```, such that its calls are timed.
+    pub fn wrap<Op: Operator>(&self, operator: Op) -> Profiled<Op> {
+        let mut times = self.times.borrow_mut();
+        times.push(OperatorTime {
+            name: operator.name().to_string(),
+            calls: 0,
+            total: Duration::ZERO,
+        });
+        Profiled {
+            operator,
+            index: times.len() - 1,
+            times: self.times.clone(),
+        }
+    }
+
+    /// Return the time spent in every operator so far, by operator index.
+    pub fn report(&self) -> Vec<OperatorTime> {
+        self.times.borrow().clone()
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl Default for OperatorProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl OperatorTime {
+    /// Return the name of the operator.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the number of timed calls.
+    pub fn calls(&self) -> usize {
+        self.calls
+    }
+
+    /// Return the cumulative time spent in the timed calls.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl<Op> Profiled<Op> {
+    /// Return the wrapped operator.
+    pub fn into_inner(self) -> Op {
+        self.operator
+    }
+
+    fn timed<T>(&self, call: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = call();
+        let mut times = self.times.borrow_mut();
+        times[self.index].calls += 1;
+        times[self.index].total += start.elapsed();
+        result
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl<Op: Operator> Operator for Profiled<Op> {
+    type Solution = Op::Solution;
+
+    fn name(&self) -> &str {
+        self.operator.name()
+    }
+
+    fn construct_neighborhood(
+        &self,
+        solution: Self::Solution,
+    ) -> Box<dyn Iterator<Item = Self::Solution>> {
+        self.operator.construct_neighborhood(solution)
+    }
+
+    fn find_best_neighbor(&self, solution: Self::Solution) -> Self::Solution {
+        self.timed(|| self.operator.find_best_neighbor(solution))
+    }
+
+    fn find_best_neighbor_epsilon(&self, solution: Self::Solution, epsilon: f32) -> Self::Solution {
+        self.timed(|| self.operator.find_best_neighbor_epsilon(solution, epsilon))
+    }
+
+    fn try_find_best_neighbor(&self, solution: Self::Solution) -> Option<Self::Solution> {
+        self.timed(|| self.operator.try_find_best_neighbor(solution))
+    }
+
+    fn try_find_best_neighbor_epsilon(
+        &self,
+        solution: Self::Solution,
+        epsilon: f32,
+    ) -> Option<Self::Solution> {
+        self.timed(|| {
+            self.operator
+                .try_find_best_neighbor_epsilon(solution, epsilon)
+        })
+    }
+
+    fn find_first_improving(
+        &self,
+        solution: Self::Solution,
+        epsilon: f32,
+    ) -> Option<Self::Solution> {
+        self.timed(|| self.operator.find_first_improving(solution, epsilon))
+    }
+
+    fn find_best_neighbor_filtered(
+        &self,
+        solution: Self::Solution,
+        accept: &dyn Fn(&Self::Solution) -> bool,
+    ) -> Option<Self::Solution> {
+        self.timed(|| self.operator.find_best_neighbor_filtered(solution, accept))
+    }
+
+    fn shake(&self, solution: Self::Solution, rng: &mut dyn rand::RngCore) -> Self::Solution {
+        self.timed(|| self.operator.shake(solution, rng))
+    }
+
+    fn destroy(
+        &self,
+        solution: Self::Solution,
+        size: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self::Solution {
+        self.timed(|| self.operator.destroy(solution, size, rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::{
+        algorithms::local_search::LocalSearch,
+        diagnostics::{RejectionRecorder, VecRecorder},
+        selectors::RandomSelector,
+        termination::Terminator,
+        test::*,
+        ImprovingHeuristic,
+    };
+
+    #[test]
+    fn bounded_recorder() {
+        let recorder = VecRecorder::bounded(100);
+        assert!(recorder.is_empty());
+        for iteration in 0..10_000 {
+            recorder.record(iteration, 1. / (iteration + 1) as f32);
+        }
+        let points = recorder.points();
+        assert!(points.len() <= 100);
+        assert!(points.len() > 50);
+        // the points cover the whole run, in order
+        assert_eq!(points.first().unwrap().0, 0);
+        assert_eq!(points.last().unwrap().0, 9_999);
+        assert!(points.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        let recorder = VecRecorder::new();
+        for iteration in 0..1_000 {
+            recorder.record(iteration, 0.);
+        }
+        assert_eq!(recorder.len(), 1_000);
+    }
+
+    #[test]
+    fn rejected_deltas_are_worsening() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let recorder = RejectionRecorder::new(
+            LocalSearch::builder()
+                .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(100).build())
+                .rng(rng)
+                .build(),
+        );
+        let best = (&recorder).optimize(Number::new(2, numbers[2]));
+        assert_eq!(best.index(), 2);
+
+        // the search is stuck in the local optimum at index 2, hence all neighbors are rejected
+        let deltas = recorder.rejected_deltas();
+        assert!(!deltas.is_empty());
+        assert!(deltas.iter().all(|delta| *delta > 0.));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "invalid candidate: [0, 1, 0] is not a permutation")]
+    fn validated_panics_on_invalid_candidate() {
+        use crate::{
+            algorithms::vns::VariableNeighborhoodSearch, selectors::SequentialSelector,
+            solutions::permutation::Permutation, termination::IterationTerminator, Operator,
+        };
+
+        /// Overwrites the last city by the first one, such that a city is lost.
+        struct LoseCity;
+
+        impl Operator for LoseCity {
+            type Solution = Permutation;
+
+            fn construct_neighborhood(
+                &self,
+                solution: Permutation,
+            ) -> Box<dyn Iterator<Item = Permutation>> {
+                let mut order = solution.order().to_vec();
+                let last = order.len() - 1;
+                order[last] = order[0];
+                Box::new(std::iter::once(Permutation::new(
+                    order,
+                    &|_: &Permutation| 0.,
+                )))
+            }
+        }
+
+        VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(LoseCity))
+            .terminator(IterationTerminator::new(10))
+            .build()
+            .validated()
+            .optimize(Permutation::identity(3, &|_: &Permutation| 1.));
+    }
+
+    #[cfg(feature = "clone-audit")]
+    #[test]
+    fn clone_audit_counts_loop_clones() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let iterations = 50;
+        let audit = crate::diagnostics::CloneAudit::new(
+            LocalSearch::builder()
+                .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(iterations).build())
+                .rng(rng)
+                .build(),
+            1.,
+        );
+        let best = (&audit).optimize(audit.wrap(Number::new(2, numbers[2])));
+        assert_eq!(best.solution().index(), 2);
+
+        // the best solution is cloned once upfront, and the incumbent once per iteration to propose a candidate
+        assert_eq!(audit.iterations(), iterations);
+        assert_eq!(audit.clones(), iterations + 1);
+        assert!(audit.clones_per_iteration() > 1.);
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn profiler_finds_slow_operator() {
+        use std::{cell::Cell, rc::Rc, time::Duration};
+
+        use crate::{
+            diagnostics::OperatorProfiler, operators::FnOperator, selectors::SequentialSelector,
+        };
+
+        let profiler = OperatorProfiler::new();
+        let slow = FnOperator::new("slow", |solution: Number, _rng| {
+            std::thread::sleep(Duration::from_millis(5));
+            solution
+        });
+        let fast = CountingShake::new(Rc::new(Cell::new(0)));
+        let search = LocalSearch::builder()
+            .selector(
+                SequentialSelector::new()
+                    .option(profiler.wrap(slow))
+                    .option(profiler.wrap(fast)),
+            )
+            .terminator(Terminator::builder().iterations(10).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+        search.optimize(Number::new(0, 1.));
+
+        let report = profiler.report();
+        assert_eq!(report[0].name(), "slow");
+        assert_eq!(report[0].calls() + report[1].calls(), 10);
+        assert!(report[0].total() >= Duration::from_millis(5 * report[0].calls() as u64));
+        assert!(report[0].total() > report[1].total());
+    }
+}
@@ -0,0 +1,173 @@
+//! Re-optimize problems whose instance changes over time, e.g. customers which appear in dynamic vehicle routing
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{Evaluate, ImprovingHeuristic, Operator};
+
+/// Builds the heuristic of a single run around the shared operator.
+pub type ReoptimizeFactory<Op, Solution> =
+    Box<dyn Fn(SharedOperator<Op>) -> Box<dyn ImprovingHeuristic<Solution>>>;
+
+/// An operator whose instance data, e.g. the set of cities, can change between runs.
+pub trait DynamicInstance {
+    /// A change of the instance, e.g. a new city.
+    type Update;
+
+    fn update_instance(&mut self, update: Self::Update);
+}
+
+/// An operator shared between a [Reoptimizer] and the heuristics it builds.
+///
+/// The name of the wrapped operator cannot be borrowed through the share, hence the default name is reported.
+pub struct SharedOperator<Op> {
+    operator: Rc<RefCell<Op>>,
+}
+
+/// Re-optimize from the previous best solution whenever the instance changes.
+///
+/// The operator, which holds the instance data, is kept across runs and updated in place. Only the heuristic around it
+/// is rebuilt for every run, by a factory, such that termination criteria and other state of a run start afresh.
+pub struct Reoptimizer<Op, Solution> {
+    operator: Rc<RefCell<Op>>,
+    heuristic: ReoptimizeFactory<Op, Solution>,
+}
+
+impl<Op> Clone for SharedOperator<Op> {
+    fn clone(&self) -> Self {
+        Self {
+            operator: self.operator.clone(),
+        }
+    }
+}
+
+impl<Op> Operator for SharedOperator<Op>
+where
+    Op: Operator,
+{
+    type Solution = Op::Solution;
+
+    fn construct_neighborhood(
+        &self,
+        solution: Self::Solution,
+    ) -> Box<dyn Iterator<Item = Self::Solution>> {
+        self.operator.borrow().construct_neighborhood(solution)
+    }
+
+    fn try_find_best_neighbor_epsilon(
+        &self,
+        solution: Self::Solution,
+        epsilon: f32,
+    ) -> Option<Self::Solution> {
+        self.operator
+            .borrow()
+            .try_find_best_neighbor_epsilon(solution, epsilon)
+    }
+
+    fn shake(&self, solution: Self::Solution, rng: &mut dyn rand::RngCore) -> Self::Solution {
+        self.operator.borrow().shake(solution, rng)
+    }
+
+    fn destroy(
+        &self,
+        solution: Self::Solution,
+        size: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self::Solution {
+        self.operator.borrow().destroy(solution, size, rng)
+    }
+}
+
+impl<Op, Solution> Reoptimizer<Op, Solution>
+where
+    Op: Operator<Solution = Solution> + DynamicInstance,
+{
+    /// Re-optimize with the heuristics built by ```factory```, which should use the given operator.
+    pub fn new<H, F>(operator: Op, factory: F) -> Self
+    where
+        H: ImprovingHeuristic<Solution> + 'static,
+        F: Fn(SharedOperator<Op>) -> H + 'static,
+    {
+        Self {
+            operator: Rc::new(RefCell::new(operator)),
+            heuristic: Box::new(move |operator| Box::new(factory(operator))),
+        }
+    }
+
+    /// Change the instance of the operator, e.g. after new data arrived.
+    pub fn update_instance(&self, update: Op::Update) {
+        self.operator.borrow_mut().update_instance(update)
+    }
+
+    /// Optimize the current instance, starting from ```prior_best```, e.g. the best solution of the previous run.
+    pub fn reoptimize(&self, prior_best: Solution) -> Solution
+    where
+        Solution: Clone + Evaluate,
+    {
+        let operator = SharedOperator {
+            operator: self.operator.clone(),
+        };
+        (self.heuristic)(operator).optimize(prior_best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algorithms::vns::VariableNeighborhoodSearch,
+        dynamic::{DynamicInstance, Reoptimizer},
+        selectors::SequentialSelector,
+        termination::IterationTerminator,
+        test::Number,
+        Operator,
+    };
+
+    /// Numbers on a line, where the neighbors are the adjacent numbers.
+    struct Line {
+        numbers: Vec<f32>,
+    }
+
+    impl Operator for Line {
+        type Solution = Number;
+
+        fn construct_neighborhood(&self, solution: Number) -> Box<dyn Iterator<Item = Number>> {
+            let numbers = self.numbers.clone();
+            let indices = [solution.index().checked_sub(1), Some(solution.index() + 1)];
+            Box::new(
+                indices
+                    .into_iter()
+                    .flatten()
+                    .filter_map(move |index| Some(Number::new(index, *numbers.get(index)?))),
+            )
+        }
+    }
+
+    /// Append a number to the line.
+    impl DynamicInstance for Line {
+        type Update = f32;
+
+        fn update_instance(&mut self, update: f32) {
+            self.numbers.push(update);
+        }
+    }
+
+    #[test]
+    fn reoptimize_after_update() {
+        let reoptimizer = Reoptimizer::new(
+            Line {
+                numbers: vec![5., 4., 3., 2.],
+            },
+            |operator| {
+                VariableNeighborhoodSearch::builder()
+                    .selector(SequentialSelector::new().option(operator))
+                    .terminator(IterationTerminator::new(10))
+                    .build()
+            },
+        );
+
+        let best = reoptimizer.reoptimize(Number::new(0, 5.));
+        assert_eq!(best.index(), 3);
+
+        reoptimizer.update_instance(1.);
+        let best = reoptimizer.reoptimize(best);
+        assert_eq!(best.index(), 4);
+    }
+}
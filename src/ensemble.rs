@@ -0,0 +1,108 @@
+//! Run several heuristics on the same instance and keep the best result
+use crate::{Evaluate, ImprovingHeuristic, Outcome};
+
+/// Runs several heuristics, each starting from the same initial solution.
+pub struct Ensemble<Solution> {
+    heuristics: Vec<Box<dyn ImprovingHeuristic<Solution>>>,
+}
+
+/// The outcomes of all heuristics in an [Ensemble], in the order in which the heuristics were added.
+pub struct EnsembleOutcome<Solution> {
+    outcomes: Vec<Outcome<Solution>>,
+    index_best: usize,
+}
+
+impl<Solution> Ensemble<Solution> {
+    pub fn new() -> Self {
+        Self { heuristics: vec![] }
+    }
+
+    /// Add a heuristic to the ensemble
+    pub fn heuristic<H: ImprovingHeuristic<Solution> + 'static>(mut self, heuristic: H) -> Self {
+        self.heuristics.push(Box::new(heuristic));
+        self
+    }
+
+    /// Run every heuristic, one after the other, starting from ```initial```.
+    pub fn run(self, initial: Solution) -> EnsembleOutcome<Solution>
+    where
+        Solution: Clone + Evaluate,
+    {
+        let outcomes: Vec<Outcome<Solution>> = self
+            .heuristics
+            .into_iter()
+            .map(|heuristic| heuristic.optimize_timed(initial.clone()))
+            .collect();
+
+        let mut index_best = 0;
+        for (index, outcome) in outcomes.iter().enumerate() {
+            if outcome.solution().evaluate() < outcomes[index_best].solution().evaluate() {
+                index_best = index;
+            }
+        }
+
+        EnsembleOutcome {
+            outcomes,
+            index_best,
+        }
+    }
+}
+
+impl<Solution> Default for Ensemble<Solution> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Solution> EnsembleOutcome<Solution> {
+    /// Return the outcome with the best solution.
+    ///
+    /// Panics if the ensemble contained no heuristics.
+    pub fn best(&self) -> &Outcome<Solution> {
+        &self.outcomes[self.index_best]
+    }
+
+    /// Return the index of the heuristic that found the best solution.
+    pub fn index_best(&self) -> usize {
+        self.index_best
+    }
+
+    /// Return the outcome of every heuristic.
+    pub fn outcomes(&self) -> &[Outcome<Solution>] {
+        &self.outcomes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algorithms::vns::VariableNeighborhoodSearch, ensemble::Ensemble,
+        selectors::SequentialSelector, termination::IterationTerminator, test::*,
+    };
+
+    #[test]
+    fn ensemble_returns_best() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let iterations_max = 10;
+
+        let vns_short = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(NeighborsUpUntilN::new(&numbers, 1)))
+            .terminator(IterationTerminator::new(iterations_max))
+            .build();
+        let vns_long = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(NeighborsUpUntilN::new(&numbers, 3)))
+            .terminator(IterationTerminator::new(iterations_max))
+            .build();
+
+        let outcome = Ensemble::new()
+            .heuristic(vns_short)
+            .heuristic(vns_long)
+            .run(Number::new(0, numbers[0]));
+
+        assert_eq!(outcome.outcomes().len(), 2);
+        assert_eq!(outcome.outcomes()[0].solution().index(), 2);
+        assert_eq!(outcome.outcomes()[1].solution().index(), 6);
+        assert_eq!(outcome.index_best(), 1);
+        assert_eq!(outcome.best().solution().index(), 6);
+    }
+}
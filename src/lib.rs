@@ -8,14 +8,51 @@
 //! - Variable Neighborhood Search
 //! - Simulated Annealing
 //! - Large Neighborhood Search
+//! - Iterated Local Search
+//! - Genetic Algorithm
+//! - Incremental Local Search, on solutions which are modified in place by moves
+//!
+//! ## Features
+//! - ```std-time``` (default): wall-clock based functionality, i.e. [termination::TimeTerminator],
+//!   [ImprovingHeuristic::optimize_timed], [pareto::ParetoAcceptance::optimize_multi], and [operators::TimedOperator]. Disable it with ```--no-default-features``` to build the
+//!   algorithms, operators, and iteration-based termination in environments without ```std::time::SystemTime```.
+//! - ```parallel```: search large neighborhoods in parallel with rayon, i.e. ```parallel::ParallelNeighborhood```, and
+//!   evaluate populations in parallel, i.e. ```GABuilder::parallel_evaluation```.
+//! - ```serde```: (de)serialize the hyperparameters of algorithms, e.g. [algorithms::sa::SaConfig], logged moves, i.e.
+//!   [diagnostics::MoveRecord], and paused searches, i.e. [pause::SearchState].
+//! - ```clone-audit```: count the solution clones of the optimize loop, and warn via ```log``` when there are
+//!   suspiciously many, i.e. ```diagnostics::CloneAudit```. A debugging aid, not meant for production.
+//! - ```profiling```: accumulate the time spent in every operator, i.e. ```diagnostics::OperatorProfiler```.
 //!
 //! ## Future
 //! The plan for this crate's future is to assist the user as much as possible in creating metaheuristics. This could mean that other popular
 //! metaheuristics are added, or it means that functionality is added to help creating operators.
-use std::time::{Duration, SystemTime};
+use core::time::Duration;
+use std::collections::HashMap;
+#[cfg(feature = "std-time")]
+use std::time::SystemTime;
 
+pub mod acceptance;
 pub mod algorithms;
+pub mod callbacks;
+pub mod diagnostics;
+pub mod dynamic;
+#[cfg(feature = "std-time")]
+pub mod ensemble;
+pub mod multi_start;
+pub mod operators;
+pub mod ordered_set;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod pareto;
+pub mod pause;
+pub mod penalty;
+#[cfg(feature = "std-time")]
+pub mod pipeline;
+pub mod population;
 pub mod selectors;
+pub mod solutions;
+pub mod tabu;
 pub mod termination;
 #[cfg(test)]
 mod test;
@@ -23,11 +60,96 @@ mod test;
 /// Evaluate the quality of a solution.
 pub trait Evaluate {
     fn evaluate(&self) -> f32;
+
+    /// Evaluate several solutions at once, in order.
+    ///
+    /// Population-based heuristics evaluate through this method, such that it can be overridden by
+    /// objectives which profit from vectorization.
+    fn evaluate_batch(solutions: &[Self]) -> Vec<f32>
+    where
+        Self: Sized,
+    {
+        solutions.iter().map(|x| x.evaluate()).collect()
+    }
+}
+
+/// Check the invariants of a solution, e.g. that a tour visits every city exactly once.
+///
+/// Candidates are validated in debug builds by heuristics wrapped with [ImprovingHeuristic::validated].
+pub trait Validate {
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// Evaluate the quality of a solution, where the evaluation can fail, e.g. when a simulation diverges.
+///
+/// Every [Evaluate] type is [TryEvaluate], where a NaN objective counts as failed. Fallible objectives are
+/// optimized by wrapping them in a [solutions::fallible::Fallible].
+pub trait TryEvaluate {
+    fn try_evaluate(&self) -> Result<f32, EvalError>;
+}
+
+impl<T: Evaluate + ?Sized> TryEvaluate for T {
+    fn try_evaluate(&self) -> Result<f32, EvalError> {
+        let objective = self.evaluate();
+        if objective.is_nan() {
+            Err(EvalError::NotANumber)
+        } else {
+            Ok(objective)
+        }
+    }
+}
+
+/// Evaluate the quality of a solution by objectives in strict order of priority, e.g. hard-constraint violations
+/// before cost.
+///
+/// Unlike a weighted sum, a less important objective never outweighs a more important one: it only decides
+/// between solutions whose more important objectives tie. Compare with [is_lexicographic_improvement], or order
+/// by [LexicographicObjective], the counterparts of [is_improvement] and [Objective].
+pub trait LexicographicEvaluate {
+    /// Return the objectives, most important first, where smaller is better.
+    fn objectives(&self) -> Vec<f32>;
+}
+
+/// Measure how different two solutions are, as used by diversity-aware methods.
+pub trait Distance {
+    fn distance(&self, other: &Self) -> f32;
+}
+
+/// Identify a solution by a 64-bit key, e.g. to memoize neighborhoods or to remember tabu solutions.
+///
+/// Equal solutions must have equal keys. Types which implement [std::hash::Hash] can implement this trait
+/// with [hash_key], i.e. ```fn key(&self) -> u64 { hash_key(self) }```. Features which identify solutions
+/// default to this trait, but also accept a closure instead.
+pub trait SolutionKey {
+    fn key(&self) -> u64;
+}
+
+/// Compute a key from the [std::hash::Hash] implementation of ```value```, e.g. to implement [SolutionKey].
+///
+/// Keys are stable within a build, but not across Rust versions.
+pub fn hash_key<T: std::hash::Hash + ?Sized>(value: &T) -> u64 {
+    use std::hash::Hasher;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A solution subject to constraints, which operators may violate.
+///
+/// Heuristics can retry infeasible candidates, e.g. [algorithms::sa::SABuilder::retry_until_feasible].
+pub trait Constrained {
+    fn is_feasible(&self) -> bool;
 }
 
 /// A local search operator returns the neighborhood of its argument.
 pub trait Operator {
     type Solution: Evaluate;
+
+    /// Return a human-readable name, e.g. for reporting (default: the type name).
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
     /// Construct the neighborhood of ```solution```.
     #[allow(unused_variables)]
     fn construct_neighborhood(
@@ -38,25 +160,65 @@ pub trait Operator {
     }
 
     /// Return the optimal neighbor of ```solution```.
+    ///
+    /// Panics if the neighborhood is empty, see [Operator::try_find_best_neighbor].
     fn find_best_neighbor(&self, solution: Self::Solution) -> Self::Solution {
-        // init
-        let mut winner;
-        let mut iterator = self.construct_neighborhood(solution);
-        if let Some(x) = iterator.next() {
-            winner = x
-        } else {
-            panic!("neighborhood was empty")
-        }
+        self.find_best_neighbor_epsilon(solution, 0.)
+    }
+
+    /// Return the optimal neighbor of ```solution```, where a neighbor only beats the best so far if it is better by more than ```epsilon```.
+    ///
+    /// Ties within ```epsilon``` are thus resolved in favor of the earlier neighbor. Panics if the neighborhood is empty.
+    fn find_best_neighbor_epsilon(&self, solution: Self::Solution, epsilon: f32) -> Self::Solution {
+        self.try_find_best_neighbor_epsilon(solution, epsilon)
+            .expect("neighborhood was empty")
+    }
+
+    /// Return the optimal neighbor of ```solution```, or ```None``` if the neighborhood is empty.
+    fn try_find_best_neighbor(&self, solution: Self::Solution) -> Option<Self::Solution> {
+        self.try_find_best_neighbor_epsilon(solution, 0.)
+    }
+
+    /// Return the optimal neighbor of ```solution```, as [Operator::find_best_neighbor_epsilon], or ```None``` if the neighborhood is empty.
+    fn try_find_best_neighbor_epsilon(
+        &self,
+        solution: Self::Solution,
+        epsilon: f32,
+    ) -> Option<Self::Solution> {
+        try_best_neighbor(self.construct_neighborhood(solution), epsilon)
+    }
+
+    /// Return the first neighbor of ```solution``` which improves on it by more than ```epsilon```, i.e. without
+    /// scanning the rest of the neighborhood, or, if no neighbor improves, the best neighbor.
+    ///
+    /// Returns ```None``` if the neighborhood is empty.
+    fn find_first_improving(
+        &self,
+        solution: Self::Solution,
+        epsilon: f32,
+    ) -> Option<Self::Solution> {
+        let reference = solution.evaluate();
+        try_first_improving(self.construct_neighborhood(solution), reference, epsilon)
+    }
 
-        // iterate neighborhood
-        for neighbor in iterator {
-            // if neighbor is better than the best
-            if neighbor.evaluate() < winner.evaluate() {
-                // update the best
-                winner = neighbor;
+    /// Return the best neighbor of ```solution``` for which ```accept``` holds, or ```None``` if there is none.
+    ///
+    /// Tabu search, for example, rejects tabu neighbors unless they satisfy an aspiration criterium.
+    fn find_best_neighbor_filtered(
+        &self,
+        solution: Self::Solution,
+        accept: &dyn Fn(&Self::Solution) -> bool,
+    ) -> Option<Self::Solution> {
+        let mut winner: Option<Self::Solution> = None;
+        for neighbor in self.construct_neighborhood(solution) {
+            if !accept(&neighbor) {
+                continue;
+            }
+            match &winner {
+                Some(best) if !is_improvement(neighbor.evaluate(), best.evaluate(), 0.) => {}
+                _ => winner = Some(neighbor),
             }
         }
-
         winner
     }
 
@@ -65,12 +227,113 @@ pub trait Operator {
     fn shake(&self, solution: Self::Solution, rng: &mut dyn rand::RngCore) -> Self::Solution {
         todo!()
     }
+
+    /// Destroy ```size``` elements of ```solution```, as used by destroy operators of _large neighborhood search_.
+    ///
+    /// By default, ```shake``` is applied ```size``` times, i.e. a shake is assumed to destroy a single element.
+    fn destroy(
+        &self,
+        mut solution: Self::Solution,
+        size: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self::Solution {
+        for _ in 0..size {
+            solution = self.shake(solution, rng);
+        }
+        solution
+    }
+
+    /// Evaluate every neighbor of ```solution``` and return their objectives, in neighborhood order.
+    ///
+    /// Intended as a diagnostic tool for operator authors.
+    fn neighborhood_objectives(&self, solution: Self::Solution) -> Vec<f32> {
+        self.construct_neighborhood(solution)
+            .map(|neighbor| neighbor.evaluate())
+            .collect()
+    }
+
+    /// Summarize the objectives of the neighborhood of ```solution```.
+    ///
+    /// Returns ```None``` if the neighborhood is empty.
+    fn neighborhood_summary(&self, solution: Self::Solution) -> Option<NeighborhoodSummary> {
+        NeighborhoodSummary::from_objectives(&self.neighborhood_objectives(solution))
+    }
+}
+
+/// A solution which is modified in place by moves, such that auxiliary state, e.g. a cached objective,
+/// is updated incrementally rather than recomputed.
+///
+/// Move-based heuristics, e.g. [algorithms::incremental::IncrementalLocalSearch], undo rejected moves
+/// instead of cloning the incumbent every iteration.
+pub trait IncrementalSolution: Evaluate {
+    /// A description of a modification, e.g. the positions of a swap.
+    type Move;
+
+    /// Modify the solution according to ```m```.
+    fn apply_move(&mut self, m: &Self::Move);
+
+    /// Revert ```m```, which must be the move applied last, such that the solution is exactly as before.
+    fn undo_move(&mut self, m: &Self::Move);
+}
+
+/// An operator which proposes moves, rather than neighbors, for an [IncrementalSolution].
+pub trait MoveOperator {
+    type Solution: IncrementalSolution;
+
+    /// Return a random move applicable to ```solution```.
+    fn random_move(
+        &self,
+        solution: &Self::Solution,
+        rng: &mut dyn rand::RngCore,
+    ) -> <Self::Solution as IncrementalSolution>::Move;
+
+    /// Return the attributes which ```m``` removes from and adds to ```solution```, e.g. edges, such that a
+    /// [tabu::AttributeTabuList] can forbid re-adding what was removed recently (default: none).
+    #[allow(unused_variables)]
+    fn move_attributes(
+        &self,
+        solution: &Self::Solution,
+        m: &<Self::Solution as IncrementalSolution>::Move,
+    ) -> tabu::MoveAttributes {
+        tabu::MoveAttributes::default()
+    }
+}
+
+/// Statistics on the objectives of a neighborhood.
+///
+/// Objectives which are NaN are counted, but excluded from the minimum, maximum, and mean.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeighborhoodSummary {
+    /// Smallest objective in the neighborhood, or NaN if every objective is NaN
+    pub min: f32,
+    /// Largest objective in the neighborhood, or NaN if every objective is NaN
+    pub max: f32,
+    /// Average objective of the neighborhood, or NaN if every objective is NaN
+    pub mean: f32,
+    /// Number of neighbors
+    pub count: usize,
+    /// Number of neighbors whose objective is NaN
+    pub nan_count: usize,
 }
 
 /// Solution decorated with some metadata
 pub struct Outcome<T> {
     solution: T,
-    duration: std::time::Duration,
+    duration: Duration,
+    iterations: Option<usize>,
+    evaluations: Option<usize>,
+    history: Vec<f32>,
+    seed: Option<u64>,
+}
+
+/// Builder design pattern for [Outcome], where only the solution is required.
+pub struct OutcomeBuilder<T> {
+    solution: Option<T>,
+    duration: Duration,
+    iterations: Option<usize>,
+    evaluations: Option<usize>,
+    history: Vec<f32>,
+    seed: Option<u64>,
 }
 
 /// Model of an improvement heuristic based on iterations.
@@ -85,6 +348,8 @@ pub struct Outcome<T> {
 /// 4. if ```should_terminate```(incumbent)
 ///     - return best_solution
 /// 5. else go back to (2)
+///
+/// A candidate whose evaluation fails, see [TryEvaluate], is rejected right away, and never becomes the best.
 pub trait ImprovingHeuristic<Solution> {
     /// Propose a candidate solution given the incumbent.
     ///
@@ -92,6 +357,18 @@ pub trait ImprovingHeuristic<Solution> {
     fn propose_candidate(&self, incumbent: Solution) -> Solution
     where
         Solution: Evaluate;
+
+    /// Propose a candidate solution given the incumbent, or ```None``` if there is none, e.g. because the
+    /// neighborhood of the incumbent is empty.
+    ///
+    /// The search then continues as if the incumbent itself was proposed. By default, a candidate is always proposed.
+    fn try_propose_candidate(&self, incumbent: Solution) -> Option<Solution>
+    where
+        Solution: Evaluate,
+    {
+        Some(self.propose_candidate(incumbent))
+    }
+
     /// Test whether the current candidate is accepted as the next incumbent.
     ///
     /// Usually with local search this tests whether the candidate is better than the incumbent.
@@ -100,86 +377,1563 @@ pub trait ImprovingHeuristic<Solution> {
     where
         Solution: Evaluate;
     fn should_terminate(&self, incumbent: &Solution) -> bool;
+
+    /// Validate the initial solution before the search starts, and possibly repair it.
+    ///
+    /// By default, the initial solution is used as is.
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        Ok(initial)
+    }
+
+    /// Runs the [ImprovingHeuristic::try_optimize] method, and panics if the initial solution is rejected.
     fn optimize(self, initial: Solution) -> Solution
     where
         Solution: Clone + Evaluate,
         Self: Sized,
+    {
+        match self.try_optimize(initial) {
+            Ok(solution) => solution,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Optimize, starting from ```initial```, and return the best solution found.
+    ///
+    /// Returns an error if [ImprovingHeuristic::guard_initial] rejects the initial solution.
+    fn try_optimize(self, initial: Solution) -> Result<Solution, OptimizeError>
+    where
+        Solution: Clone + Evaluate,
+        Self: Sized,
+    {
+        let mut state = self.start(initial)?;
+        loop {
+            self.step(&mut state);
+            if state.is_terminated() {
+                break;
+            }
+        }
+        Ok(state.into_solution(self.return_policy()))
+    }
+
+    /// Guard ```initial```, see [ImprovingHeuristic::guard_initial], and return the state before the first
+    /// iteration, e.g. to drive the search by [ImprovingHeuristic::step] from an external control loop.
+    fn start(&self, initial: Solution) -> Result<OptimizeState<Solution>, OptimizeError>
+    where
+        Solution: Clone,
+    {
+        Ok(OptimizeState::new(self.guard_initial(initial)?))
+    }
+
+    /// Perform a single iteration of [ImprovingHeuristic::try_optimize]: propose a candidate, accept or reject
+    /// it, and test for termination, after which [OptimizeState::is_terminated] tells whether to stop.
+    ///
+    /// Returns [ProposalEvaluation::ImprovedBest] if the candidate became the best solution, and otherwise
+    /// whether it was accepted. Stepping a terminated state simply continues the search.
+    fn step(&self, state: &mut OptimizeState<Solution>) -> ProposalEvaluation
+    where
+        Solution: Clone + Evaluate,
+    {
+        let candidate = self
+            .try_propose_candidate(state.incumbent.clone())
+            .unwrap_or_else(|| state.incumbent.clone());
+        let mut evaluation = ProposalEvaluation::Reject;
+
+        // if candidate is new best, update
+        let failed = candidate.try_evaluate().is_err();
+        if !failed
+            && is_improvement(
+                candidate.evaluate(),
+                state.best.evaluate(),
+                self.objective_epsilon(),
+            )
+        {
+            self.callback_candidate_improved_best(&candidate, &state.incumbent);
+            state.best = candidate.clone();
+            self.callback_new_best(&state.best);
+            evaluation = ProposalEvaluation::ImprovedBest;
+        }
+
+        // accept candidate as incumbent, or not ...
+        if !failed && self.accept_candidate(&candidate, &state.incumbent) {
+            self.callback_candidate_accepted(&candidate, &state.incumbent);
+            state.incumbent = candidate;
+            if evaluation == ProposalEvaluation::Reject {
+                evaluation = ProposalEvaluation::Accept;
+            }
+        } else {
+            self.callback_candidate_rejected(&candidate, &state.incumbent);
+        }
+
+        // intervene, where an incumbent which the hook sets can be a new best as well
+        state.iteration += 1;
+        self.on_iteration_end(state);
+        if state.incumbent.try_evaluate().is_ok()
+            && is_improvement(
+                state.incumbent.evaluate(),
+                state.best.evaluate(),
+                self.objective_epsilon(),
+            )
+        {
+            state.best = state.incumbent.clone();
+            self.callback_new_best(&state.best);
+        }
+
+        // test for termination
+        state.terminated = self.should_terminate(&state.incumbent);
+        evaluation
+    }
+
+    /// Optimize solutions which cannot be cloned, starting from ```initial```, and return the best solution found.
+    ///
+    /// Instead of cloning, ```snapshot``` captures a minimal descriptor of a solution, from which ```restore```
+    /// rebuilds the full solution. The tradeoff is that the incumbent is snapshotted and restored every iteration,
+    /// as proposing a candidate consumes the incumbent, while the best solution is only restored when it improves.
+    /// Iterations end with [ImprovingHeuristic::on_iteration_end], as in [ImprovingHeuristic::step].
+    fn optimize_in_place<Snapshot>(
+        self,
+        initial: Solution,
+        snapshot: impl Fn(&Solution) -> Snapshot,
+        restore: impl Fn(&Snapshot) -> Solution,
+    ) -> Result<Solution, OptimizeError>
+    where
+        Solution: Evaluate,
+        Self: Sized,
     {
         // init
-        let mut incumbent = initial;
-        let mut best_solution = incumbent.clone();
+        let incumbent = self.guard_initial(initial)?;
+        let best = restore(&snapshot(&incumbent));
+        let mut state = OptimizeState {
+            incumbent,
+            best,
+            iteration: 0,
+            parameters: HashMap::new(),
+            terminated: false,
+        };
 
         // do until termination
         loop {
-            let candidate = self.propose_candidate(incumbent.clone());
+            let snapshot_incumbent = snapshot(&state.incumbent);
+            let incumbent = std::mem::replace(&mut state.incumbent, restore(&snapshot_incumbent));
+            let candidate = self
+                .try_propose_candidate(incumbent)
+                .unwrap_or_else(|| restore(&snapshot_incumbent));
 
             // if candidate is new best, update
-            if candidate.evaluate() < best_solution.evaluate() {
-                self.callback_candidate_improved_best(&candidate, &incumbent);
-                best_solution = candidate.clone();
+            let failed = candidate.try_evaluate().is_err();
+            if !failed
+                && is_improvement(
+                    candidate.evaluate(),
+                    state.best.evaluate(),
+                    self.objective_epsilon(),
+                )
+            {
+                self.callback_candidate_improved_best(&candidate, &state.incumbent);
+                state.best = restore(&snapshot(&candidate));
+                self.callback_new_best(&state.best);
             }
 
             // accept candidate as incumbent, or not ...
-            if self.accept_candidate(&candidate, &incumbent) {
-                self.callback_candidate_accepted(&candidate, &incumbent);
-                incumbent = candidate;
+            if !failed && self.accept_candidate(&candidate, &state.incumbent) {
+                self.callback_candidate_accepted(&candidate, &state.incumbent);
+                state.incumbent = candidate;
             } else {
-                self.callback_candidate_rejected(&candidate, &incumbent);
+                self.callback_candidate_rejected(&candidate, &state.incumbent);
+            }
+
+            // intervene, where an incumbent which the hook sets can be a new best as well
+            state.iteration += 1;
+            self.on_iteration_end(&mut state);
+            if state.incumbent.try_evaluate().is_ok()
+                && is_improvement(
+                    state.incumbent.evaluate(),
+                    state.best.evaluate(),
+                    self.objective_epsilon(),
+                )
+            {
+                state.best = restore(&snapshot(&state.incumbent));
+                self.callback_new_best(&state.best);
             }
 
             // test for termination
-            if self.should_terminate(&incumbent) {
+            if self.should_terminate(&state.incumbent) {
                 break;
             }
         }
-        best_solution
+        Ok(state.into_solution(self.return_policy()))
+    }
+
+    /// Return which solution is returned once the search terminates (default: [ReturnPolicy::BestEver]).
+    fn return_policy(&self) -> ReturnPolicy {
+        ReturnPolicy::BestEver
+    }
+
+    /// Return how far the search is towards termination, between 0 and 1, if the termination criteria are bounded (default: ```None```).
+    fn progress(&self) -> Option<f32> {
+        None
+    }
+
+    /// Return the seed of the source of randomness, if the heuristic was seeded by its builder, e.g. by
+    /// [algorithms::sa::SABuilder::seed], such that a failed run can be replayed (default: ```None```).
+    fn seed(&self) -> Option<u64> {
+        None
     }
 
+    /// Return the margin by which a candidate must be better to count as an improvement (default: 0).
+    fn objective_epsilon(&self) -> f32 {
+        0.
+    }
+
+    /// Capture the internal state which a newly built heuristic needs to continue a paused search exactly, e.g. the
+    /// position of its source of randomness, see [pause::SearchState]. By default, nothing is captured.
+    fn pause_state(&self) -> pause::HeuristicState {
+        pause::HeuristicState::default()
+    }
+
+    /// Continue a search paused after ```iterations``` iterations from the internal ```state``` captured by
+    /// [ImprovingHeuristic::pause_state], e.g. in a newly built heuristic. By default, the heuristic continues with
+    /// its own internal state.
+    #[allow(unused_variables)]
+    fn resume_state(&self, state: &pause::HeuristicState, iterations: usize) {}
+
     #[allow(unused_variables)]
     fn callback_candidate_improved_best(&self, candidate: &Solution, incumbent: &Solution) {}
+    /// Called with the new best solution, right after it has been found.
+    #[allow(unused_variables)]
+    fn callback_new_best(&self, best: &Solution) {}
     #[allow(unused_variables)]
     fn callback_candidate_accepted(&self, candidate: &Solution, incumbent: &Solution) {}
     #[allow(unused_variables)]
     fn callback_candidate_rejected(&self, candidate: &Solution, incumbent: &Solution) {}
 
-    /// Runs the [ImprovingHeuristic::optimize] method and returns an [Outcome]
+    /// Called at the end of every iteration of [ImprovingHeuristic::try_optimize], right before the termination
+    /// test, to intervene in the search, e.g. to reset the incumbent to the best solution, or to inject diversity.
+    #[allow(unused_variables)]
+    fn on_iteration_end(&self, state: &mut OptimizeState<Solution>) {}
+
+    /// Decorate the heuristic with closures for its callbacks, see [callbacks::WithCallbacks].
+    fn with_callbacks(self) -> callbacks::WithCallbacks<Solution, Self>
+    where
+        Self: Sized,
+    {
+        callbacks::WithCallbacks::new(self)
+    }
+
+    /// Validate every candidate in debug builds, and panic on invalid ones, see [diagnostics::Validated].
+    fn validated(self) -> diagnostics::Validated<Self>
+    where
+        Self: Sized,
+        Solution: Validate,
+    {
+        diagnostics::Validated::new(self)
+    }
+
+    /// Runs the [ImprovingHeuristic::try_optimize_timed] method, and panics on an error.
+    #[cfg(feature = "std-time")]
     fn optimize_timed(self, solution: Solution) -> Outcome<Solution>
     where
         Solution: Clone + Evaluate,
         Self: Sized,
     {
+        match self.try_optimize_timed(solution) {
+            Ok(outcome) => outcome,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Runs the [ImprovingHeuristic::try_optimize] method and returns an [Outcome], or an error if the initial
+    /// solution is rejected, or the system clock fails.
+    #[cfg(feature = "std-time")]
+    fn try_optimize_timed(self, solution: Solution) -> Result<Outcome<Solution>, OptimizeError>
+    where
+        Solution: Clone + Evaluate,
+        Self: Sized,
+    {
+        let seed = self.seed();
         let now = SystemTime::now();
-        let solution = self.optimize(solution);
-        let duration = now.elapsed().expect("failed to time for duration");
-        let outcome = Outcome { duration, solution };
-        outcome
+        let solution = self.try_optimize(solution)?;
+        let duration = elapsed_since(now)?;
+        let builder = Outcome::builder().solution(solution).duration(duration);
+        Ok(match seed {
+            Some(seed) => builder.seed(seed).build(),
+            None => builder.build(),
+        })
     }
 }
 
-/// Evaluation of a proposed candidate
-pub enum ProposalEvaluation {
-    /// Candidate improved the incumbent
-    ImprovedBest,
-    /// Candidate was accepted
-    Accept,
-    /// Candidate was rejected
-    Reject,
+/// Forward to the boxed heuristic, such that a ```Box<dyn ImprovingHeuristic<Solution>>``` can be optimized.
+impl<Solution, H: ImprovingHeuristic<Solution> + ?Sized> ImprovingHeuristic<Solution> for Box<H> {
+    fn propose_candidate(&self, incumbent: Solution) -> Solution
+    where
+        Solution: Evaluate,
+    {
+        self.as_ref().propose_candidate(incumbent)
+    }
+
+    fn try_propose_candidate(&self, incumbent: Solution) -> Option<Solution>
+    where
+        Solution: Evaluate,
+    {
+        self.as_ref().try_propose_candidate(incumbent)
+    }
+
+    fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool
+    where
+        Solution: Evaluate,
+    {
+        self.as_ref().accept_candidate(candidate, incumbent)
+    }
+
+    fn should_terminate(&self, incumbent: &Solution) -> bool {
+        self.as_ref().should_terminate(incumbent)
+    }
+
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        self.as_ref().guard_initial(initial)
+    }
+
+    fn objective_epsilon(&self) -> f32 {
+        self.as_ref().objective_epsilon()
+    }
+
+    fn return_policy(&self) -> ReturnPolicy {
+        self.as_ref().return_policy()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.as_ref().progress()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.as_ref().seed()
+    }
+
+    fn pause_state(&self) -> pause::HeuristicState {
+        self.as_ref().pause_state()
+    }
+
+    fn resume_state(&self, state: &pause::HeuristicState, iterations: usize) {
+        self.as_ref().resume_state(state, iterations)
+    }
+
+    fn callback_candidate_improved_best(&self, candidate: &Solution, incumbent: &Solution) {
+        self.as_ref()
+            .callback_candidate_improved_best(candidate, incumbent)
+    }
+
+    fn callback_new_best(&self, best: &Solution) {
+        self.as_ref().callback_new_best(best)
+    }
+
+    fn callback_candidate_accepted(&self, candidate: &Solution, incumbent: &Solution) {
+        self.as_ref()
+            .callback_candidate_accepted(candidate, incumbent)
+    }
+
+    fn callback_candidate_rejected(&self, candidate: &Solution, incumbent: &Solution) {
+        self.as_ref()
+            .callback_candidate_rejected(candidate, incumbent)
+    }
+
+    fn on_iteration_end(&self, state: &mut OptimizeState<Solution>) {
+        self.as_ref().on_iteration_end(state)
+    }
 }
 
-impl<T> Outcome<T> {
-    pub fn new(solution: T, duration: Duration) -> Self {
-        Self { solution, duration }
+/// The state of [ImprovingHeuristic::try_optimize] between iterations, see [ImprovingHeuristic::on_iteration_end],
+/// and [ImprovingHeuristic::step].
+///
+/// The incumbent can be replaced, while the best solution is read-only: an incumbent which improves on it
+/// becomes the new best solution. The parameters persist across iterations, e.g. to adapt a parameter
+/// to the run so far.
+pub struct OptimizeState<Solution> {
+    incumbent: Solution,
+    best: Solution,
+    iteration: usize,
+    parameters: HashMap<String, f32>,
+    terminated: bool,
+}
+
+impl<Solution> OptimizeState<Solution> {
+    /// Start from ```initial```, which is both the incumbent and the best solution.
+    pub fn new(initial: Solution) -> Self
+    where
+        Solution: Clone,
+    {
+        Self {
+            best: initial.clone(),
+            incumbent: initial,
+            iteration: 0,
+            parameters: HashMap::new(),
+            terminated: false,
+        }
     }
 
-    /// Get the solution which is decorated.
-    pub fn solution(&self) -> &T {
-        &self.solution
+    /// Return the incumbent.
+    pub fn incumbent(&self) -> &Solution {
+        &self.incumbent
     }
 
-    /// Return the computation time that was needed to get this solution.
-    pub fn duration(&self) -> Duration {
-        self.duration
+    /// Return the incumbent for modification.
+    pub fn incumbent_mut(&mut self) -> &mut Solution {
+        &mut self.incumbent
+    }
+
+    /// Replace the incumbent.
+    pub fn set_incumbent(&mut self, incumbent: Solution) {
+        self.incumbent = incumbent;
+    }
+
+    /// Return the best solution so far.
+    pub fn best(&self) -> &Solution {
+        &self.best
+    }
+
+    /// Replace the incumbent by the best solution so far, i.e. intensify.
+    pub fn reset_to_best(&mut self)
+    where
+        Solution: Clone,
+    {
+        self.incumbent = self.best.clone();
+    }
+
+    /// Return the number of completed iterations.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Return the named parameters, which are empty at the start of the run.
+    pub fn parameters(&self) -> &HashMap<String, f32> {
+        &self.parameters
+    }
+
+    /// Return the named parameters for modification.
+    pub fn parameters_mut(&mut self) -> &mut HashMap<String, f32> {
+        &mut self.parameters
+    }
+
+    /// Return whether the termination criteria were fulfilled after the last iteration.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Return the solution according to ```policy```, e.g. the [ImprovingHeuristic::return_policy].
+    pub fn into_solution(self, policy: ReturnPolicy) -> Solution {
+        match policy {
+            ReturnPolicy::BestEver => self.best,
+            ReturnPolicy::FinalIncumbent => self.incumbent,
+        }
     }
 }
 
-// todo: add SA cooling schedule
+/// Which solution an [ImprovingHeuristic] returns once the search terminates.
+///
+/// The policy does not affect the search itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ReturnPolicy {
+    /// The best solution found during the search
+    #[default]
+    BestEver,
+    /// The incumbent at termination, e.g. when the objective is noisy and the annealed state is trusted
+    FinalIncumbent,
+}
+
+/// Validates, and possibly repairs, the initial solution of a heuristic.
+///
+/// Returns an error message if the initial solution is rejected.
+pub type InitialGuard<Solution> = Box<dyn Fn(Solution) -> Result<Solution, String>>;
+
+/// Observes a solution, e.g. to log or persist it.
+pub type Callback<Solution> = Box<dyn Fn(&Solution)>;
+
+/// The margin by which a new best solution must improve on the last reported one to be reported to a hook,
+/// see e.g. [algorithms::sa::SABuilder::on_new_best_above].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImprovementThreshold {
+    /// Improve by more than a fixed amount
+    Absolute(f32),
+    /// Improve by more than a fraction of the magnitude of the last reported objective
+    Relative(f32),
+}
+
+impl ImprovementThreshold {
+    /// Test whether ```objective``` improves on ```reference``` by more than the threshold.
+    pub fn exceeded(&self, objective: f32, reference: f32) -> bool {
+        let margin = match self {
+            ImprovementThreshold::Absolute(margin) => *margin,
+            ImprovementThreshold::Relative(fraction) => fraction * reference.abs(),
+        };
+        is_improvement(objective, reference, margin)
+    }
+}
+
+/// Identifies a solution by a key, overriding its [SolutionKey].
+pub type KeyFn<Solution> = Box<dyn Fn(&Solution) -> u64>;
+
+/// Constructs a solution from scratch, e.g. randomly or greedily.
+pub type Constructor<Solution> = Box<dyn Fn(&mut dyn rand::RngCore) -> Solution>;
+
+/// Errors that can occur while optimizing
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptimizeError {
+    /// The initial solution was rejected
+    InitialSolutionRejected(String),
+    /// The system clock failed while timing the search, e.g. because it was set back
+    ClockFailed(String),
+}
+
+/// Return the time passed since ```start```, or an error if the system clock was set back in the meantime.
+#[cfg(feature = "std-time")]
+pub(crate) fn elapsed_since(start: SystemTime) -> Result<Duration, OptimizeError> {
+    start
+        .elapsed()
+        .map_err(|error| OptimizeError::ClockFailed(error.to_string()))
+}
+
+impl std::fmt::Display for OptimizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptimizeError::InitialSolutionRejected(message) => {
+                write!(f, "initial solution was rejected: {}", message)
+            }
+            OptimizeError::ClockFailed(message) => write!(f, "system clock failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for OptimizeError {}
+
+/// Errors that can occur while evaluating a solution
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    /// The objective is not a number
+    NotANumber,
+    /// The evaluation failed, e.g. because a simulation diverged
+    Failed(String),
+}
+
+impl std::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::NotANumber => write!(f, "objective is not a number"),
+            EvalError::Failed(message) => write!(f, "evaluation failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// Apply ```guard```, if any, to the initial solution.
+pub(crate) fn guard_initial<Solution>(
+    guard: &Option<InitialGuard<Solution>>,
+    initial: Solution,
+) -> Result<Solution, OptimizeError> {
+    match guard {
+        Some(guard) => guard(initial).map_err(OptimizeError::InitialSolutionRejected),
+        None => Ok(initial),
+    }
+}
+
+/// Wrap ```hook```, such that it only observes the solutions which improve on the last observed one by more than
+/// ```threshold```. The first solution is always observed.
+pub(crate) fn throttle_callback<Solution: Evaluate, F: Fn(&Solution) + 'static>(
+    threshold: ImprovementThreshold,
+    hook: F,
+) -> Callback<Solution> {
+    let reported: std::cell::Cell<Option<f32>> = std::cell::Cell::new(None);
+    Box::new(move |best: &Solution| {
+        let objective = best.evaluate();
+        let exceeded = match reported.get() {
+            Some(reference) => threshold.exceeded(objective, reference),
+            None => true,
+        };
+        if exceeded {
+            reported.set(Some(objective));
+            hook(best);
+        }
+    })
+}
+
+/// Derive an independent random number generator for ```stream``` from ```seed```.
+///
+/// Operators drawing from their own stream are unaffected by how many numbers other operators draw.
+pub(crate) fn stream_rng(seed: u64, stream: u64) -> rand::rngs::StdRng {
+    use rand::SeedableRng;
+    // splitmix64 finalizer, such that neighboring streams are decorrelated
+    let mut z = seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    rand::rngs::StdRng::seed_from_u64(z ^ (z >> 31))
+}
+
+/// Seed of the source of randomness of heuristics which were built without one.
+///
+/// Such that quick experiments run, and are reproducible, builders fall back to a [rand::rngs::StdRng] seeded with
+/// this seed, and warn via ```log```. Every run then draws the same numbers, so production code should set a seed,
+/// or a source of randomness, explicitly, e.g. by [algorithms::sa::SABuilder::seed].
+pub const DEFAULT_SEED: u64 = 0;
+
+/// Warn that ```builder``` falls back to [DEFAULT_SEED].
+pub(crate) fn warn_default_seed(builder: &str) {
+    log::warn!("{builder}: no source of randomness specified, falling back to seed {DEFAULT_SEED}");
+}
+
+/// Return the source of randomness of ```builder``` which was given none, see [DEFAULT_SEED].
+pub(crate) fn default_rng(builder: &str) -> Box<dyn rand::RngCore> {
+    use rand::SeedableRng;
+    warn_default_seed(builder);
+    Box::new(rand::rngs::StdRng::seed_from_u64(DEFAULT_SEED))
+}
+
+/// The source of randomness of a search: either seeded by its builder, in which case its position in the stream can be
+/// captured when the search is paused, see [pause::HeuristicState], or given by the user.
+///
+/// A seeded source draws the same numbers as a [rand::rngs::StdRng] with the same seed.
+pub(crate) enum SearchRng {
+    Seeded(Box<rand_chacha::ChaCha12Rng>),
+    Custom(Box<dyn rand::RngCore>),
+}
+
+impl SearchRng {
+    pub(crate) fn seeded(seed: u64) -> Self {
+        use rand::SeedableRng;
+        Self::Seeded(Box::new(rand_chacha::ChaCha12Rng::seed_from_u64(seed)))
+    }
+
+    /// Return the position in the stream, if seeded.
+    pub(crate) fn state(&self) -> Option<pause::RngState> {
+        match self {
+            Self::Seeded(rng) => Some(pause::RngState {
+                seed: rng.get_seed(),
+                stream: rng.get_stream(),
+                word_pos: rng.get_word_pos(),
+            }),
+            Self::Custom(_) => None,
+        }
+    }
+
+    /// Continue the seeded stream at the position of ```state```, replacing the current source of randomness.
+    pub(crate) fn restore(&mut self, state: &pause::RngState) {
+        use rand::SeedableRng;
+        let mut rng = rand_chacha::ChaCha12Rng::from_seed(state.seed);
+        rng.set_stream(state.stream);
+        rng.set_word_pos(state.word_pos);
+        *self = Self::Seeded(Box::new(rng));
+    }
+}
+
+impl rand::RngCore for SearchRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Seeded(rng) => rng.next_u32(),
+            Self::Custom(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Seeded(rng) => rng.next_u64(),
+            Self::Custom(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Seeded(rng) => rng.fill_bytes(dest),
+            Self::Custom(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            Self::Seeded(rng) => rng.try_fill_bytes(dest),
+            Self::Custom(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+/// Replaces a random number generator by a freshly seeded one, see [SearchRng::seeded], at a fixed interval of
+/// iterations.
+pub(crate) struct Reseed {
+    every: usize,
+    seed: Box<dyn Fn() -> u64>,
+    iterations: std::cell::Cell<usize>,
+}
+
+impl Reseed {
+    pub(crate) fn new<F: Fn() -> u64 + 'static>(every: usize, seed: F) -> Self {
+        assert!(every > 0, "reseed interval must be positive");
+        Self {
+            every,
+            seed: Box::new(seed),
+            iterations: std::cell::Cell::new(0),
+        }
+    }
+
+    /// Count an iteration about to start, and reseed ```rng``` if ```every``` iterations have passed since the last reseed.
+    // `usize::is_multiple_of` requires Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    pub(crate) fn tick(&self, rng: &std::cell::RefCell<SearchRng>) {
+        let iterations = self.iterations.get();
+        if iterations > 0 && iterations % self.every == 0 {
+            rng.replace(SearchRng::seeded((self.seed)()));
+        }
+        self.iterations.set(iterations + 1);
+    }
+
+    /// Continue counting after ```iterations``` iterations, e.g. of a paused search.
+    pub(crate) fn resume(&self, iterations: usize) {
+        self.iterations.set(iterations);
+    }
+}
+
+/// Shakes a solution, e.g. with an operator and a source of randomness.
+pub(crate) type Shake<'a, Solution> = &'a mut dyn FnMut(Solution) -> Solution;
+
+/// Retries a shake until it produces a feasible candidate, see [Constrained].
+pub(crate) struct FeasibilityRetry<Solution> {
+    max_retries: usize,
+    is_feasible: fn(&Solution) -> bool,
+    duplicate: fn(&Solution) -> Solution,
+}
+
+impl<Solution> FeasibilityRetry<Solution> {
+    pub(crate) fn new(max_retries: usize) -> Self
+    where
+        Solution: Constrained + Clone,
+    {
+        Self {
+            max_retries,
+            is_feasible: Solution::is_feasible,
+            duplicate: Solution::clone,
+        }
+    }
+
+    /// Shake ```incumbent``` until the candidate is feasible, with up to ```max_retries``` retries, and return
+    /// the ```incumbent``` itself if all of them fail.
+    pub(crate) fn shake(&self, incumbent: Solution, shake: Shake<Solution>) -> Solution {
+        for _ in 0..=self.max_retries {
+            let candidate = shake((self.duplicate)(&incumbent));
+            if (self.is_feasible)(&candidate) {
+                return candidate;
+            }
+        }
+        incumbent
+    }
+}
+
+/// Return the best of ```neighbors```, where a neighbor only beats the best so far if it is better by more than ```epsilon```,
+/// or ```None``` if ```neighbors``` is empty.
+pub(crate) fn try_best_neighbor<Solution: Evaluate>(
+    mut neighbors: impl Iterator<Item = Solution>,
+    epsilon: f32,
+) -> Option<Solution> {
+    // init
+    let mut winner = neighbors.next()?;
+
+    // iterate neighborhood
+    for neighbor in neighbors {
+        // if neighbor is better than the best
+        if is_improvement(neighbor.evaluate(), winner.evaluate(), epsilon) {
+            // update the best
+            winner = neighbor;
+        }
+    }
+
+    Some(winner)
+}
+
+/// Return the first neighbor which improves on ```reference``` by more than ```epsilon```, or otherwise the best neighbor.
+pub(crate) fn try_first_improving<Solution: Evaluate>(
+    mut neighbors: impl Iterator<Item = Solution>,
+    reference: f32,
+    epsilon: f32,
+) -> Option<Solution> {
+    let mut winner = neighbors.next()?;
+    if is_improvement(winner.evaluate(), reference, epsilon) {
+        return Some(winner);
+    }
+
+    for neighbor in neighbors {
+        if is_improvement(neighbor.evaluate(), reference, epsilon) {
+            return Some(neighbor);
+        }
+        if is_improvement(neighbor.evaluate(), winner.evaluate(), epsilon) {
+            winner = neighbor;
+        }
+    }
+
+    Some(winner)
+}
+
+/// Test whether objective ```candidate``` is better than ```reference``` by more than ```epsilon```.
+///
+/// Guards against float jitter, where two practically equal solutions differ in the last bits.
+/// NaN is the worst possible objective: a NaN candidate is never an improvement, while any other
+/// candidate improves on a NaN reference.
+pub fn is_improvement(candidate: f32, reference: f32, epsilon: f32) -> bool {
+    match (candidate.is_nan(), reference.is_nan()) {
+        (true, _) => false,
+        (false, true) => true,
+        (false, false) => candidate < reference - epsilon,
+    }
+}
+
+/// An objective value with a total order, where smaller is better and NaN is the worst possible objective.
+///
+/// All NaNs are equal, and so are 0 and -0, such that ties are preserved, e.g. by stable sorts.
+#[derive(Clone, Copy, Debug)]
+pub struct Objective(pub f32);
+
+impl PartialEq for Objective {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Objective {}
+
+impl PartialOrd for Objective {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Objective {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.0.is_nan(), other.0.is_nan()) {
+            (true, true) => std::cmp::Ordering::Equal,
+            (true, false) => std::cmp::Ordering::Greater,
+            (false, true) => std::cmp::Ordering::Less,
+            (false, false) => self.0.partial_cmp(&other.0).expect("neither is NaN"),
+        }
+    }
+}
+
+/// Test whether objectives ```candidate``` are lexicographically better than ```reference```, where objectives
+/// within ```epsilon``` of each other tie, and the next objective decides.
+///
+/// Every objective is compared like [is_improvement], e.g. NaN is the worst possible objective.
+/// If the shorter objectives tie with the start of the longer ones, the shorter are better, like the order of
+/// [LexicographicObjective].
+pub fn is_lexicographic_improvement(candidate: &[f32], reference: &[f32], epsilon: f32) -> bool {
+    for (candidate, reference) in candidate.iter().zip(reference) {
+        if is_improvement(*candidate, *reference, epsilon) {
+            return true;
+        }
+        if is_improvement(*reference, *candidate, epsilon) {
+            return false;
+        }
+    }
+    candidate.len() < reference.len()
+}
+
+/// Objective values in order of priority, with a total lexicographic order, where smaller is better, see [Objective].
+///
+/// A shorter vector which is a prefix of a longer one is smaller.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LexicographicObjective(Vec<Objective>);
+
+impl LexicographicObjective {
+    pub fn new(objectives: &[f32]) -> Self {
+        Self(
+            objectives
+                .iter()
+                .map(|objective| Objective(*objective))
+                .collect(),
+        )
+    }
+}
+
+/// Evaluation of a proposed candidate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProposalEvaluation {
+    /// Candidate improved the incumbent
+    ImprovedBest,
+    /// Candidate was accepted
+    Accept,
+    /// Candidate was rejected
+    Reject,
+}
+
+impl<T> Outcome<T> {
+    pub fn new(solution: T, duration: Duration) -> Self {
+        Self::builder()
+            .solution(solution)
+            .duration(duration)
+            .build()
+    }
+
+    pub fn builder() -> OutcomeBuilder<T> {
+        OutcomeBuilder {
+            solution: None,
+            duration: Duration::ZERO,
+            iterations: None,
+            evaluations: None,
+            history: vec![],
+            seed: None,
+        }
+    }
+
+    /// Get the solution which is decorated.
+    pub fn solution(&self) -> &T {
+        &self.solution
+    }
+
+    /// Return the computation time that was needed to get this solution.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Return the number of iterations performed, if known.
+    pub fn iterations(&self) -> Option<usize> {
+        self.iterations
+    }
+
+    /// Return the number of evaluated solutions, if known.
+    pub fn evaluations(&self) -> Option<usize> {
+        self.evaluations
+    }
+
+    /// Return the recorded objectives, e.g. of the best solution per iteration. Empty if none were recorded.
+    pub fn history(&self) -> &[f32] {
+        &self.history
+    }
+
+    /// Return the seed of the source of randomness, if known, see [ImprovingHeuristic::seed].
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
+
+impl<T> OutcomeBuilder<T> {
+    /// Build the outcome
+    pub fn build(self) -> Outcome<T> {
+        Outcome {
+            solution: self.solution.expect("No solution specified"),
+            duration: self.duration,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            history: self.history,
+            seed: self.seed,
+        }
+    }
+
+    /// Set the solution
+    pub fn solution(mut self, solution: T) -> Self {
+        self.solution = Some(solution);
+        self
+    }
+
+    /// Set the computation time (default: 0)
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Set the number of iterations performed
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Set the number of evaluated solutions
+    pub fn evaluations(mut self, evaluations: usize) -> Self {
+        self.evaluations = Some(evaluations);
+        self
+    }
+
+    /// Set the recorded objectives
+    pub fn history(mut self, history: Vec<f32>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Set the seed of the source of randomness
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// Return the outcome with the best, i.e. smallest, objective, e.g. among restarts, where the first of tied outcomes
+/// wins and NaN is the worst objective, see [Objective]. Returns ```None``` if ```outcomes``` is empty.
+pub fn best_outcome<T: Evaluate>(outcomes: &[Outcome<T>]) -> Option<&Outcome<T>> {
+    outcomes
+        .iter()
+        .min_by_key(|outcome| Objective(outcome.solution.evaluate()))
+}
+
+/// Sort ```outcomes``` from the best to the worst objective, like [best_outcome]. Tied outcomes keep their order.
+pub fn sort_outcomes_by_quality<T: Evaluate>(outcomes: &mut [Outcome<T>]) {
+    outcomes.sort_by_cached_key(|outcome| Objective(outcome.solution.evaluate()));
+}
+
+/// Return the outcome with the shortest duration, where the first of tied outcomes wins. Returns ```None``` if
+/// ```outcomes``` is empty.
+pub fn fastest_outcome<T>(outcomes: &[Outcome<T>]) -> Option<&Outcome<T>> {
+    outcomes.iter().min_by_key(|outcome| outcome.duration)
+}
+
+impl NeighborhoodSummary {
+    /// Summarize a collection of objectives. Returns ```None``` if ```objectives``` is empty.
+    pub fn from_objectives(objectives: &[f32]) -> Option<Self> {
+        if objectives.is_empty() {
+            return None;
+        }
+
+        let numbers: Vec<f32> = objectives.iter().copied().filter(|x| !x.is_nan()).collect();
+        let count = objectives.len();
+        let nan_count = count - numbers.len();
+        if numbers.is_empty() {
+            return Some(Self {
+                min: f32::NAN,
+                max: f32::NAN,
+                mean: f32::NAN,
+                count,
+                nan_count,
+            });
+        }
+
+        let min = numbers.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = numbers.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let mean = numbers.iter().sum::<f32>() / numbers.len() as f32;
+        Some(Self {
+            min,
+            max,
+            mean,
+            count,
+            nan_count,
+        })
+    }
+}
+
+// todo: add SA cooling schedule
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use crate::{
+        best_outcome, fastest_outcome, is_lexicographic_improvement, solutions::fallible::Fallible,
+        sort_outcomes_by_quality, test::*, EvalError, Evaluate, ImprovingHeuristic,
+        LexicographicEvaluate, LexicographicObjective, NeighborhoodSummary, Objective, Operator,
+        OptimizeState, Outcome, TryEvaluate,
+    };
+
+    #[test]
+    #[cfg(feature = "std-time")]
+    fn clock_failure_is_an_error() {
+        use std::time::{Duration, SystemTime};
+
+        use crate::{elapsed_since, OptimizeError};
+
+        // a start in the future behaves as if the clock was set back
+        let start = SystemTime::now() + Duration::from_secs(3600);
+        assert!(matches!(
+            elapsed_since(start),
+            Err(OptimizeError::ClockFailed(_))
+        ));
+        assert!(elapsed_since(SystemTime::now()).is_ok());
+    }
+
+    #[test]
+    fn neighborhood_objectives_match_neighbors() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let operator = NeighborsUpUntilN::new(&numbers, 2);
+        let solution = Number::new(3, numbers[3]);
+
+        let expected: Vec<f32> = operator
+            .construct_neighborhood(solution.clone())
+            .map(|neighbor| neighbor.evaluate())
+            .collect();
+        let objectives = operator.neighborhood_objectives(solution.clone());
+        assert_eq!(objectives, expected);
+        assert_eq!(objectives, vec![8., 7.]);
+
+        let summary = operator.neighborhood_summary(solution).unwrap();
+        assert_eq!(summary.count, 2);
+        assert_approx_eq!(summary.min, 7.);
+        assert_approx_eq!(summary.max, 8.);
+        assert_approx_eq!(summary.mean, 7.5);
+        assert_eq!(summary.nan_count, 0);
+    }
+
+    #[test]
+    fn neighborhood_summary_skips_nan() {
+        let summary = NeighborhoodSummary::from_objectives(&[3., f32::NAN, 1.]).unwrap();
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.nan_count, 1);
+        assert_approx_eq!(summary.min, 1.);
+        assert_approx_eq!(summary.max, 3.);
+        assert_approx_eq!(summary.mean, 2.);
+
+        let summary = NeighborhoodSummary::from_objectives(&[f32::NAN]).unwrap();
+        assert_eq!(summary.nan_count, 1);
+        assert!(summary.min.is_nan() && summary.max.is_nan() && summary.mean.is_nan());
+        assert_eq!(NeighborhoodSummary::from_objectives(&[]), None);
+    }
+
+    #[test]
+    fn best_neighbor_filtered() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let operator = NeighborsUpUntilN::new(&numbers, 2);
+        let solution = Number::new(3, numbers[3]);
+
+        // the best neighbor is filtered out
+        let neighbor = operator
+            .find_best_neighbor_filtered(solution.clone(), &|neighbor| neighbor.index() == 1)
+            .unwrap();
+        assert_eq!(neighbor.index(), 1);
+
+        let neighbor = operator
+            .find_best_neighbor_filtered(solution.clone(), &|neighbor| neighbor.index() > 10);
+        assert!(neighbor.is_none());
+
+        let neighbor = operator
+            .find_best_neighbor_filtered(solution, &|_| true)
+            .unwrap();
+        assert_eq!(neighbor.index(), 5);
+    }
+
+    #[test]
+    fn best_neighbor_epsilon() {
+        let numbers = vec![1., 3., 1. - 1e-6];
+        let operator = NeighborsUpUntilN::new(&numbers, 1);
+        let solution = Number::new(1, numbers[1]);
+
+        let neighbor = operator.find_best_neighbor(solution.clone());
+        assert_eq!(neighbor.index(), 2);
+        let neighbor = operator.find_best_neighbor_epsilon(solution, 1e-4);
+        assert_eq!(neighbor.index(), 0);
+    }
+
+    #[test]
+    fn evaluate_batch_default() {
+        let numbers: Vec<Number> = [3., 1., 2.]
+            .iter()
+            .enumerate()
+            .map(|(index, value)| Number::new(index, *value))
+            .collect();
+        let expected: Vec<f32> = numbers.iter().map(|x| x.evaluate()).collect();
+        assert_eq!(Number::evaluate_batch(&numbers), expected);
+    }
+
+    /// Deliberately not [Clone], as if it held a resource.
+    struct Handle {
+        index: usize,
+        value: f32,
+    }
+
+    impl Evaluate for Handle {
+        fn evaluate(&self) -> f32 {
+            self.value
+        }
+    }
+
+    struct HandleNeighbors {
+        numbers: Vec<f32>,
+    }
+
+    impl Operator for HandleNeighbors {
+        type Solution = Handle;
+
+        fn construct_neighborhood(&self, solution: Handle) -> Box<dyn Iterator<Item = Handle>> {
+            let neighbors: Vec<Handle> = [solution.index.wrapping_sub(1), solution.index + 1]
+                .into_iter()
+                .filter(|index| *index < self.numbers.len())
+                .map(|index| Handle {
+                    index,
+                    value: self.numbers[index],
+                })
+                .collect();
+            Box::new(neighbors.into_iter())
+        }
+    }
+
+    #[test]
+    fn optimize_in_place_without_clone() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let vns = crate::algorithms::vns::VariableNeighborhoodSearch::builder()
+            .selector(
+                crate::selectors::SequentialSelector::new().option(HandleNeighbors {
+                    numbers: numbers.clone(),
+                }),
+            )
+            .terminator(crate::termination::IterationTerminator::new(10))
+            .build();
+
+        let initial = Handle {
+            index: 0,
+            value: numbers[0],
+        };
+        let best = vns
+            .optimize_in_place(
+                initial,
+                |handle: &Handle| handle.index,
+                |index: &usize| Handle {
+                    index: *index,
+                    value: numbers[*index],
+                },
+            )
+            .unwrap();
+        assert_eq!(best.index, 2);
+        assert_eq!(best.evaluate(), 7.);
+    }
+
+    /// A simulation which diverges for odd indices.
+    #[derive(Clone)]
+    struct Simulation {
+        index: usize,
+    }
+
+    impl TryEvaluate for Simulation {
+        fn try_evaluate(&self) -> Result<f32, EvalError> {
+            if self.index % 2 == 1 {
+                Err(EvalError::Failed("diverged".to_string()))
+            } else {
+                Ok(-(self.index as f32))
+            }
+        }
+    }
+
+    /// Walk away from the optimum at index 0, and accept every step.
+    struct WalkAway {
+        numbers: Vec<f32>,
+        iterations: usize,
+        reset_every: Option<usize>,
+        incumbents: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl ImprovingHeuristic<Number> for WalkAway {
+        fn propose_candidate(&self, incumbent: Number) -> Number {
+            let index = incumbent.index() + 1;
+            Number::new(index, self.numbers[index])
+        }
+
+        fn accept_candidate(&self, _candidate: &Number, _incumbent: &Number) -> bool {
+            true
+        }
+
+        fn should_terminate(&self, incumbent: &Number) -> bool {
+            self.incumbents.borrow_mut().push(incumbent.index());
+            self.incumbents.borrow().len() >= self.iterations
+        }
+
+        fn return_policy(&self) -> crate::ReturnPolicy {
+            crate::ReturnPolicy::FinalIncumbent
+        }
+
+        #[allow(clippy::manual_is_multiple_of)]
+        fn on_iteration_end(&self, state: &mut OptimizeState<Number>) {
+            if let Some(every) = self.reset_every {
+                if state.iteration() % every == 0 {
+                    state.reset_to_best();
+                    *state
+                        .parameters_mut()
+                        .entry("resets".to_string())
+                        .or_default() += 1.;
+                    assert_eq!(
+                        state.parameters()["resets"],
+                        (state.iteration() / every) as f32
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn on_iteration_end_resets_to_best() {
+        let numbers: Vec<f32> = (0..10).map(|x| x as f32).collect();
+        let walk = |reset_every| {
+            let incumbents = Rc::new(RefCell::new(vec![]));
+            let heuristic = WalkAway {
+                numbers: numbers.clone(),
+                iterations: 9,
+                reset_every,
+                incumbents: incumbents.clone(),
+            };
+            let last = heuristic.optimize(Number::new(0, numbers[0]));
+            let incumbents = incumbents.borrow().clone();
+            assert_eq!(incumbents.last(), Some(&last.index()));
+            incumbents
+        };
+
+        assert_eq!(walk(None), (1..10).collect::<Vec<usize>>());
+        // every third iteration, the walk starts over from the optimum
+        assert_eq!(walk(Some(3)), vec![1, 2, 0, 1, 2, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn step_matches_optimize() {
+        use crate::{
+            algorithms::sa::{FactorSchedule, SimulatedAnnealing},
+            selectors::SequentialSelector,
+            termination::IterationTerminator,
+            ProposalEvaluation,
+        };
+
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0., 3., 1., 4., 6.];
+        let iterations = 30;
+        let build = |iterations_max| {
+            SimulatedAnnealing::builder()
+                .selector(SequentialSelector::new().option(NeighborSwap::new(&numbers)))
+                .terminator(Box::new(IterationTerminator::new(iterations_max)))
+                .cooling_schedule(FactorSchedule::new(5., 0.9))
+                .seed(7)
+                .build()
+        };
+
+        let optimized = build(iterations).optimize(Number::new(0, numbers[0]));
+
+        // drive the search by hand, well before its own termination
+        let sa = build(10 * iterations);
+        let mut state = sa.start(Number::new(0, numbers[0])).unwrap();
+        let mut improvements = 0;
+        for _ in 0..iterations {
+            if sa.step(&mut state) == ProposalEvaluation::ImprovedBest {
+                improvements += 1;
+            }
+            assert!(!state.is_terminated());
+        }
+        assert_eq!(state.iteration(), iterations);
+        assert!(improvements > 0);
+        let stepped = state.into_solution(sa.return_policy());
+        assert_eq!(stepped.index(), optimized.index());
+        assert_eq!(stepped.evaluate(), optimized.evaluate());
+
+        // the state terminates along with the heuristic
+        let sa = build(iterations);
+        let mut state = sa.start(Number::new(0, numbers[0])).unwrap();
+        let mut steps = 0;
+        while !state.is_terminated() {
+            sa.step(&mut state);
+            steps += 1;
+        }
+        assert_eq!(steps, iterations);
+    }
+
+    /// Propose the indices 1, 2, ... in turn, and accept whatever is proposed.
+    struct AcceptAll {
+        proposals: Cell<usize>,
+        accepted: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl ImprovingHeuristic<Fallible<Simulation>> for AcceptAll {
+        fn propose_candidate(&self, _incumbent: Fallible<Simulation>) -> Fallible<Simulation> {
+            self.proposals.set(self.proposals.get() + 1);
+            Fallible::new(Simulation {
+                index: self.proposals.get(),
+            })
+        }
+
+        fn accept_candidate(
+            &self,
+            _candidate: &Fallible<Simulation>,
+            _incumbent: &Fallible<Simulation>,
+        ) -> bool {
+            true
+        }
+
+        fn should_terminate(&self, _incumbent: &Fallible<Simulation>) -> bool {
+            self.proposals.get() >= 5
+        }
+
+        fn callback_candidate_accepted(
+            &self,
+            candidate: &Fallible<Simulation>,
+            _incumbent: &Fallible<Simulation>,
+        ) {
+            self.accepted.borrow_mut().push(candidate.solution().index);
+        }
+    }
+
+    #[test]
+    fn failed_evaluations_are_rejected() {
+        let accepted = Rc::new(RefCell::new(vec![]));
+        let heuristic = AcceptAll {
+            proposals: Cell::new(0),
+            accepted: accepted.clone(),
+        };
+        let best = heuristic
+            .optimize(Fallible::new(Simulation { index: 0 }))
+            .into_inner();
+        // odd indices are proposed, and would be best, but are never accepted nor best
+        assert_eq!(*accepted.borrow(), vec![2, 4]);
+        assert_eq!(best.index, 4);
+
+        let failed = Fallible::new(Simulation { index: 1 });
+        assert!(failed.evaluate().is_nan());
+        assert!(failed.try_evaluate().is_err());
+        assert_eq!(
+            failed.error(),
+            Some(&EvalError::Failed("diverged".to_string()))
+        );
+    }
+
+    #[test]
+    fn outcome_builder() {
+        let duration = core::time::Duration::from_millis(5);
+        let outcome = Outcome::builder().solution(Number::new(0, 1.)).build();
+        assert_eq!(outcome.solution().index(), 0);
+        assert!(outcome.duration().is_zero());
+        assert_eq!(outcome.iterations(), None);
+        assert_eq!(outcome.evaluations(), None);
+        assert!(outcome.history().is_empty());
+
+        let outcome = Outcome::builder()
+            .solution(Number::new(1, 0.))
+            .duration(duration)
+            .iterations(10)
+            .evaluations(25)
+            .history(vec![2., 1., 0.])
+            .build();
+        assert_eq!(outcome.duration(), duration);
+        assert_eq!(outcome.iterations(), Some(10));
+        assert_eq!(outcome.evaluations(), Some(25));
+        assert_eq!(outcome.history(), &[2., 1., 0.]);
+
+        let outcome = Outcome::new(Number::new(2, 0.), duration);
+        assert_eq!(outcome.duration(), duration);
+        assert_eq!(outcome.iterations(), None);
+    }
+
+    #[test]
+    fn select_outcomes() {
+        let outcome = |index: usize, objective: f32, millis: u64| {
+            Outcome::new(
+                Number::new(index, objective),
+                core::time::Duration::from_millis(millis),
+            )
+        };
+        let mut outcomes = vec![
+            outcome(0, 3., 20),
+            outcome(1, f32::NAN, 1),
+            outcome(2, 1., 50),
+            outcome(3, 2., 5),
+            outcome(4, 1., 10),
+        ];
+        assert_eq!(best_outcome(&outcomes).unwrap().solution().index(), 2);
+        assert_eq!(fastest_outcome(&outcomes).unwrap().solution().index(), 1);
+
+        sort_outcomes_by_quality(&mut outcomes);
+        let order: Vec<usize> = outcomes.iter().map(|x| x.solution().index()).collect();
+        assert_eq!(order, vec![2, 4, 3, 0, 1]);
+
+        let empty: Vec<Outcome<Number>> = vec![];
+        assert!(best_outcome(&empty).is_none());
+        assert!(fastest_outcome(&empty).is_none());
+    }
+
+    /// Violations of hard constraints, and cost.
+    struct Schedule {
+        violations: f32,
+        cost: f32,
+    }
+
+    impl LexicographicEvaluate for Schedule {
+        fn objectives(&self) -> Vec<f32> {
+            vec![self.violations, self.cost]
+        }
+    }
+
+    #[test]
+    fn lexicographic_comparison() {
+        let cheap = Schedule {
+            violations: 1.,
+            cost: 10.,
+        };
+        let expensive = Schedule {
+            violations: 1.,
+            cost: 20.,
+        };
+        let feasible = Schedule {
+            violations: 0.,
+            cost: 1000.,
+        };
+
+        // the violations tie, hence the cost decides
+        assert!(is_lexicographic_improvement(
+            &cheap.objectives(),
+            &expensive.objectives(),
+            0.
+        ));
+        assert!(!is_lexicographic_improvement(
+            &expensive.objectives(),
+            &cheap.objectives(),
+            0.
+        ));
+        // fewer violations win, however expensive
+        assert!(is_lexicographic_improvement(
+            &feasible.objectives(),
+            &cheap.objectives(),
+            0.
+        ));
+        // within epsilon, the violations tie as well
+        assert!(is_lexicographic_improvement(&[0.5, 10.], &[0.4, 20.], 0.2));
+        assert!(!is_lexicographic_improvement(
+            &cheap.objectives(),
+            &cheap.objectives(),
+            0.
+        ));
+
+        let mut schedules = [expensive, feasible, cheap];
+        schedules.sort_by_key(|schedule| LexicographicObjective::new(&schedule.objectives()));
+        let costs: Vec<f32> = schedules.iter().map(|schedule| schedule.cost).collect();
+        assert_eq!(costs, vec![1000., 10., 20.]);
+        assert!(
+            LexicographicObjective::new(&[1., f32::NAN]) > LexicographicObjective::new(&[1., 5.])
+        );
+    }
+
+    #[test]
+    fn lexicographic_different_lengths() {
+        // a prefix is better, unless an objective it has decides
+        let pairs: [(&[f32], &[f32]); 4] = [
+            (&[1.], &[1., 5.]),
+            (&[], &[1.]),
+            (&[0.], &[1., 0.]),
+            (&[1., 5.], &[2.]),
+        ];
+        for (better, worse) in pairs {
+            assert!(is_lexicographic_improvement(better, worse, 0.));
+            assert!(!is_lexicographic_improvement(worse, better, 0.));
+            assert!(LexicographicObjective::new(better) < LexicographicObjective::new(worse));
+        }
+        // within epsilon, the first objective ties, hence the prefix is still better
+        assert!(is_lexicographic_improvement(&[1.1], &[1., 5.], 0.2));
+        assert!(!is_lexicographic_improvement(&[1.], &[1.], 0.));
+    }
+
+    #[test]
+    fn nan_is_never_best() {
+        let numbers = [f32::NAN, 3., 2., f32::NAN];
+        let operator = NeighborsUpUntilN::new(&numbers, 1);
+
+        // the NaN neighbor comes first, but does not block better neighbors
+        let neighbor = operator.find_best_neighbor(Number::new(1, numbers[1]));
+        assert_eq!(neighbor.index(), 2);
+        let neighbor = operator
+            .find_best_neighbor_filtered(Number::new(1, numbers[1]), &|_| true)
+            .unwrap();
+        assert_eq!(neighbor.index(), 2);
+
+        // a search which starts next to NaN neighbors never moves to, nor returns, them
+        let vns = crate::algorithms::vns::VariableNeighborhoodSearch::builder()
+            .selector(crate::selectors::SequentialSelector::new().option(operator))
+            .terminator(crate::termination::IterationTerminator::new(5))
+            .build();
+        let solution = vns.optimize(Number::new(2, numbers[2]));
+        assert_eq!(solution.index(), 2);
+
+        let mut objectives = [Objective(f32::NAN), Objective(1.), Objective(-1.)];
+        objectives.sort();
+        assert_eq!(objectives[0], Objective(-1.));
+        assert_eq!(objectives[2], Objective(f32::NAN));
+        assert_eq!(Objective(0.), Objective(-0.));
+    }
+}
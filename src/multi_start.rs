@@ -0,0 +1,349 @@
+//! Restart a heuristic from several initial solutions, possibly in parallel
+use std::thread;
+
+use rand::Rng;
+
+use crate::{is_improvement, stream_rng, Evaluate, ImprovingHeuristic};
+
+/// Builds the heuristic of a single restart from its seed.
+pub type HeuristicFactory<Solution> =
+    Box<dyn Fn(u64) -> Box<dyn ImprovingHeuristic<Solution>> + Send + Sync>;
+
+/// Constructs the initial solution of a single restart.
+pub type SharedConstructor<Solution> =
+    Box<dyn Fn(&mut dyn rand::RngCore) -> Solution + Send + Sync>;
+
+/// Runs independent restarts, each with a fresh initial solution and a fresh heuristic.
+///
+/// Every restart ```i``` derives its own seed from the master seed, which seeds both the constructor and
+/// the heuristic. Hence, the result of a restart does not depend on whether, or how, the restarts are run in parallel.
+pub struct MultiStart<Solution> {
+    constructor: SharedConstructor<Solution>,
+    heuristic: HeuristicFactory<Solution>,
+    restarts: usize,
+    master_seed: u64,
+}
+
+/// Builder design pattern for [MultiStart].
+pub struct MultiStartBuilder<Solution> {
+    constructor: Option<SharedConstructor<Solution>>,
+    heuristic: Option<HeuristicFactory<Solution>>,
+    restarts: usize,
+    master_seed: u64,
+}
+
+/// The final solutions of all restarts of a [MultiStart], in the order of the restarts.
+pub struct MultiStartOutcome<Solution> {
+    solutions: Vec<Solution>,
+    index_best: usize,
+}
+
+/// Summary statistics over the final objectives of all restarts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RestartStatistics {
+    mean: f32,
+    std: f32,
+    min: f32,
+    confidence_interval: (f32, f32),
+}
+
+/// Run all restarts of ```multi_start``` and summarize their final objectives.
+///
+/// The 95% confidence interval on the mean is bootstrapped from ```resamples``` resamples, which are drawn
+/// from a stream of the master seed, such that the statistics are reproducible.
+pub fn restart_statistics<Solution: Clone + Evaluate>(
+    multi_start: MultiStart<Solution>,
+    resamples: usize,
+) -> RestartStatistics {
+    let mut rng = stream_rng(multi_start.master_seed, multi_start.restarts as u64);
+    multi_start.run().statistics(resamples, &mut rng)
+}
+
+impl<Solution> MultiStart<Solution> {
+    pub fn builder() -> MultiStartBuilder<Solution> {
+        MultiStartBuilder {
+            constructor: None,
+            heuristic: None,
+            restarts: 1,
+            master_seed: 0,
+        }
+    }
+
+    /// Return the seed of restart ```index```.
+    pub fn seed(&self, index: usize) -> u64 {
+        rand::RngCore::next_u64(&mut stream_rng(self.master_seed, index as u64))
+    }
+
+    /// Run all restarts, one after the other.
+    pub fn run(self) -> MultiStartOutcome<Solution>
+    where
+        Solution: Clone + Evaluate,
+    {
+        let solutions = (0..self.restarts).map(|i| self.restart(i)).collect();
+        MultiStartOutcome::new(solutions)
+    }
+
+    /// Run all restarts on ```threads``` threads. The outcome equals that of [MultiStart::run].
+    pub fn run_parallel(self, threads: usize) -> MultiStartOutcome<Solution>
+    where
+        Solution: Clone + Evaluate + Send,
+    {
+        let threads = threads.max(1);
+        let this = &self;
+        let mut results: Vec<(usize, Solution)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|thread| {
+                    scope.spawn(move || {
+                        (thread..this.restarts)
+                            .step_by(threads)
+                            .map(|i| (i, this.restart(i)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("restart panicked"))
+                .collect()
+        });
+        results.sort_by_key(|(i, _)| *i);
+        MultiStartOutcome::new(results.into_iter().map(|(_, x)| x).collect())
+    }
+
+    fn restart(&self, index: usize) -> Solution
+    where
+        Solution: Clone + Evaluate,
+    {
+        let seed = self.seed(index);
+        let initial = (self.constructor)(&mut stream_rng(seed, 0));
+        (self.heuristic)(seed).optimize(initial)
+    }
+}
+
+impl<Solution> MultiStartBuilder<Solution> {
+    /// Build the configured multi start
+    pub fn build(self) -> MultiStart<Solution> {
+        MultiStart {
+            constructor: self.constructor.expect("No constructor specified"),
+            heuristic: self.heuristic.expect("No heuristic specified"),
+            restarts: self.restarts,
+            master_seed: self.master_seed,
+        }
+    }
+
+    /// Set the construction heuristic which creates the initial solution of every restart
+    pub fn constructor<F>(mut self, constructor: F) -> Self
+    where
+        F: Fn(&mut dyn rand::RngCore) -> Solution + Send + Sync + 'static,
+    {
+        self.constructor = Some(Box::new(constructor));
+        self
+    }
+
+    /// Set the factory which builds the heuristic of a restart, given the seed of that restart
+    pub fn heuristic<H, F>(mut self, factory: F) -> Self
+    where
+        H: ImprovingHeuristic<Solution> + 'static,
+        F: Fn(u64) -> H + Send + Sync + 'static,
+    {
+        self.heuristic = Some(Box::new(move |seed| Box::new(factory(seed))));
+        self
+    }
+
+    /// Set the number of restarts (default: 1)
+    pub fn restarts(mut self, restarts: usize) -> Self {
+        self.restarts = restarts;
+        self
+    }
+
+    /// Set the seed from which the seeds of all restarts are derived (default: 0)
+    pub fn master_seed(mut self, seed: u64) -> Self {
+        self.master_seed = seed;
+        self
+    }
+}
+
+impl<Solution: Evaluate> MultiStartOutcome<Solution> {
+    fn new(solutions: Vec<Solution>) -> Self {
+        let mut index_best = 0;
+        for (index, solution) in solutions.iter().enumerate() {
+            if is_improvement(solution.evaluate(), solutions[index_best].evaluate(), 0.) {
+                index_best = index;
+            }
+        }
+        Self {
+            solutions,
+            index_best,
+        }
+    }
+
+    /// Return the best solution over all restarts.
+    ///
+    /// Panics if there were no restarts.
+    pub fn best(&self) -> &Solution {
+        &self.solutions[self.index_best]
+    }
+
+    /// Return the index of the restart that found the best solution.
+    pub fn index_best(&self) -> usize {
+        self.index_best
+    }
+
+    /// Return the final solution of every restart.
+    pub fn solutions(&self) -> &[Solution] {
+        &self.solutions
+    }
+
+    /// Return the final objective of every restart.
+    pub fn objectives(&self) -> Vec<f32> {
+        self.solutions.iter().map(|x| x.evaluate()).collect()
+    }
+
+    /// Summarize the final objectives, with a 95% confidence interval on the mean bootstrapped from ```resamples``` resamples.
+    pub fn statistics(&self, resamples: usize, rng: &mut dyn rand::RngCore) -> RestartStatistics {
+        RestartStatistics::from_objectives(&self.objectives(), resamples, rng)
+    }
+}
+
+impl RestartStatistics {
+    /// Summarize ```objectives```, with a 95% confidence interval on the mean bootstrapped from ```resamples``` resamples.
+    ///
+    /// Panics if there are no objectives, or no resamples.
+    pub fn from_objectives(
+        objectives: &[f32],
+        resamples: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self {
+        assert!(!objectives.is_empty(), "no objectives to summarize");
+        assert!(resamples > 0, "no resamples to bootstrap from");
+        let n = objectives.len();
+        let mean = objectives.iter().sum::<f32>() / n as f32;
+        let std = if n > 1 {
+            let sum_squares: f32 = objectives.iter().map(|x| (x - mean).powi(2)).sum();
+            (sum_squares / (n - 1) as f32).sqrt()
+        } else {
+            0.
+        };
+        let min = objectives.iter().copied().fold(f32::INFINITY, f32::min);
+
+        let mut means: Vec<f32> = (0..resamples)
+            .map(|_| (0..n).map(|_| objectives[rng.gen_range(0..n)]).sum::<f32>() / n as f32)
+            .collect();
+        means.sort_by(f32::total_cmp);
+        let quantile = |q: f32| means[((q * resamples as f32) as usize).min(resamples - 1)];
+
+        Self {
+            mean,
+            std,
+            min,
+            confidence_interval: (quantile(0.025), quantile(0.975)),
+        }
+    }
+
+    /// Return the mean objective.
+    pub fn mean(&self) -> f32 {
+        self.mean
+    }
+
+    /// Return the sample standard deviation of the objectives.
+    pub fn std(&self) -> f32 {
+        self.std
+    }
+
+    /// Return the best objective.
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    /// Return the lower and upper bound of the bootstrapped 95% confidence interval on the mean.
+    pub fn confidence_interval(&self) -> (f32, f32) {
+        self.confidence_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use crate::{
+        algorithms::sa::{FactorSchedule, SimulatedAnnealing},
+        multi_start::{restart_statistics, MultiStart, RestartStatistics},
+        selectors::RandomSelector,
+        termination::Terminator,
+        test::*,
+    };
+
+    fn multi_start(master_seed: u64) -> MultiStart<Number> {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0., 3., 4., 2., 6.];
+        let numbers_initial = numbers.clone();
+        MultiStart::builder()
+            .constructor(move |rng| {
+                let index = rng.gen_range(0..numbers_initial.len());
+                Number::new(index, numbers_initial[index])
+            })
+            .heuristic(move |seed| {
+                let rng = rand::rngs::StdRng::seed_from_u64(seed);
+                SimulatedAnnealing::builder()
+                    .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+                    .terminator(Terminator::builder().iterations(5).build())
+                    .rng(rng)
+                    .cooling_schedule(FactorSchedule::new(1., 0.1))
+                    .build()
+            })
+            .restarts(10)
+            .master_seed(master_seed)
+            .build()
+    }
+
+    #[test]
+    fn multi_start_parallel_reproducible() {
+        let sequential = multi_start(42).run();
+        for threads in [1, 3, 4] {
+            let parallel = multi_start(42).run_parallel(threads);
+            assert_eq!(parallel.objectives(), sequential.objectives());
+            assert_eq!(parallel.index_best(), sequential.index_best());
+        }
+
+        // the restarts differ from each other
+        let objectives = sequential.objectives();
+        assert!(objectives.iter().any(|x| *x != objectives[0]));
+    }
+
+    #[test]
+    fn restart_statistics_zero_variance() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0., 3., 4., 2., 6.];
+        let numbers_initial = numbers.clone();
+        // every restart starts, and stays, at the global minimum
+        let multi_start = MultiStart::builder()
+            .constructor(move |_| Number::new(7, numbers_initial[7]))
+            .heuristic(move |seed| {
+                let rng = rand::rngs::StdRng::seed_from_u64(seed);
+                SimulatedAnnealing::builder()
+                    .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+                    .terminator(Terminator::builder().iterations(5).build())
+                    .rng(rng)
+                    .cooling_schedule(FactorSchedule::new(1., 0.1))
+                    .build()
+            })
+            .restarts(8)
+            .build();
+
+        let statistics = restart_statistics(multi_start, 100);
+        assert_eq!(statistics.mean(), 0.);
+        assert_eq!(statistics.std(), 0.);
+        assert_eq!(statistics.min(), 0.);
+        assert_eq!(statistics.confidence_interval(), (0., 0.));
+    }
+
+    #[test]
+    fn restart_statistics_bootstrap() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let objectives: Vec<f32> = (0..50).map(|i| i as f32).collect();
+        let statistics = RestartStatistics::from_objectives(&objectives, 1000, &mut rng);
+        assert_eq!(statistics.mean(), 24.5);
+        assert_eq!(statistics.min(), 0.);
+        let (lower, upper) = statistics.confidence_interval();
+        assert!(lower < 24.5 && 24.5 < upper);
+        assert!(upper - lower < 4. * statistics.std());
+    }
+}
@@ -0,0 +1,719 @@
+//! Operators which wrap other operators, or external code such as solvers, and helpers to implement operators
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+#[cfg(feature = "std-time")]
+use std::{sync::Arc, time::Duration};
+
+use crate::{try_best_neighbor, Constrained, Evaluate, KeyFn, Operator, SolutionKey};
+
+/// Draws a random neighbor of a solution.
+pub type ShakeFn<Solution> = Box<dyn Fn(Solution, &mut dyn rand::RngCore) -> Solution>;
+
+/// A fallible call which computes a new solution, e.g. by an external MIP or CP solver.
+#[cfg(feature = "std-time")]
+pub type ExternalCall<Solution> =
+    Arc<dyn Fn(&Solution, &mut dyn rand::RngCore) -> Result<Solution, String> + Send + Sync>;
+
+/// Box any sequence of neighbors, e.g. a ```Vec``` or an iterator chain, such that it can be returned by
+/// [Operator::construct_neighborhood] without writing a dedicated [Iterator] type.
+///
+/// Lazy sequences, e.g. ```std::iter::from_fn```, generate their neighbors on demand. Since the neighborhood
+/// outlives the call, a chain must own what it captures, e.g. by ```move``` closures.
+pub fn neighborhood<I>(neighbors: I) -> Box<dyn Iterator<Item = I::Item>>
+where
+    I: IntoIterator,
+    I::IntoIter: 'static,
+{
+    Box::new(neighbors.into_iter())
+}
+
+/// Lazily generate the neighborhood ```neighbor(0), neighbor(1), ...``` up until the first ```None```, e.g. to
+/// enumerate the moves of an operator by index.
+pub fn neighborhood_from_fn<Solution, F>(mut neighbor: F) -> Box<dyn Iterator<Item = Solution>>
+where
+    F: FnMut(usize) -> Option<Solution> + 'static,
+{
+    let mut index = 0;
+    neighborhood(std::iter::from_fn(move || {
+        let next = neighbor(index);
+        index += 1;
+        next
+    }))
+}
+
+/// An operator whose shake is a closure, e.g. to use a one-off move without declaring a type.
+///
+/// Slow or fallible calls, e.g. to an external solver, are better wrapped in a [TimedOperator].
+pub struct FnOperator<Solution> {
+    name: String,
+    shake: ShakeFn<Solution>,
+}
+
+/// An operator whose shake is fully determined by the solution, as it ignores the random number generator.
+///
+/// Use it for reproducible benchmarks and regression tests: with a deterministic selector and acceptance
+/// criterion, e.g. [crate::selectors::SequentialSelector] and [crate::acceptance::BetterOnly], a run does not
+/// depend on the seed at all. Moves which need randomness should draw from the generator, e.g. via [FnOperator].
+pub struct DeterministicShake<Solution> {
+    name: String,
+    shake: Box<dyn Fn(&Solution) -> Solution>,
+}
+
+/// Guard a slow and fallible call, e.g. to an external solver, by a time limit, as used by matheuristics.
+///
+/// Every shake runs the call on a separate thread, with its own source of randomness seeded from the search.
+/// If the call fails, or does not return within the time limit, the solution is returned unchanged, and the
+/// shake counts as a fallback. A call which hangs is abandoned rather than killed, so it should eventually return.
+#[cfg(feature = "std-time")]
+pub struct TimedOperator<Solution> {
+    name: String,
+    timeout: Duration,
+    call: ExternalCall<Solution>,
+    fallbacks: Cell<usize>,
+}
+
+/// Cache the neighborhood of the last solution it was constructed for.
+///
+/// On a cache hit, a clone of the stored neighbors is returned instead of calling the wrapped operator.
+/// Solutions are identified by their key, so this is only correct for deterministic neighborhoods,
+/// i.e. the neighborhood of a solution must not depend on anything but the solution itself.
+pub struct MemoizedNeighborhood<Op: Operator> {
+    operator: Op,
+    key: KeyFn<Op::Solution>,
+    cache: RefCell<Option<(u64, Vec<Op::Solution>)>>,
+}
+
+/// Truncate the neighborhood after a fixed number of neighbors.
+///
+/// Bounds the cost of [Operator::find_best_neighbor], which returns the best of the first ```max_neighbors```
+/// neighbors, independent of the size of the wrapped neighborhood.
+pub struct CappedNeighborhood<Op> {
+    operator: Op,
+    max_neighbors: usize,
+}
+
+/// Draw every neighbor from a budget of neighbor evaluations, shared by all operators of a run.
+///
+/// Once the budget is exhausted, neighborhoods are empty. Share the budget of a
+/// [crate::termination::BudgetTerminator], see [crate::termination::BudgetTerminator::budget], to stop the run
+/// as well.
+pub struct BudgetedNeighborhood<Op> {
+    operator: Op,
+    budget: Rc<Cell<usize>>,
+}
+
+/// Restrict the neighborhood to feasible neighbors, such that e.g. [Operator::find_best_neighbor] never returns an
+/// infeasible solution.
+///
+/// If no neighbor is feasible, the best neighbor is the solution itself. Shakes are forwarded unfiltered.
+pub struct FeasibleOnly<Op> {
+    operator: Op,
+}
+
+impl<Op: Operator> MemoizedNeighborhood<Op> {
+    /// Identify solutions by their [SolutionKey].
+    pub fn new(operator: Op) -> Self
+    where
+        Op::Solution: SolutionKey,
+    {
+        Self::with_key(operator, |solution: &Op::Solution| solution.key())
+    }
+
+    /// Identify solutions by ```key```.
+    pub fn with_key<F: Fn(&Op::Solution) -> u64 + 'static>(operator: Op, key: F) -> Self {
+        Self {
+            operator,
+            key: Box::new(key),
+            cache: RefCell::new(None),
+        }
+    }
+
+    /// Forget the cached neighborhood.
+    pub fn clear(&self) {
+        self.cache.replace(None);
+    }
+}
+
+impl<Op> Operator for MemoizedNeighborhood<Op>
+where
+    Op: Operator,
+    Op::Solution: Clone + 'static,
+{
+    type Solution = Op::Solution;
+
+    fn construct_neighborhood(
+        &self,
+        solution: Self::Solution,
+    ) -> Box<dyn Iterator<Item = Self::Solution>> {
+        let key = (self.key)(&solution);
+
+        if let Some((key_cached, neighbors)) = self.cache.borrow().as_ref() {
+            if *key_cached == key {
+                return Box::new(neighbors.clone().into_iter());
+            }
+        }
+
+        let neighbors: Vec<Self::Solution> =
+            self.operator.construct_neighborhood(solution).collect();
+        self.cache.replace(Some((key, neighbors.clone())));
+        Box::new(neighbors.into_iter())
+    }
+
+    fn shake(&self, solution: Self::Solution, rng: &mut dyn rand::RngCore) -> Self::Solution {
+        self.operator.shake(solution, rng)
+    }
+}
+
+impl<Solution> FnOperator<Solution> {
+    pub fn new<F: Fn(Solution, &mut dyn rand::RngCore) -> Solution + 'static>(
+        name: &str,
+        shake: F,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            shake: Box::new(shake),
+        }
+    }
+}
+
+impl<Solution: Evaluate> Operator for FnOperator<Solution> {
+    type Solution = Solution;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn shake(&self, solution: Solution, rng: &mut dyn rand::RngCore) -> Solution {
+        (self.shake)(solution, rng)
+    }
+}
+
+impl<Solution> DeterministicShake<Solution> {
+    pub fn new<F: Fn(&Solution) -> Solution + 'static>(name: &str, shake: F) -> Self {
+        Self {
+            name: name.to_string(),
+            shake: Box::new(shake),
+        }
+    }
+}
+
+impl<Solution: Evaluate> Operator for DeterministicShake<Solution> {
+    type Solution = Solution;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn shake(&self, solution: Solution, _rng: &mut dyn rand::RngCore) -> Solution {
+        (self.shake)(&solution)
+    }
+}
+
+#[cfg(feature = "std-time")]
+impl<Solution> TimedOperator<Solution> {
+    pub fn new<F>(name: &str, timeout: Duration, call: F) -> Self
+    where
+        F: Fn(&Solution, &mut dyn rand::RngCore) -> Result<Solution, String>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            timeout,
+            call: Arc::new(call),
+            fallbacks: Cell::new(0),
+        }
+    }
+
+    /// Return the number of shakes in which the call failed or timed out.
+    pub fn fallbacks(&self) -> usize {
+        self.fallbacks.get()
+    }
+}
+
+#[cfg(feature = "std-time")]
+impl<Solution> Operator for TimedOperator<Solution>
+where
+    Solution: Evaluate + Clone + Send + 'static,
+{
+    type Solution = Solution;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn shake(&self, solution: Solution, rng: &mut dyn rand::RngCore) -> Solution {
+        use rand::SeedableRng;
+        let seed = rng.next_u64();
+        let call = self.call.clone();
+        let input = solution.clone();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            // the receiver is gone if the call timed out
+            let _ = sender.send(call(&input, &mut rng));
+        });
+
+        match receiver.recv_timeout(self.timeout) {
+            Ok(Ok(candidate)) => candidate,
+            _ => {
+                self.fallbacks.set(self.fallbacks.get() + 1);
+                solution
+            }
+        }
+    }
+}
+
+impl<Op: Operator> CappedNeighborhood<Op> {
+    pub fn new(operator: Op, max_neighbors: usize) -> Self {
+        Self {
+            operator,
+            max_neighbors,
+        }
+    }
+}
+
+impl<Op> Operator for CappedNeighborhood<Op>
+where
+    Op: Operator,
+    Op::Solution: 'static,
+{
+    type Solution = Op::Solution;
+
+    fn name(&self) -> &str {
+        self.operator.name()
+    }
+
+    fn construct_neighborhood(
+        &self,
+        solution: Self::Solution,
+    ) -> Box<dyn Iterator<Item = Self::Solution>> {
+        Box::new(
+            self.operator
+                .construct_neighborhood(solution)
+                .take(self.max_neighbors),
+        )
+    }
+
+    fn shake(&self, solution: Self::Solution, rng: &mut dyn rand::RngCore) -> Self::Solution {
+        self.operator.shake(solution, rng)
+    }
+
+    fn destroy(
+        &self,
+        solution: Self::Solution,
+        size: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self::Solution {
+        self.operator.destroy(solution, size, rng)
+    }
+}
+
+impl<Op: Operator> BudgetedNeighborhood<Op> {
+    pub fn new(operator: Op, budget: Rc<Cell<usize>>) -> Self {
+        Self { operator, budget }
+    }
+}
+
+impl<Op> Operator for BudgetedNeighborhood<Op>
+where
+    Op: Operator,
+    Op::Solution: 'static,
+{
+    type Solution = Op::Solution;
+
+    fn name(&self) -> &str {
+        self.operator.name()
+    }
+
+    /// Yield neighbors of the wrapped neighborhood while the budget lasts, each taking one unit of it.
+    fn construct_neighborhood(
+        &self,
+        solution: Self::Solution,
+    ) -> Box<dyn Iterator<Item = Self::Solution>> {
+        let budget = self.budget.clone();
+        let mut neighbors = self.operator.construct_neighborhood(solution);
+        Box::new(std::iter::from_fn(move || {
+            let remaining = budget.get();
+            if remaining == 0 {
+                return None;
+            }
+            let neighbor = neighbors.next()?;
+            budget.set(remaining - 1);
+            Some(neighbor)
+        }))
+    }
+
+    fn shake(&self, solution: Self::Solution, rng: &mut dyn rand::RngCore) -> Self::Solution {
+        self.operator.shake(solution, rng)
+    }
+
+    fn destroy(
+        &self,
+        solution: Self::Solution,
+        size: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self::Solution {
+        self.operator.destroy(solution, size, rng)
+    }
+}
+
+impl<Op: Operator> FeasibleOnly<Op> {
+    pub fn new(operator: Op) -> Self {
+        Self { operator }
+    }
+}
+
+impl<Op> Operator for FeasibleOnly<Op>
+where
+    Op: Operator,
+    Op::Solution: Constrained + Clone + 'static,
+{
+    type Solution = Op::Solution;
+
+    fn name(&self) -> &str {
+        self.operator.name()
+    }
+
+    fn construct_neighborhood(
+        &self,
+        solution: Self::Solution,
+    ) -> Box<dyn Iterator<Item = Self::Solution>> {
+        Box::new(
+            self.operator
+                .construct_neighborhood(solution)
+                .filter(|neighbor| neighbor.is_feasible()),
+        )
+    }
+
+    /// Return the best feasible neighbor of ```solution```, or ```solution``` itself if there is none.
+    fn try_find_best_neighbor_epsilon(
+        &self,
+        solution: Self::Solution,
+        epsilon: f32,
+    ) -> Option<Self::Solution> {
+        try_best_neighbor(self.construct_neighborhood(solution.clone()), epsilon).or(Some(solution))
+    }
+
+    fn shake(&self, solution: Self::Solution, rng: &mut dyn rand::RngCore) -> Self::Solution {
+        self.operator.shake(solution, rng)
+    }
+
+    fn destroy(
+        &self,
+        solution: Self::Solution,
+        size: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self::Solution {
+        self.operator.destroy(solution, size, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use rand::SeedableRng;
+
+    use crate::{
+        algorithms::local_search::LocalSearch,
+        algorithms::vns::VariableNeighborhoodSearch,
+        operators::{
+            neighborhood, neighborhood_from_fn, BudgetedNeighborhood, CappedNeighborhood,
+            DeterministicShake, FeasibleOnly, FnOperator, MemoizedNeighborhood,
+        },
+        selectors::SequentialSelector,
+        termination::{BudgetTerminator, Terminator},
+        test::*,
+        Constrained, Evaluate, ImprovingHeuristic, Operator,
+    };
+
+    /// The neighborhood of [NeighborsUpUntilN], without a dedicated iterator type.
+    struct NeighborsAtDistance {
+        numbers: Vec<f32>,
+        distance: usize,
+    }
+
+    impl Operator for NeighborsAtDistance {
+        type Solution = Number;
+
+        fn construct_neighborhood(&self, solution: Number) -> Box<dyn Iterator<Item = Number>> {
+            let numbers = self.numbers.clone();
+            let indices = [
+                solution.index().checked_sub(self.distance),
+                Some(solution.index() + self.distance),
+            ];
+            neighborhood(indices.into_iter().flatten().filter_map(move |index| {
+                numbers.get(index).map(|value| Number::new(index, *value))
+            }))
+        }
+    }
+
+    fn indices(neighborhood: Box<dyn Iterator<Item = Number>>) -> Vec<usize> {
+        neighborhood.map(|neighbor| neighbor.index()).collect()
+    }
+
+    #[test]
+    fn neighborhood_helpers_match_iterator() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let handwritten = NeighborsUpUntilN::new(&numbers, 2);
+        let helper = NeighborsAtDistance {
+            numbers: numbers.clone(),
+            distance: 2,
+        };
+        for (index, value) in numbers.iter().enumerate() {
+            let solution = Number::new(index, *value);
+            assert_eq!(
+                indices(helper.construct_neighborhood(solution.clone())),
+                indices(handwritten.construct_neighborhood(solution.clone()))
+            );
+            assert_eq!(
+                helper.neighborhood_objectives(solution.clone()),
+                handwritten.neighborhood_objectives(solution)
+            );
+        }
+
+        let evens = neighborhood_from_fn(move |k| {
+            let index = 2 * k;
+            (index < numbers.len()).then(|| Number::new(index, numbers[index]))
+        });
+        assert_eq!(indices(evens), vec![0, 2, 4, 6]);
+    }
+
+    #[test]
+    fn fn_operator_in_search() {
+        let numbers = [9., 8., 7., 8., 9., 7., 5., 0.];
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        // a mock solver, which knows the optimum
+        let solver = FnOperator::new("solver", move |solution: Number, _rng| {
+            counter.set(counter.get() + 1);
+            if solution.index() == 7 {
+                solution
+            } else {
+                Number::new(7, numbers[7])
+            }
+        });
+        assert_eq!(solver.name(), "solver");
+
+        let search = LocalSearch::builder()
+            .selector(SequentialSelector::new().option(solver))
+            .terminator(Terminator::builder().iterations(3).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+        let solution = search.optimize(Number::new(0, numbers[0]));
+        assert_eq!(solution.index(), 7);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn deterministic_shake_ignores_seed() {
+        let numbers = [9., 8., 7., 8., 9., 7., 5., 0.];
+        let run = |seed: u64| -> String {
+            let trace = Rc::new(RefCell::new(String::new()));
+            let recorder = trace.clone();
+            let shake = DeterministicShake::new("stride", move |solution: &Number| {
+                let index = (solution.index() * 3 + 1) % numbers.len();
+                recorder
+                    .borrow_mut()
+                    .push_str(&format!("{:?};", (index, numbers[index])));
+                Number::new(index, numbers[index])
+            });
+            let search = LocalSearch::builder()
+                .selector(SequentialSelector::new().option(shake))
+                .terminator(Terminator::builder().iterations(20).build())
+                .rng(rand::rngs::StdRng::seed_from_u64(seed))
+                .build();
+            let solution = search.optimize(Number::new(0, numbers[0]));
+            let trace = trace.borrow().clone();
+            trace + &format!("{:?}", (solution.index(), solution.evaluate()))
+        };
+
+        let trace = run(0);
+        assert!(!trace.is_empty());
+        for seed in 1..5 {
+            assert_eq!(run(seed).as_bytes(), trace.as_bytes());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std-time")]
+    fn timed_operator_falls_back() {
+        use std::time::Duration;
+
+        use crate::operators::TimedOperator;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let solution = Number::new(0, 5.);
+
+        let solver = TimedOperator::new("solver", Duration::from_secs(10), |_: &Number, _rng| {
+            Ok(Number::new(1, 1.))
+        });
+        assert_eq!(solver.shake(solution.clone(), &mut rng).index(), 1);
+        assert_eq!(solver.fallbacks(), 0);
+
+        let failing = TimedOperator::new("failing", Duration::from_secs(10), |_: &Number, _rng| {
+            Err("infeasible model".to_string())
+        });
+        assert_eq!(failing.shake(solution.clone(), &mut rng).index(), 0);
+        assert_eq!(failing.fallbacks(), 1);
+
+        let hanging =
+            TimedOperator::new("hanging", Duration::from_millis(10), |_: &Number, _rng| {
+                std::thread::sleep(Duration::from_millis(200));
+                Ok(Number::new(1, 1.))
+            });
+        assert_eq!(hanging.shake(solution, &mut rng).index(), 0);
+        assert_eq!(hanging.fallbacks(), 1);
+    }
+
+    /// Count how often the neighborhood is constructed.
+    struct CountingNeighborhood {
+        operator: NeighborsUpUntilN,
+        count: Rc<Cell<usize>>,
+    }
+
+    impl Operator for CountingNeighborhood {
+        type Solution = Number;
+
+        fn construct_neighborhood(&self, solution: Number) -> Box<dyn Iterator<Item = Number>> {
+            self.count.set(self.count.get() + 1);
+            self.operator.construct_neighborhood(solution)
+        }
+    }
+
+    /// Count how many neighbors are drawn.
+    struct CountingNeighbors {
+        operator: NeighborsUpUntilN,
+        count: Rc<Cell<usize>>,
+    }
+
+    impl Operator for CountingNeighbors {
+        type Solution = Number;
+
+        fn construct_neighborhood(&self, solution: Number) -> Box<dyn Iterator<Item = Number>> {
+            let count = self.count.clone();
+            Box::new(
+                self.operator
+                    .construct_neighborhood(solution)
+                    .inspect(move |_| count.set(count.get() + 1)),
+            )
+        }
+    }
+
+    #[test]
+    fn memoized_neighborhood() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let count = Rc::new(Cell::new(0));
+        let operator = MemoizedNeighborhood::new(CountingNeighborhood {
+            operator: NeighborsUpUntilN::new(&numbers, 1),
+            count: count.clone(),
+        });
+
+        let solution = Number::new(3, numbers[3]);
+        let first: Vec<usize> = operator
+            .construct_neighborhood(solution.clone())
+            .map(|x| x.index())
+            .collect();
+        let second: Vec<usize> = operator
+            .construct_neighborhood(solution)
+            .map(|x| x.index())
+            .collect();
+        assert_eq!(first, vec![2, 4]);
+        assert_eq!(second, first);
+        assert_eq!(count.get(), 1);
+
+        let _ = operator.construct_neighborhood(Number::new(4, numbers[4]));
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn capped_neighborhood() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let operator = CappedNeighborhood::new(NeighborsUpUntilN::new(&numbers, 3), 2);
+
+        let solution = Number::new(3, numbers[3]);
+        let neighbors: Vec<usize> = operator
+            .construct_neighborhood(solution.clone())
+            .map(|x| x.index())
+            .collect();
+        assert_eq!(neighbors.len(), 2);
+        let best = operator.find_best_neighbor(solution);
+        assert!(neighbors.contains(&best.index()));
+    }
+
+    #[test]
+    fn budgeted_neighborhood() {
+        // a long descent, such that only the budget stops the search
+        let numbers: Vec<f32> = (0..100).rev().map(|x| x as f32).collect();
+        let count = Rc::new(Cell::new(0));
+        let terminator = BudgetTerminator::new(25);
+        let counting = CountingNeighbors {
+            operator: NeighborsUpUntilN::new(&numbers, 2),
+            count: count.clone(),
+        };
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(
+                SequentialSelector::new()
+                    .option(BudgetedNeighborhood::new(counting, terminator.budget())),
+            )
+            .terminator(terminator)
+            .build();
+
+        let solution = vns.optimize(Number::new(0, numbers[0]));
+        assert_eq!(count.get(), 25);
+        // every step of two indices takes at least one neighbor evaluation
+        assert!(solution.index() > 0 && solution.index() <= 2 * 25);
+    }
+
+    /// Every other number is a neighbor.
+    struct AllNumbers {
+        numbers: Vec<f32>,
+    }
+
+    impl Operator for AllNumbers {
+        type Solution = Number;
+
+        fn construct_neighborhood(&self, solution: Number) -> Box<dyn Iterator<Item = Number>> {
+            let numbers = self.numbers.clone();
+            neighborhood(
+                (0..numbers.len())
+                    .filter(move |index| *index != solution.index())
+                    .map(move |index| Number::new(index, numbers[index])),
+            )
+        }
+    }
+
+    #[test]
+    fn feasible_only() {
+        // negative numbers are infeasible, i.e. half of the neighbors of index 2, including the best one
+        let numbers = vec![3., -5., 2., -1., 4.];
+        let solution = Number::new(2, numbers[2]);
+        let unrestricted = AllNumbers {
+            numbers: numbers.clone(),
+        };
+        assert_eq!(unrestricted.find_best_neighbor(solution.clone()).index(), 1);
+
+        let operator = FeasibleOnly::new(unrestricted);
+        let neighbors: Vec<usize> = operator
+            .construct_neighborhood(solution.clone())
+            .map(|x| x.index())
+            .collect();
+        assert_eq!(neighbors, vec![0, 4]);
+        let best = operator.find_best_neighbor(solution);
+        assert!(best.is_feasible());
+        assert_eq!(best.index(), 0);
+
+        // without feasible neighbors, the solution is its own best neighbor
+        let operator = FeasibleOnly::new(AllNumbers {
+            numbers: vec![1., -2., -3.],
+        });
+        assert_eq!(operator.find_best_neighbor(Number::new(0, 1.)).index(), 0);
+    }
+}
@@ -0,0 +1,64 @@
+//! Deterministic set operations
+//!
+//! Iterating over a [std::collections::HashSet] visits its elements in an order which differs from run to run,
+//! which breaks reproducibility of seeded heuristics. The functions in this module return their results in the
+//! order of their input instead.
+use std::{collections::HashSet, hash::Hash};
+
+/// Return the elements of ```universe``` which are not contained in ```subset```, in the order of ```universe```.
+pub fn difference<T: Eq + Hash + Clone>(universe: &[T], subset: &[T]) -> Vec<T> {
+    let subset: HashSet<&T> = subset.iter().collect();
+    universe
+        .iter()
+        .filter(|element| !subset.contains(element))
+        .cloned()
+        .collect()
+}
+
+/// Return the elements of ```universe``` which are also contained in ```subset```, in the order of ```universe```.
+pub fn intersection<T: Eq + Hash + Clone>(universe: &[T], subset: &[T]) -> Vec<T> {
+    let subset: HashSet<&T> = subset.iter().collect();
+    universe
+        .iter()
+        .filter(|element| subset.contains(element))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, Rng, SeedableRng};
+
+    use crate::ordered_set::{difference, intersection};
+
+    // remove a few elements at random and reinsert the missing ones at random positions
+    fn destroy_and_repair(seed: u64) -> Vec<usize> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        let universe: Vec<usize> = (0..50).collect();
+        let mut solution = universe.clone();
+        solution.shuffle(&mut rng);
+        solution.truncate(40);
+
+        for element in difference(&universe, &solution) {
+            let index = rng.gen_range(0..=solution.len());
+            solution.insert(index, element);
+        }
+        solution
+    }
+
+    #[test]
+    fn repair_is_reproducible() {
+        let reference = destroy_and_repair(0);
+        for _ in 0..10 {
+            assert_eq!(destroy_and_repair(0), reference);
+        }
+    }
+
+    #[test]
+    fn order_follows_universe() {
+        let universe = vec![5, 3, 8, 1, 9];
+        let subset = vec![9, 3];
+        assert_eq!(difference(&universe, &subset), vec![5, 8, 1]);
+        assert_eq!(intersection(&universe, &subset), vec![3, 9]);
+    }
+}
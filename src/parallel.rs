@@ -0,0 +1,235 @@
+//! Parallel evaluation of neighborhoods, based on rayon
+use rayon::prelude::*;
+
+use crate::{is_improvement, try_best_neighbor, Evaluate, Operator};
+
+/// Search the neighborhood of the wrapped operator in parallel, in chunks of neighbors.
+///
+/// Every chunk is searched sequentially by a single thread, which amortizes the overhead of parallelism for
+/// neighbors that are cheap to evaluate. Neighborhoods smaller than the threshold are searched sequentially.
+/// The best neighbor is the same as the sequential one for ```epsilon``` = 0, ties included.
+pub struct ParallelNeighborhood<Op> {
+    operator: Op,
+    chunk_size: usize,
+    threshold: usize,
+}
+
+impl<Op: Operator> ParallelNeighborhood<Op> {
+    pub fn new(operator: Op) -> Self {
+        Self {
+            operator,
+            chunk_size: 64,
+            threshold: 1024,
+        }
+    }
+
+    /// Set the number of neighbors searched per task (default: 64)
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Set the neighborhood size from which on neighbors are searched in parallel (default: 1024)
+    pub fn threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+}
+
+impl<Op> Operator for ParallelNeighborhood<Op>
+where
+    Op: Operator,
+    Op::Solution: Send + Sync,
+{
+    type Solution = Op::Solution;
+
+    fn name(&self) -> &str {
+        self.operator.name()
+    }
+
+    fn construct_neighborhood(
+        &self,
+        solution: Self::Solution,
+    ) -> Box<dyn Iterator<Item = Self::Solution>> {
+        self.operator.construct_neighborhood(solution)
+    }
+
+    fn try_find_best_neighbor_epsilon(
+        &self,
+        solution: Self::Solution,
+        epsilon: f32,
+    ) -> Option<Self::Solution> {
+        let neighbors: Vec<Self::Solution> =
+            self.operator.construct_neighborhood(solution).collect();
+        if neighbors.len() < self.threshold {
+            try_best_neighbor(neighbors.into_iter(), epsilon)
+        } else {
+            Some(best_neighbor_chunked(neighbors, self.chunk_size, epsilon))
+        }
+    }
+
+    fn shake(&self, solution: Self::Solution, rng: &mut dyn rand::RngCore) -> Self::Solution {
+        self.operator.shake(solution, rng)
+    }
+
+    fn destroy(
+        &self,
+        solution: Self::Solution,
+        size: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self::Solution {
+        self.operator.destroy(solution, size, rng)
+    }
+}
+
+/// Return the best of ```neighbors```, where chunks of ```chunk_size``` neighbors are searched in parallel.
+///
+/// The result does not depend on the number of threads, nor on how rayon splits the work: every chunk is reduced
+/// to its winner sequentially, the winners are collected in neighborhood order, together with their stable index
+/// in the neighborhood, and then reduced sequentially as well. Ties are thus always won by the earliest neighbor.
+///
+/// Panics if ```neighbors``` is empty.
+pub fn best_neighbor_chunked<Solution: Evaluate + Send + Sync>(
+    mut neighbors: Vec<Solution>,
+    chunk_size: usize,
+    epsilon: f32,
+) -> Solution {
+    if neighbors.is_empty() {
+        panic!("neighborhood was empty")
+    }
+
+    // the stable index and objective of the winner of every chunk, in order
+    let winners: Vec<(usize, f32)> = neighbors
+        .par_chunks(chunk_size.max(1))
+        .enumerate()
+        .map(|(index_chunk, chunk)| {
+            let (index, objective) = index_of_best(chunk.iter().map(|x| x.evaluate()), epsilon);
+            (index_chunk * chunk_size.max(1) + index, objective)
+        })
+        .collect();
+    let (index_chunk, _) = index_of_best(winners.iter().map(|(_, objective)| *objective), epsilon);
+    neighbors.swap_remove(winners[index_chunk].0)
+}
+
+/// Return the index and objective of the best objective, where earlier objectives win ties within ```epsilon```.
+fn index_of_best(objectives: impl Iterator<Item = f32>, epsilon: f32) -> (usize, f32) {
+    let mut best: Option<(usize, f32)> = None;
+    for (index, objective) in objectives.enumerate() {
+        match best {
+            Some((_, objective_best)) if !is_improvement(objective, objective_best, epsilon) => {}
+            _ => best = Some((index, objective)),
+        }
+    }
+    best.expect("chunks are not empty")
+}
+
+/// Evaluate every solution of ```population``` in parallel, returning the objectives in order.
+pub fn evaluate_population<Solution: Evaluate + Sync>(population: &[Solution]) -> Vec<f32> {
+    population
+        .par_iter()
+        .map(|solution| solution.evaluate())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parallel::{best_neighbor_chunked, evaluate_population, ParallelNeighborhood},
+        test::Number,
+        Evaluate, Operator,
+    };
+
+    /// Every number is a neighbor.
+    struct AllNumbers {
+        numbers: Vec<f32>,
+    }
+
+    impl Operator for AllNumbers {
+        type Solution = Number;
+
+        fn construct_neighborhood(&self, _solution: Number) -> Box<dyn Iterator<Item = Number>> {
+            let neighbors: Vec<Number> = self
+                .numbers
+                .iter()
+                .enumerate()
+                .map(|(index, value)| Number::new(index, *value))
+                .collect();
+            Box::new(neighbors.into_iter())
+        }
+    }
+
+    #[test]
+    fn chunked_equals_sequential() {
+        // many ties, such that the order of the reduction matters
+        let numbers: Vec<f32> = (0..1000).map(|x| ((x * 37) % 101) as f32).collect();
+        let operator = AllNumbers {
+            numbers: numbers.clone(),
+        };
+        let solution = Number::new(0, numbers[0]);
+        let sequential = operator.find_best_neighbor(solution.clone());
+
+        for chunk_size in [1, 2, 7, 64, 999, 1000, 5000] {
+            let neighbors: Vec<Number> =
+                operator.construct_neighborhood(solution.clone()).collect();
+            let chunked = best_neighbor_chunked(neighbors, chunk_size, 0.);
+            assert_eq!(chunked.index(), sequential.index());
+            assert_eq!(chunked.evaluate(), sequential.evaluate());
+        }
+
+        for threshold in [0, 10_000] {
+            let parallel = ParallelNeighborhood::new(AllNumbers {
+                numbers: numbers.clone(),
+            })
+            .chunk_size(16)
+            .threshold(threshold);
+            let best = parallel.find_best_neighbor(solution.clone());
+            assert_eq!(best.index(), sequential.index());
+        }
+    }
+
+    #[test]
+    fn chunked_independent_of_threads() {
+        // every objective occurs ten times, and the best ones are spread over many chunks
+        let numbers: Vec<f32> = (0..1000).map(|x| ((x * 37) % 100) as f32 / 10.).collect();
+        let operator = AllNumbers {
+            numbers: numbers.clone(),
+        };
+        let solution = Number::new(0, numbers[0]);
+
+        let winners = |threads: usize, epsilon: f32| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                (0..10)
+                    .map(|_| {
+                        let neighbors: Vec<Number> =
+                            operator.construct_neighborhood(solution.clone()).collect();
+                        best_neighbor_chunked(neighbors, 8, epsilon).index()
+                    })
+                    .collect::<Vec<usize>>()
+            })
+        };
+        for epsilon in [0., 0.15] {
+            let reference = winners(1, epsilon);
+            assert!(reference.iter().all(|index| *index == reference[0]));
+            assert_eq!(winners(2, epsilon), reference);
+            assert_eq!(winners(4, epsilon), reference);
+        }
+        // without epsilon, the earliest of the tied best neighbors wins
+        let first_best = numbers.iter().position(|x| *x == 0.).unwrap();
+        assert_eq!(winners(4, 0.)[0], first_best);
+    }
+
+    #[test]
+    fn population_equals_sequential() {
+        let population: Vec<Number> = (0..1000)
+            .map(|x| Number::new(x, ((x * 37) % 101) as f32))
+            .collect();
+        assert_eq!(
+            evaluate_population(&population),
+            Number::evaluate_batch(&population)
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Multi-objective search, where solutions are compared by _Pareto dominance_
+use core::time::Duration;
+use std::cell::RefCell;
+#[cfg(feature = "std-time")]
+use std::time::SystemTime;
+
+use crate::{
+    pause::HeuristicState, Evaluate, ImprovingHeuristic, OptimizeError, OptimizeState,
+    ReturnPolicy, TryEvaluate,
+};
+
+/// Evaluate a solution by multiple objectives, all of which are to be minimized.
+pub trait MultiEvaluate {
+    fn objectives(&self) -> Vec<f32>;
+}
+
+/// Test whether objectives ```a``` dominate ```b```, i.e. ```a``` is nowhere worse, and somewhere better.
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a <= b) && a.iter().zip(b).any(|(a, b)| a < b)
+}
+
+/// The non-dominated solutions seen so far, i.e. an approximation of the Pareto front.
+///
+/// Solutions with equal objectives are only archived once.
+pub struct ParetoArchive<Solution> {
+    front: Vec<Solution>,
+}
+
+/// The Pareto front found by a multi-objective search, decorated with some metadata.
+pub struct MultiOutcome<T> {
+    front: Vec<T>,
+    duration: Duration,
+}
+
+/// Wraps a heuristic, such that it accepts every candidate which is not dominated by the incumbent, and archives
+/// all non-dominated candidates in a [ParetoArchive].
+///
+/// The wrapped heuristic still proposes candidates, and decides on termination, while its own acceptance is
+/// bypassed. Its scalar objective, see [Evaluate], only determines the single best solution.
+///
+/// Optimize with [ParetoAcceptance::optimize_multi] to obtain the front, or through a reference, such that the
+/// front can be read after the run: ```(&pareto).optimize(initial)```, followed by ```pareto.front()```.
+pub struct ParetoAcceptance<Solution, H> {
+    heuristic: H,
+    archive: RefCell<ParetoArchive<Solution>>,
+}
+
+impl<Solution: MultiEvaluate> ParetoArchive<Solution> {
+    pub fn new() -> Self {
+        Self { front: vec![] }
+    }
+
+    /// Archive a clone of ```solution``` if no archived solution dominates or equals it, and remove the
+    /// archived solutions which it dominates. Return whether ```solution``` was archived.
+    pub fn offer(&mut self, solution: &Solution) -> bool
+    where
+        Solution: Clone,
+    {
+        let objectives = solution.objectives();
+        if self.front.iter().any(|archived| {
+            let archived = archived.objectives();
+            archived == objectives || dominates(&archived, &objectives)
+        }) {
+            return false;
+        }
+        self.front
+            .retain(|archived| !dominates(&objectives, &archived.objectives()));
+        self.front.push(solution.clone());
+        true
+    }
+
+    /// Return the archived solutions, in order of archiving.
+    pub fn front(&self) -> &[Solution] {
+        &self.front
+    }
+
+    /// Return the number of archived solutions.
+    pub fn len(&self) -> usize {
+        self.front.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.front.is_empty()
+    }
+}
+
+impl<Solution: MultiEvaluate> Default for ParetoArchive<Solution> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MultiOutcome<T> {
+    pub fn new(front: Vec<T>, duration: Duration) -> Self {
+        Self { front, duration }
+    }
+
+    /// Get the non-dominated solutions.
+    pub fn front(&self) -> &[T] {
+        &self.front
+    }
+
+    /// Return the non-dominated solutions.
+    pub fn into_front(self) -> Vec<T> {
+        self.front
+    }
+
+    /// Return the computation time that was needed to get the front.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl<Solution: MultiEvaluate, H> ParetoAcceptance<Solution, H> {
+    pub fn new(heuristic: H) -> Self {
+        Self {
+            heuristic,
+            archive: RefCell::new(ParetoArchive::new()),
+        }
+    }
+
+    /// Return the non-dominated solutions found so far.
+    pub fn front(&self) -> Vec<Solution>
+    where
+        Solution: Clone,
+    {
+        self.archive.borrow().front().to_vec()
+    }
+
+    /// Optimize, starting from ```initial```, and return the non-dominated solutions found.
+    #[cfg(feature = "std-time")]
+    pub fn optimize_multi(self, initial: Solution) -> MultiOutcome<Solution>
+    where
+        Solution: Clone + Evaluate,
+        H: ImprovingHeuristic<Solution>,
+    {
+        let now = SystemTime::now();
+        (&self).optimize(initial);
+        let duration = now.elapsed().expect("failed to time for duration");
+        MultiOutcome::new(self.archive.into_inner().front, duration)
+    }
+}
+
+impl<Solution, H> ImprovingHeuristic<Solution> for &ParetoAcceptance<Solution, H>
+where
+    Solution: Clone + Evaluate + MultiEvaluate,
+    H: ImprovingHeuristic<Solution>,
+{
+    fn propose_candidate(&self, incumbent: Solution) -> Solution {
+        self.heuristic.propose_candidate(incumbent)
+    }
+
+    fn try_propose_candidate(&self, incumbent: Solution) -> Option<Solution> {
+        self.heuristic.try_propose_candidate(incumbent)
+    }
+
+    /// Archive the candidate if it is non-dominated, and accept it iff the incumbent does not dominate it.
+    fn accept_candidate(&self, candidate: &Solution, incumbent: &Solution) -> bool {
+        self.archive.borrow_mut().offer(candidate);
+        !dominates(&incumbent.objectives(), &candidate.objectives())
+    }
+
+    fn should_terminate(&self, incumbent: &Solution) -> bool {
+        self.heuristic.should_terminate(incumbent)
+    }
+
+    /// Apply the guard of the wrapped heuristic, then archive the initial solution.
+    fn guard_initial(&self, initial: Solution) -> Result<Solution, OptimizeError> {
+        let initial = self.heuristic.guard_initial(initial)?;
+        if initial.try_evaluate().is_ok() {
+            self.archive.borrow_mut().offer(&initial);
+        }
+        Ok(initial)
+    }
+
+    fn objective_epsilon(&self) -> f32 {
+        self.heuristic.objective_epsilon()
+    }
+
+    fn return_policy(&self) -> ReturnPolicy {
+        self.heuristic.return_policy()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.heuristic.progress()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.heuristic.seed()
+    }
+
+    fn pause_state(&self) -> HeuristicState {
+        self.heuristic.pause_state()
+    }
+
+    fn resume_state(&self, state: &HeuristicState, iterations: usize) {
+        self.heuristic.resume_state(state, iterations)
+    }
+
+    fn callback_candidate_improved_best(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_improved_best(candidate, incumbent)
+    }
+
+    fn callback_new_best(&self, best: &Solution) {
+        self.heuristic.callback_new_best(best)
+    }
+
+    fn callback_candidate_accepted(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_accepted(candidate, incumbent)
+    }
+
+    fn callback_candidate_rejected(&self, candidate: &Solution, incumbent: &Solution) {
+        self.heuristic
+            .callback_candidate_rejected(candidate, incumbent)
+    }
+
+    fn on_iteration_end(&self, state: &mut OptimizeState<Solution>) {
+        self.heuristic.on_iteration_end(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pareto::{dominates, MultiEvaluate, ParetoArchive},
+        Evaluate,
+    };
+
+    /// A point on a line, which should be close to both 5 and 12, i.e. the front is 5, 6, ..., 12.
+    #[derive(Clone, Debug)]
+    struct Point(usize);
+
+    impl MultiEvaluate for Point {
+        fn objectives(&self) -> Vec<f32> {
+            vec![self.0.abs_diff(5) as f32, self.0.abs_diff(12) as f32]
+        }
+    }
+
+    impl Evaluate for Point {
+        fn evaluate(&self) -> f32 {
+            self.objectives().iter().sum()
+        }
+    }
+
+    #[test]
+    fn archive_keeps_non_dominated() {
+        let mut archive = ParetoArchive::new();
+        assert!(archive.offer(&Point(0)));
+        assert!(archive.offer(&Point(3)));
+        assert_eq!(archive.len(), 1);
+        assert!(!archive.offer(&Point(3)));
+        assert!(!archive.offer(&Point(1)));
+        assert!(archive.offer(&Point(8)));
+        assert_eq!(archive.len(), 2);
+        assert!(!dominates(&[1., 2.], &[2., 1.]));
+    }
+
+    #[test]
+    #[cfg(feature = "std-time")]
+    fn optimize_multi_returns_front() {
+        use rand::{Rng, SeedableRng};
+
+        use crate::{
+            algorithms::local_search::LocalSearch, pareto::ParetoAcceptance,
+            selectors::SequentialSelector, termination::Terminator, Operator,
+        };
+
+        struct Step;
+
+        impl Operator for Step {
+            type Solution = Point;
+
+            fn shake(&self, solution: Point, rng: &mut dyn rand::RngCore) -> Point {
+                match rng.gen_bool(0.5) {
+                    true => Point((solution.0 + 1).min(20)),
+                    false => Point(solution.0.saturating_sub(1)),
+                }
+            }
+        }
+
+        let search = LocalSearch::builder()
+            .selector(SequentialSelector::new().option(Step))
+            .terminator(Terminator::builder().iterations(500).build())
+            .rng(rand::rngs::StdRng::seed_from_u64(0))
+            .build();
+        let outcome = ParetoAcceptance::new(search).optimize_multi(Point(0));
+
+        let front = outcome.front();
+        assert!(front.len() > 1);
+        for a in front {
+            assert!((5..=12).contains(&a.0));
+            for b in front {
+                assert!(!dominates(&a.objectives(), &b.objectives()));
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Pause a running search cooperatively, e.g. on Ctrl-C, and resume it later
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::{Evaluate, ImprovingHeuristic, OptimizeError, OptimizeState};
+
+/// The state of a paused search, i.e. of its optimize loop and of the heuristic, see [HeuristicState], which can be
+/// stored and resumed by [PausableOptimize::resume].
+///
+/// A newly built heuristic, configured like the one which paused, thus continues the search exactly, as far as its
+/// internal state is captured. Resuming with the heuristic which paused always continues the search exactly.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchState<Solution> {
+    incumbent: Solution,
+    best: Solution,
+    iteration: usize,
+    parameters: HashMap<String, f32>,
+    heuristic: HeuristicState,
+}
+
+/// The internal state of a paused heuristic, see [ImprovingHeuristic::pause_state].
+///
+/// [crate::algorithms::local_search::LocalSearch] and [crate::algorithms::sa::SimulatedAnnealing] capture the position
+/// of a source of randomness seeded by their builder, and the temperature, while their termination criteria, e.g.
+/// [crate::termination::IterationTerminator], and acceptance criteria continue from [SearchState::iteration].
+/// Everything else, e.g. a source of randomness given by the user, the state of an operator selector, or the
+/// observations of an [crate::algorithms::sa::AdaptiveSchedule], continues from the newly built heuristic.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeuristicState {
+    pub(crate) rng: Option<RngState>,
+    pub(crate) temperature: Option<f32>,
+}
+
+/// The position of a seeded source of randomness in its stream.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RngState {
+    pub(crate) seed: [u8; 32],
+    pub(crate) stream: u64,
+    pub(crate) word_pos: u128,
+}
+
+/// The result of a pausable run: either the search terminated, or it was paused.
+#[derive(Clone, Debug)]
+pub enum PauseOutcome<Solution> {
+    /// The search terminated, with the solution according to the return policy
+    Finished(Solution),
+    /// The search was paused before the iteration after [SearchState::iteration]
+    Paused(SearchState<Solution>),
+}
+
+/// Optimize such that the search can be paused between iterations, by setting a shared flag, e.g. from a Ctrl-C
+/// handler.
+///
+/// The flag is tested before every iteration, and left as is, i.e. it must be reset before resuming.
+pub trait PausableOptimize<Solution>: ImprovingHeuristic<Solution> {
+    /// Optimize, starting from ```initial```, until the search terminates or ```pause``` is set.
+    ///
+    /// Returns an error if [ImprovingHeuristic::guard_initial] rejects the initial solution.
+    fn optimize_pausable(
+        &self,
+        initial: Solution,
+        pause: &Arc<AtomicBool>,
+    ) -> Result<PauseOutcome<Solution>, OptimizeError>
+    where
+        Solution: Clone + Evaluate,
+    {
+        let state = self.start(initial)?;
+        Ok(run_pausable(self, state, pause))
+    }
+
+    /// Continue a paused search until it terminates or ```pause``` is set again, after restoring the internal state
+    /// of the heuristic, see [ImprovingHeuristic::resume_state].
+    fn resume(
+        &self,
+        state: SearchState<Solution>,
+        pause: &Arc<AtomicBool>,
+    ) -> PauseOutcome<Solution>
+    where
+        Solution: Clone + Evaluate,
+    {
+        self.resume_state(&state.heuristic, state.iteration);
+        run_pausable(self, state.into(), pause)
+    }
+}
+
+impl<Solution, H: ImprovingHeuristic<Solution> + ?Sized> PausableOptimize<Solution> for H {}
+
+/// Step the search until it terminates, or ```pause``` is set before an iteration.
+fn run_pausable<Solution, H>(
+    heuristic: &H,
+    mut state: OptimizeState<Solution>,
+    pause: &Arc<AtomicBool>,
+) -> PauseOutcome<Solution>
+where
+    Solution: Clone + Evaluate,
+    H: ImprovingHeuristic<Solution> + ?Sized,
+{
+    loop {
+        if pause.load(Ordering::SeqCst) {
+            let mut paused = SearchState::from(state);
+            paused.heuristic = heuristic.pause_state();
+            return PauseOutcome::Paused(paused);
+        }
+        heuristic.step(&mut state);
+        if state.is_terminated() {
+            return PauseOutcome::Finished(state.into_solution(heuristic.return_policy()));
+        }
+    }
+}
+
+impl<Solution> SearchState<Solution> {
+    /// Return the incumbent at the pause.
+    pub fn incumbent(&self) -> &Solution {
+        &self.incumbent
+    }
+
+    /// Return the best solution found before the pause.
+    pub fn best(&self) -> &Solution {
+        &self.best
+    }
+
+    /// Return the number of iterations completed before the pause.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Return the named parameters of the optimize loop, see [OptimizeState::parameters].
+    pub fn parameters(&self) -> &HashMap<String, f32> {
+        &self.parameters
+    }
+
+    /// Return the internal state of the heuristic at the pause.
+    pub fn heuristic(&self) -> &HeuristicState {
+        &self.heuristic
+    }
+}
+
+impl HeuristicState {
+    /// Return the position of the seeded source of randomness, if captured.
+    pub fn rng(&self) -> Option<&RngState> {
+        self.rng.as_ref()
+    }
+
+    /// Return the temperature, if captured.
+    pub fn temperature(&self) -> Option<f32> {
+        self.temperature
+    }
+}
+
+impl<Solution> From<OptimizeState<Solution>> for SearchState<Solution> {
+    fn from(state: OptimizeState<Solution>) -> Self {
+        Self {
+            incumbent: state.incumbent,
+            best: state.best,
+            iteration: state.iteration,
+            parameters: state.parameters,
+            heuristic: HeuristicState::default(),
+        }
+    }
+}
+
+impl<Solution> From<SearchState<Solution>> for OptimizeState<Solution> {
+    fn from(state: SearchState<Solution>) -> Self {
+        Self {
+            incumbent: state.incumbent,
+            best: state.best,
+            iteration: state.iteration,
+            parameters: state.parameters,
+            terminated: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+
+    use crate::{
+        acceptance::AcceptanceRule,
+        algorithms::sa::{FactorSchedule, SimulatedAnnealing},
+        pause::{PausableOptimize, PauseOutcome},
+        selectors::{RandomSelector, SequentialSelector},
+        termination::Terminator,
+        test::{NeighborSwap, Number},
+        Evaluate, ImprovingHeuristic,
+    };
+    use rand::SeedableRng;
+
+    #[test]
+    fn pause_and_resume() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0., 3., 1., 4., 6.];
+        let build = || {
+            let rng = rand::rngs::StdRng::seed_from_u64(7);
+            SimulatedAnnealing::builder()
+                .selector(RandomSelector::new(rng).option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(60).build())
+                .cooling_schedule(FactorSchedule::new(5., 0.95))
+                .seed(3)
+                .build()
+        };
+        let uninterrupted = build().optimize(Number::new(0, numbers[0]));
+
+        // request a pause after the 25th iteration
+        let pause = Arc::new(AtomicBool::new(false));
+        let iterations = Rc::new(Cell::new(0));
+        let count = |pause: Arc<AtomicBool>, iterations: Rc<Cell<usize>>| {
+            move |_: &Number, _: &Number| {
+                iterations.set(iterations.get() + 1);
+                if iterations.get() == 25 {
+                    pause.store(true, Ordering::SeqCst);
+                }
+            }
+        };
+        let sa = build()
+            .with_callbacks()
+            .on_accepted(count(pause.clone(), iterations.clone()))
+            .on_rejected(count(pause.clone(), iterations.clone()));
+
+        let state = match sa.optimize_pausable(Number::new(0, numbers[0]), &pause) {
+            Ok(PauseOutcome::Paused(state)) => state,
+            _ => panic!("search was not paused"),
+        };
+        assert_eq!(state.iteration(), 25);
+        #[cfg(feature = "serde")]
+        let state: crate::pause::SearchState<Number> =
+            serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+
+        pause.store(false, Ordering::SeqCst);
+        let resumed = match sa.resume(state, &pause) {
+            PauseOutcome::Finished(solution) => solution,
+            PauseOutcome::Paused(_) => panic!("search was paused again"),
+        };
+        assert_eq!(iterations.get(), 60);
+        assert_eq!(resumed.index(), uninterrupted.index());
+        assert_eq!(resumed.evaluate(), uninterrupted.evaluate());
+    }
+
+    #[test]
+    fn resume_with_new_heuristic() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0., 3., 1., 4., 6.];
+        let pause = Arc::new(AtomicBool::new(false));
+        // record the proposed candidates, and request a pause after the 25th iteration
+        let build = |candidates: Rc<RefCell<Vec<usize>>>| {
+            let record = |pause: Arc<AtomicBool>, candidates: Rc<RefCell<Vec<usize>>>| {
+                move |candidate: &Number, _: &Number| {
+                    candidates.borrow_mut().push(candidate.index());
+                    if candidates.borrow().len() == 25 {
+                        pause.store(true, Ordering::SeqCst);
+                    }
+                }
+            };
+            SimulatedAnnealing::builder()
+                .selector(SequentialSelector::new().option(NeighborSwap::new(&numbers)))
+                .terminator(Terminator::builder().iterations(60).build())
+                .cooling_schedule(FactorSchedule::new(5., 0.05))
+                .acceptance_rule(AcceptanceRule::Glauber)
+                .seed(3)
+                .build()
+                .with_callbacks()
+                .on_accepted(record(pause.clone(), candidates.clone()))
+                .on_rejected(record(pause.clone(), candidates))
+        };
+
+        let uninterrupted = Rc::new(RefCell::new(vec![]));
+        build(uninterrupted.clone()).optimize(Number::new(0, numbers[0]));
+        pause.store(false, Ordering::SeqCst);
+
+        let before = Rc::new(RefCell::new(vec![]));
+        let state =
+            match build(before.clone()).optimize_pausable(Number::new(0, numbers[0]), &pause) {
+                Ok(PauseOutcome::Paused(state)) => state,
+                _ => panic!("search was not paused"),
+            };
+        assert!(state.heuristic().rng().is_some());
+        assert!(state.heuristic().temperature().unwrap() < 5.);
+        #[cfg(feature = "serde")]
+        let state: crate::pause::SearchState<Number> =
+            serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+
+        // continue the record with a newly built heuristic
+        pause.store(false, Ordering::SeqCst);
+        let resumed = Rc::new(RefCell::new(before.borrow().clone()));
+        match build(resumed.clone()).resume(state, &pause) {
+            PauseOutcome::Finished(_) => {}
+            PauseOutcome::Paused(_) => panic!("search was paused again"),
+        }
+        assert_eq!(resumed.borrow().len(), 60);
+        assert_eq!(*resumed.borrow(), *uninterrupted.borrow());
+    }
+}
@@ -0,0 +1,413 @@
+//! Constraint handling by penalties, where the weight of the constraint violation adapts to the search
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+};
+
+use crate::{
+    pause::HeuristicState, Constrained, Evaluate, ImprovingHeuristic, Operator, OptimizeError,
+    OptimizeState, ReturnPolicy,
+};
+
+/// A solution whose constraints may be violated, where the objective of [Evaluate] excludes the violation.
+pub trait Penalizable: Evaluate {
+    /// Return by how much the constraints are violated, where 0 means the solution is feasible.
+    fn violation(&self) -> f32;
+}
+
+/// The weight of the constraint violation, which adapts to the feasibility of recent best solutions, after
+/// _Bean and Hadj-Alouane_, as surveyed by _Coello_.
+///
+/// After every ```window``` observations, the weight is multiplied by the increase factor if the best solution was
+/// infeasible every time, and divided by the decrease factor if it was feasible every time. The search is thus
+/// kept near the feasibility boundary, where constrained optima usually lie. The factors differ, by default
+/// 2 and 1.5, to prevent cycling.
+pub struct AdaptivePenalty {
+    weight: Cell<f32>,
+    window: usize,
+    increase: f32,
+    decrease: f32,
+    recent: RefCell<VecDeque<bool>>,
+}
+
+/// A solution evaluated by its objective plus the weighted violation, see [AdaptivePenalty].
+#[derive(Clone)]
+pub struct Penalized<Solution> {
+    solution: Solution,
+    penalty: Rc<AdaptivePenalty>,
+}
+
+/// Adapts an operator to [Penalized] solutions, which share the penalty of the solution they derive from.
+pub struct PenalizedOperator<Op> {
+    operator: Op,
+}
+
+/// Wraps a heuristic on [Penalized] solutions, and feeds the feasibility of its best solution to the
+/// [AdaptivePenalty] every iteration. The best feasible incumbent is remembered, as the best solution under a
+/// changing weight may be infeasible.
+///
+/// Optimize through a reference, such that the best feasible solution can be read after the run:
+/// ```(&search).optimize(initial)```, followed by ```search.best_feasible()```.
+pub struct AdaptivePenaltySearch<Solution, H> {
+    heuristic: H,
+    penalty: Rc<AdaptivePenalty>,
+    best_feasible: RefCell<Option<Solution>>,
+}
+
+impl AdaptivePenalty {
+    pub fn new(weight: f32, window: usize) -> Self {
+        assert!(window > 0, "observation window must be positive");
+        Self {
+            weight: Cell::new(weight),
+            window,
+            increase: 2.,
+            decrease: 1.5,
+            recent: RefCell::new(VecDeque::with_capacity(window)),
+        }
+    }
+
+    /// Set the factors by which the weight increases and decreases, which must both exceed 1 (default: 2 and 1.5)
+    pub fn factors(mut self, increase: f32, decrease: f32) -> Self {
+        assert!(
+            increase > 1. && decrease > 1.,
+            "penalty factors must exceed 1"
+        );
+        self.increase = increase;
+        self.decrease = decrease;
+        self
+    }
+
+    /// Return the current weight of the violation.
+    pub fn weight(&self) -> f32 {
+        self.weight.get()
+    }
+
+    /// Return the penalized objective.
+    pub fn penalize(&self, objective: f32, violation: f32) -> f32 {
+        objective + self.weight.get() * violation
+    }
+
+    /// Observe whether the best solution is feasible, and adapt the weight once the window is full.
+    pub fn observe(&self, feasible: bool) {
+        let mut recent = self.recent.borrow_mut();
+        recent.push_back(feasible);
+        if recent.len() < self.window {
+            return;
+        }
+        if recent.iter().all(|feasible| !feasible) {
+            self.weight.set(self.weight.get() * self.increase);
+        } else if recent.iter().all(|feasible| *feasible) {
+            self.weight.set(self.weight.get() / self.decrease);
+        }
+        recent.clear();
+    }
+}
+
+impl<Solution> Penalized<Solution> {
+    pub fn new(solution: Solution, penalty: &Rc<AdaptivePenalty>) -> Self {
+        Self {
+            solution,
+            penalty: penalty.clone(),
+        }
+    }
+
+    /// Get the wrapped solution.
+    pub fn solution(&self) -> &Solution {
+        &self.solution
+    }
+
+    /// Return the wrapped solution.
+    pub fn into_inner(self) -> Solution {
+        self.solution
+    }
+}
+
+impl<Solution: Penalizable> Evaluate for Penalized<Solution> {
+    fn evaluate(&self) -> f32 {
+        self.penalty
+            .penalize(self.solution.evaluate(), self.solution.violation())
+    }
+}
+
+impl<Solution: Penalizable> Constrained for Penalized<Solution> {
+    fn is_feasible(&self) -> bool {
+        self.solution.violation() <= 0.
+    }
+}
+
+impl<Op> PenalizedOperator<Op> {
+    pub fn new(operator: Op) -> Self {
+        Self { operator }
+    }
+}
+
+impl<Op> Operator for PenalizedOperator<Op>
+where
+    Op: Operator,
+    Op::Solution: Penalizable + 'static,
+{
+    type Solution = Penalized<Op::Solution>;
+
+    fn name(&self) -> &str {
+        self.operator.name()
+    }
+
+    fn construct_neighborhood(
+        &self,
+        solution: Self::Solution,
+    ) -> Box<dyn Iterator<Item = Self::Solution>> {
+        let penalty = solution.penalty;
+        Box::new(
+            self.operator
+                .construct_neighborhood(solution.solution)
+                .map(move |neighbor| Penalized::new(neighbor, &penalty)),
+        )
+    }
+
+    fn shake(&self, solution: Self::Solution, rng: &mut dyn rand::RngCore) -> Self::Solution {
+        let penalty = solution.penalty;
+        Penalized::new(self.operator.shake(solution.solution, rng), &penalty)
+    }
+
+    fn destroy(
+        &self,
+        solution: Self::Solution,
+        size: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Self::Solution {
+        let penalty = solution.penalty;
+        Penalized::new(
+            self.operator.destroy(solution.solution, size, rng),
+            &penalty,
+        )
+    }
+}
+
+impl<Solution, H> AdaptivePenaltySearch<Solution, H> {
+    pub fn new(heuristic: H, penalty: &Rc<AdaptivePenalty>) -> Self {
+        Self {
+            heuristic,
+            penalty: penalty.clone(),
+            best_feasible: RefCell::new(None),
+        }
+    }
+
+    /// Return the feasible incumbent with the best objective so far, if any.
+    pub fn best_feasible(&self) -> Option<Solution>
+    where
+        Solution: Clone,
+    {
+        self.best_feasible.borrow().clone()
+    }
+
+    /// Remember ```solution``` if it is feasible, and better than the best feasible solution so far.
+    fn observe_feasible(&self, solution: &Penalized<Solution>)
+    where
+        Solution: Penalizable + Clone,
+    {
+        if !solution.is_feasible() {
+            return;
+        }
+        let mut best_feasible = self.best_feasible.borrow_mut();
+        let improved = match best_feasible.as_ref() {
+            Some(best) => solution.solution.evaluate() < best.evaluate(),
+            None => true,
+        };
+        if improved {
+            best_feasible.replace(solution.solution.clone());
+        }
+    }
+}
+
+impl<Solution, H> ImprovingHeuristic<Penalized<Solution>> for &AdaptivePenaltySearch<Solution, H>
+where
+    Solution: Penalizable + Clone,
+    H: ImprovingHeuristic<Penalized<Solution>>,
+{
+    fn propose_candidate(&self, incumbent: Penalized<Solution>) -> Penalized<Solution> {
+        self.heuristic.propose_candidate(incumbent)
+    }
+
+    fn try_propose_candidate(&self, incumbent: Penalized<Solution>) -> Option<Penalized<Solution>> {
+        self.heuristic.try_propose_candidate(incumbent)
+    }
+
+    fn accept_candidate(
+        &self,
+        candidate: &Penalized<Solution>,
+        incumbent: &Penalized<Solution>,
+    ) -> bool {
+        self.heuristic.accept_candidate(candidate, incumbent)
+    }
+
+    fn should_terminate(&self, incumbent: &Penalized<Solution>) -> bool {
+        self.heuristic.should_terminate(incumbent)
+    }
+
+    /// Apply the guard of the wrapped heuristic, then remember the initial solution if it is feasible.
+    fn guard_initial(
+        &self,
+        initial: Penalized<Solution>,
+    ) -> Result<Penalized<Solution>, OptimizeError> {
+        let initial = self.heuristic.guard_initial(initial)?;
+        self.observe_feasible(&initial);
+        Ok(initial)
+    }
+
+    fn objective_epsilon(&self) -> f32 {
+        self.heuristic.objective_epsilon()
+    }
+
+    fn return_policy(&self) -> ReturnPolicy {
+        self.heuristic.return_policy()
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.heuristic.progress()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.heuristic.seed()
+    }
+
+    fn pause_state(&self) -> HeuristicState {
+        self.heuristic.pause_state()
+    }
+
+    fn resume_state(&self, state: &HeuristicState, iterations: usize) {
+        self.heuristic.resume_state(state, iterations)
+    }
+
+    fn callback_candidate_improved_best(
+        &self,
+        candidate: &Penalized<Solution>,
+        incumbent: &Penalized<Solution>,
+    ) {
+        self.heuristic
+            .callback_candidate_improved_best(candidate, incumbent)
+    }
+
+    fn callback_new_best(&self, best: &Penalized<Solution>) {
+        self.heuristic.callback_new_best(best)
+    }
+
+    fn callback_candidate_accepted(
+        &self,
+        candidate: &Penalized<Solution>,
+        incumbent: &Penalized<Solution>,
+    ) {
+        self.heuristic
+            .callback_candidate_accepted(candidate, incumbent)
+    }
+
+    fn callback_candidate_rejected(
+        &self,
+        candidate: &Penalized<Solution>,
+        incumbent: &Penalized<Solution>,
+    ) {
+        self.heuristic
+            .callback_candidate_rejected(candidate, incumbent)
+    }
+
+    /// Forward to the wrapped heuristic, then remember the incumbent if it is the best feasible solution, and
+    /// feed the feasibility of the best solution to the penalty.
+    fn on_iteration_end(&self, state: &mut OptimizeState<Penalized<Solution>>) {
+        self.heuristic.on_iteration_end(state);
+        self.observe_feasible(state.incumbent());
+        self.penalty.observe(state.best().is_feasible());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use rand::{Rng, SeedableRng};
+
+    use crate::{
+        algorithms::local_search::LocalSearch,
+        penalty::{
+            AdaptivePenalty, AdaptivePenaltySearch, Penalizable, Penalized, PenalizedOperator,
+        },
+        selectors::SequentialSelector,
+        termination::Terminator,
+        Evaluate, ImprovingHeuristic, Operator,
+    };
+
+    /// Maximize the level, which must not exceed 10, i.e. the optimum lies on the boundary.
+    #[derive(Clone, Debug)]
+    struct Level(i32);
+
+    impl Evaluate for Level {
+        fn evaluate(&self) -> f32 {
+            -self.0 as f32
+        }
+    }
+
+    impl Penalizable for Level {
+        fn violation(&self) -> f32 {
+            (self.0 - 10).max(0) as f32
+        }
+    }
+
+    struct Step;
+
+    impl Operator for Step {
+        type Solution = Level;
+
+        fn shake(&self, solution: Level, rng: &mut dyn rand::RngCore) -> Level {
+            match rng.gen_bool(0.5) {
+                true => Level((solution.0 + 1).min(20)),
+                false => Level((solution.0 - 1).max(0)),
+            }
+        }
+    }
+
+    #[test]
+    fn adaptive_penalty_weight() {
+        let penalty = AdaptivePenalty::new(1., 2);
+        penalty.observe(false);
+        assert_eq!(penalty.weight(), 1.);
+        penalty.observe(false);
+        assert_eq!(penalty.weight(), 2.);
+        // mixed feasibility keeps the weight
+        penalty.observe(true);
+        penalty.observe(false);
+        assert_eq!(penalty.weight(), 2.);
+        penalty.observe(true);
+        penalty.observe(true);
+        assert_eq!(penalty.weight(), 2. / 1.5);
+    }
+
+    #[test]
+    fn adaptive_penalty_finds_boundary() {
+        // return the weight, and the best feasible level, after a run of ```iterations```
+        let run = |iterations: usize| -> (f32, i32) {
+            // the initial weight is far too low, such that the unconstrained optimum of 20 is best at first
+            let penalty = Rc::new(AdaptivePenalty::new(0.1, 5));
+            let search = AdaptivePenaltySearch::new(
+                LocalSearch::builder()
+                    .selector(SequentialSelector::new().option(PenalizedOperator::new(Step)))
+                    .terminator(Terminator::builder().iterations(iterations).build())
+                    .rng(rand::rngs::StdRng::seed_from_u64(0))
+                    .build(),
+                &penalty,
+            );
+            (&search).optimize(Penalized::new(Level(0), &penalty));
+            (penalty.weight(), search.best_feasible().unwrap().0)
+        };
+
+        // the weight oscillates around 1, where stepping across the boundary breaks even
+        let mut weights = vec![];
+        for iterations in (400..=800).step_by(20) {
+            let (weight, best_feasible) = run(iterations);
+            assert_eq!(best_feasible, 10);
+            weights.push(weight);
+        }
+        assert!(weights.iter().all(|weight| (0.25..64.).contains(weight)));
+        weights.sort_by(f32::total_cmp);
+        let median = weights[weights.len() / 2];
+        assert!((0.5..=4.).contains(&median), "median weight {}", median);
+    }
+}
@@ -0,0 +1,203 @@
+//! Chain a construction heuristic and several improving heuristics
+use std::time::SystemTime;
+
+use crate::{default_rng, Constructor, Evaluate, ImprovingHeuristic, Outcome};
+
+/// Constructs an initial solution, then improves it by running heuristics in sequence, each starting from the best solution of the previous stage.
+pub struct Pipeline<Solution> {
+    constructor: Constructor<Solution>,
+    stages: Vec<Box<dyn ImprovingHeuristic<Solution>>>,
+    rng: Box<dyn rand::RngCore>,
+}
+
+/// Builder design pattern for [Pipeline].
+pub struct PipelineBuilder<Solution> {
+    constructor: Option<Constructor<Solution>>,
+    stages: Vec<Box<dyn ImprovingHeuristic<Solution>>>,
+    rng: Option<Box<dyn rand::RngCore>>,
+}
+
+/// The outcomes of all stages of a [Pipeline], starting with the construction.
+pub struct PipelineOutcome<Solution> {
+    stages: Vec<Outcome<Solution>>,
+}
+
+impl<Solution> Pipeline<Solution> {
+    pub fn builder() -> PipelineBuilder<Solution> {
+        PipelineBuilder {
+            constructor: None,
+            stages: vec![],
+            rng: None,
+        }
+    }
+
+    /// Construct an initial solution and pass it through all stages.
+    pub fn run(mut self) -> PipelineOutcome<Solution>
+    where
+        Solution: Clone + Evaluate,
+    {
+        let now = SystemTime::now();
+        let initial = (self.constructor)(self.rng.as_mut());
+        let duration = now.elapsed().expect("failed to time for duration");
+        let mut stages = vec![Outcome::new(initial, duration)];
+
+        for heuristic in self.stages {
+            let initial = stages[stages.len() - 1].solution().clone();
+            stages.push(heuristic.optimize_timed(initial));
+        }
+
+        PipelineOutcome { stages }
+    }
+}
+
+impl<Solution> PipelineBuilder<Solution> {
+    /// Build the configured pipeline
+    pub fn build(self) -> Pipeline<Solution> {
+        Pipeline {
+            constructor: self.constructor.expect("No constructor specified"),
+            stages: self.stages,
+            rng: self.rng.unwrap_or_else(|| default_rng("PipelineBuilder")),
+        }
+    }
+
+    /// Set the construction heuristic which creates the initial solution
+    pub fn constructor<F: Fn(&mut dyn rand::RngCore) -> Solution + 'static>(
+        mut self,
+        constructor: F,
+    ) -> Self {
+        self.constructor = Some(Box::new(constructor));
+        self
+    }
+
+    /// Append an improving heuristic to the pipeline
+    pub fn stage<H: ImprovingHeuristic<Solution> + 'static>(mut self, heuristic: H) -> Self {
+        self.stages.push(Box::new(heuristic));
+        self
+    }
+
+    /// Set source of randomness for the constructor (default: seeded with [crate::DEFAULT_SEED])
+    pub fn rng<T: rand::RngCore + 'static>(mut self, rng: T) -> Self {
+        self.rng = Some(Box::new(rng));
+        self
+    }
+}
+
+impl<Solution> PipelineOutcome<Solution> {
+    /// Return the final solution, along with the total computation time of all stages.
+    pub fn combined(&self) -> Outcome<Solution>
+    where
+        Solution: Clone,
+    {
+        let duration = self.stages.iter().map(|stage| stage.duration()).sum();
+        Outcome::new(self.best().clone(), duration)
+    }
+
+    /// Return the solution of the last stage.
+    pub fn best(&self) -> &Solution {
+        self.stages[self.stages.len() - 1].solution()
+    }
+
+    /// Return the outcome of every stage, starting with the construction.
+    pub fn stages(&self) -> &[Outcome<Solution>] {
+        &self.stages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::{
+        algorithms::{
+            sa::{FactorSchedule, SimulatedAnnealing},
+            vns::VariableNeighborhoodSearch,
+        },
+        pipeline::Pipeline,
+        selectors::{RandomSelector, SequentialSelector},
+        termination::{Deadline, IterationTerminator, Terminator, TimeTerminator},
+        test::*,
+        Evaluate,
+    };
+
+    #[test]
+    fn pipeline_improves_every_stage() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+
+        let vns = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(NeighborsUpUntilN::new(&numbers, 1)))
+            .terminator(IterationTerminator::new(10))
+            .build();
+        let sa = SimulatedAnnealing::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+            .terminator(Terminator::builder().iterations(100).build())
+            .rng(rng.clone())
+            .cooling_schedule(FactorSchedule::new(100., 0.05))
+            .build();
+
+        // greedily pick the best of the first two numbers
+        let first_two = numbers[..2].to_vec();
+        let outcome = Pipeline::builder()
+            .constructor(move |_| {
+                let index = if first_two[0] <= first_two[1] { 0 } else { 1 };
+                Number::new(index, first_two[index])
+            })
+            .stage(vns)
+            .stage(sa)
+            .rng(rng)
+            .build()
+            .run();
+
+        let stages = outcome.stages();
+        assert_eq!(stages.len(), 3);
+        assert_eq!(stages[0].solution().index(), 1);
+        assert_eq!(stages[1].solution().index(), 2);
+        for stage in stages {
+            assert!(outcome.best().evaluate() <= stage.solution().evaluate());
+        }
+        assert_eq!(outcome.combined().solution().index(), 7);
+    }
+
+    #[test]
+    fn stages_share_deadline() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let budget = std::time::Duration::from_millis(50);
+        let deadline = Deadline::after(budget);
+        let start = std::time::Instant::now();
+
+        // without the deadline, either stage would run for a long time
+        let stage = |rng: rand::rngs::StdRng| {
+            SimulatedAnnealing::builder()
+                .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&numbers)))
+                .terminator(
+                    Terminator::builder()
+                        .iterations(usize::MAX)
+                        .deadline(deadline.clone())
+                        .build(),
+                )
+                .rng(rng)
+                .cooling_schedule(FactorSchedule::new(100., 0.05))
+                .build()
+        };
+        let last = VariableNeighborhoodSearch::builder()
+            .selector(SequentialSelector::new().option(NeighborsUpUntilN::new(&numbers, 1)))
+            .terminator(TimeTerminator::until(deadline.clone()))
+            .build();
+        let outcome = Pipeline::builder()
+            .constructor(|_| Number::new(0, 9.))
+            .stage(stage(rng.clone()))
+            .stage(stage(rng.clone()))
+            .stage(last)
+            .rng(rng)
+            .build()
+            .run();
+
+        let elapsed = start.elapsed();
+        assert!(deadline.is_expired());
+        assert!(elapsed >= budget);
+        // the first stage consumes the budget, after which the others finish their first iteration only
+        assert!(elapsed < budget * 4, "took {elapsed:?}");
+        assert!(outcome.stages()[1].duration() >= budget.mul_f32(0.9));
+    }
+}
@@ -0,0 +1,170 @@
+//! Building blocks for population-based heuristics
+use crate::{is_improvement, Distance, Evaluate, Objective};
+
+/// Decide which individuals survive into the next generation.
+pub trait Replacement<Solution> {
+    /// Construct the next population from the current population and its offspring.
+    fn replace(&self, population: Vec<Solution>, offspring: Vec<Solution>) -> Vec<Solution>;
+}
+
+/// The offspring fully replace the population.
+pub struct Generational;
+
+/// The ```n``` best offspring replace the ```n``` worst individuals of the population.
+pub struct SteadyState(pub usize);
+
+/// The ```k``` best individuals of the population survive, the rest is replaced by the best offspring.
+pub struct Elitist(pub usize);
+
+/// _Deterministic crowding_: every offspring, in turn, replaces the individual which is most similar to it,
+/// according to [Distance], if the offspring is better.
+///
+/// Offspring thus compete within their niche only, which preserves diversity on multimodal problems.
+pub struct Crowding;
+
+impl<Solution> Replacement<Solution> for Generational {
+    fn replace(&self, _population: Vec<Solution>, offspring: Vec<Solution>) -> Vec<Solution> {
+        offspring
+    }
+}
+
+impl<Solution: Evaluate> Replacement<Solution> for SteadyState {
+    fn replace(
+        &self,
+        mut population: Vec<Solution>,
+        mut offspring: Vec<Solution>,
+    ) -> Vec<Solution> {
+        sort_by_objective(&mut population);
+        sort_by_objective(&mut offspring);
+        let n = self.0.min(population.len()).min(offspring.len());
+        population.truncate(population.len() - n);
+        population.extend(offspring.into_iter().take(n));
+        population
+    }
+}
+
+impl<Solution: Evaluate> Replacement<Solution> for Elitist {
+    fn replace(
+        &self,
+        mut population: Vec<Solution>,
+        mut offspring: Vec<Solution>,
+    ) -> Vec<Solution> {
+        let size = population.len();
+        sort_by_objective(&mut population);
+        sort_by_objective(&mut offspring);
+        population.truncate(self.0.min(size));
+        let n = size - population.len();
+        population.extend(offspring.into_iter().take(n));
+        population
+    }
+}
+
+impl<Solution: Evaluate + Distance> Replacement<Solution> for Crowding {
+    fn replace(&self, mut population: Vec<Solution>, offspring: Vec<Solution>) -> Vec<Solution> {
+        for child in offspring {
+            let nearest = population
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.distance(&child).total_cmp(&b.distance(&child)))
+                .map(|(index, _)| index);
+            if let Some(index) = nearest {
+                if is_improvement(child.evaluate(), population[index].evaluate(), 0.) {
+                    population[index] = child;
+                }
+            }
+        }
+        population
+    }
+}
+
+/// Sort from best to worst objective.
+pub(crate) fn sort_by_objective<Solution: Evaluate>(population: &mut [Solution]) {
+    population.sort_by_key(|x| Objective(x.evaluate()));
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use crate::{
+        population::{Crowding, Elitist, Generational, Replacement, SteadyState},
+        test::Number,
+        Evaluate,
+    };
+
+    fn random_population(size: usize, offset: usize, rng: &mut dyn rand::RngCore) -> Vec<Number> {
+        (0..size)
+            .map(|index| Number::new(offset + index, rng.gen::<f32>()))
+            .collect()
+    }
+
+    fn best(population: &[Number]) -> f32 {
+        population
+            .iter()
+            .map(|x| x.evaluate())
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    #[test]
+    fn elitist_keeps_best() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut population = random_population(10, 0, &mut rng);
+        for generation in 1..50 {
+            let best_before = best(&population);
+            let offspring = random_population(10, generation * 10, &mut rng);
+            population = Elitist(1).replace(population, offspring);
+            assert_eq!(population.len(), 10);
+            assert!(best(&population) <= best_before);
+        }
+    }
+
+    #[test]
+    fn crowding_keeps_both_peaks() {
+        // two peaks, at 20 and at 80, where the one at 20 is slightly better
+        let landscape = |index: usize| -> f32 {
+            (index.abs_diff(20) as f32).min(index.abs_diff(80) as f32 + 0.5)
+        };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut population: Vec<Number> = (0..20)
+            .map(|_| {
+                let index = rng.gen_range(0..100);
+                Number::new(index, landscape(index))
+            })
+            .collect();
+        for _ in 0..200 {
+            let offspring: Vec<Number> = population
+                .iter()
+                .map(|parent| {
+                    let index = (parent.index() + rng.gen_range(0..7))
+                        .saturating_sub(3)
+                        .min(99);
+                    Number::new(index, landscape(index))
+                })
+                .collect();
+            population = Crowding.replace(population, offspring);
+        }
+
+        assert_eq!(population.len(), 20);
+        assert!(population.iter().any(|x| x.index().abs_diff(20) <= 2));
+        assert!(population.iter().any(|x| x.index().abs_diff(80) <= 2));
+    }
+
+    #[test]
+    fn generational_replaces_all() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let population = random_population(10, 0, &mut rng);
+        let offspring = random_population(10, 10, &mut rng);
+        let population = Generational.replace(population, offspring);
+        assert!(population.iter().all(|x| x.index() >= 10));
+    }
+
+    #[test]
+    fn steady_state_replaces_worst() {
+        let population: Vec<Number> = (0..4).map(|i| Number::new(i, i as f32)).collect();
+        let offspring: Vec<Number> = (4..8).map(|i| Number::new(i, i as f32 - 5.)).collect();
+        let population = SteadyState(2).replace(population, offspring);
+        let mut indices: Vec<usize> = population.iter().map(|x| x.index()).collect();
+        indices.sort();
+        assert_eq!(indices, vec![0, 1, 4, 5]);
+    }
+}
@@ -1,9 +1,9 @@
 //! Select the next operator to be used
-use std::{cell::RefCell, ops::SubAssign};
+use std::{cell::RefCell, ops::SubAssign, rc::Rc};
 
 use rand::Rng;
 
-use crate::{Evaluate, Operator, ProposalEvaluation};
+use crate::{is_improvement, Evaluate, Objective, Operator, ProposalEvaluation};
 
 /// Give the next operator based on certain rules.
 #[allow(unused_variables)]
@@ -13,21 +13,80 @@ pub trait OperatorSelector<Solution> {
 
     /// Give feedback on the last selected operator
     fn feedback(&self, status: ProposalEvaluation) {}
+
+    /// Return the index of the last selected operator, in the order in which the operators were added.
+    fn last_selected(&self) -> Option<usize> {
+        None
+    }
+
+    /// Return the number of operators to select from, if known.
+    fn operator_count(&self) -> Option<usize> {
+        None
+    }
+
+    /// Return the operator at ```index```, in the order in which the operators were added, without selecting it,
+    /// i.e. without changing the state of the selector.
+    fn operator(&self, index: usize) -> Option<&dyn Operator<Solution = Solution>> {
+        None
+    }
+}
+
+/// Share a selector, e.g. to inspect its state after a search.
+impl<Solution, T: OperatorSelector<Solution> + ?Sized> OperatorSelector<Solution> for Rc<T> {
+    fn select(&self, solution: &dyn Evaluate) -> &dyn Operator<Solution = Solution> {
+        self.as_ref().select(solution)
+    }
+
+    fn feedback(&self, status: ProposalEvaluation) {
+        self.as_ref().feedback(status)
+    }
+
+    fn last_selected(&self) -> Option<usize> {
+        self.as_ref().last_selected()
+    }
+
+    fn operator_count(&self) -> Option<usize> {
+        self.as_ref().operator_count()
+    }
+
+    fn operator(&self, index: usize) -> Option<&dyn Operator<Solution = Solution>> {
+        self.as_ref().operator(index)
+    }
+}
+
+/// Maps the current solution to the index of the operator to be selected.
+pub type SelectionRule = Box<dyn Fn(&dyn Evaluate) -> usize>;
+
+/// Select the operator at the index which a rule assigns to the current solution
+///
+/// Enables rule-based selection, e.g. a repair-heavy operator whenever the solution is infeasible.
+/// Indices follow the order in which the operators were added.
+pub struct ConditionalSelector<Solution> {
+    operators: Vec<Box<dyn Operator<Solution = Solution>>>,
+    rule: SelectionRule,
+    index_last_selection: RefCell<Option<usize>>,
 }
 
 /// Select operators in a consecutive manner
 ///
-/// Iterate through all operators, consecutively, starting from the first one. When an improvement is made, the iteration is restarted from the beginning.
+/// Iterate through all operators, consecutively, starting from the first one. When an improvement is made, the iteration is restarted from the beginning,
+/// unless the selector is [SequentialSelector::cyclic]. The operators are iterated in the order in which they were added,
+/// unless reordered, e.g. by [SequentialSelector::order_by_improvement].
 pub struct SequentialSelector<Solution> {
     operators: Vec<Box<dyn Operator<Solution = Solution>>>,
+    order: RefCell<Vec<usize>>,
     operator_index: RefCell<usize>,
     objective_best: RefCell<f32>,
+    index_last_selection: RefCell<Option<usize>>,
+    cyclic: bool,
+    objective_epsilon: f32,
 }
 
 /// Select the next operator uniformly at random
 pub struct RandomSelector<Solution> {
     operators: Vec<Box<dyn Operator<Solution = Solution>>>,
     rng: RefCell<Box<dyn rand::RngCore>>,
+    index_last_selection: RefCell<Option<usize>>,
 }
 
 /// Select the next operator adaptively
@@ -37,12 +96,23 @@ pub struct RandomSelector<Solution> {
 pub struct AdaptiveSelector<Solution> {
     rng: RefCell<Box<dyn rand::RngCore>>,
     options: Vec<Box<dyn Operator<Solution = Solution>>>,
-    weights: Vec<f32>,
+    weights: RefCell<Vec<f32>>,
     decay: f32,
     index_last_selection: RefCell<Option<usize>>,
     weight_improve_best: f32,
     weight_accept: f32,
     weight_reject: f32,
+    removal: Option<OperatorRemoval>,
+    segments_below_threshold: RefCell<Vec<usize>>,
+    active: RefCell<Vec<bool>>,
+    deterministic_until_differentiated: bool,
+    index_round_robin: RefCell<usize>,
+}
+
+/// Disable operators whose weight stays below ```threshold``` for ```segments``` consecutive feedbacks.
+struct OperatorRemoval {
+    threshold: f32,
+    segments: usize,
 }
 
 impl<Solution> AdaptiveSelector<Solution> {
@@ -55,11 +125,16 @@ impl<Solution> AdaptiveSelector<Solution> {
             rng: RefCell::new(Box::new(rng)),
             decay,
             options: vec![],
-            weights: vec![],
+            weights: RefCell::new(vec![]),
             index_last_selection: RefCell::new(None),
             weight_improve_best: 3.,
             weight_accept: 1.,
             weight_reject: 0.,
+            removal: None,
+            segments_below_threshold: RefCell::new(vec![]),
+            active: RefCell::new(vec![]),
+            deterministic_until_differentiated: false,
+            index_round_robin: RefCell::new(0),
         }
     }
 
@@ -75,43 +150,131 @@ impl<Solution> AdaptiveSelector<Solution> {
             rng: RefCell::new(Box::new(rng)),
             decay,
             options: vec![],
-            weights: vec![],
+            weights: RefCell::new(vec![]),
             index_last_selection: RefCell::new(None),
             weight_improve_best,
             weight_accept,
             weight_reject,
-        }
-    }
-
-    /// Give feedback on the last chosen operator based on the last proposed candidate.
-    pub fn feedback(&mut self, status: ProposalEvaluation) {
-        if let Some(index) = self.index_last_selection.borrow().as_ref() {
-            let index = *index;
-            let weight = match status {
-                ProposalEvaluation::ImprovedBest => self.weight_improve_best,
-                ProposalEvaluation::Accept => self.weight_accept,
-                ProposalEvaluation::Reject => self.weight_reject,
-            };
-            self.weights[index] = (1. - self.decay) * self.weights[index] + self.decay * weight;
+            removal: None,
+            segments_below_threshold: RefCell::new(vec![]),
+            active: RefCell::new(vec![]),
+            deterministic_until_differentiated: false,
+            index_round_robin: RefCell::new(0),
         }
     }
 
     /// Add operator to the operator pool
     pub fn operator<T: Operator<Solution = Solution> + 'static>(mut self, option: T) -> Self {
         self.options.push(Box::new(option));
-        self.weights.push(1.);
+        self.weights.get_mut().push(1.);
+        self.segments_below_threshold.get_mut().push(0);
+        self.active.get_mut().push(true);
+        self
+    }
+
+    /// Exclude an operator from selection once its weight stayed below ```threshold``` for ```segments``` consecutive feedbacks.
+    ///
+    /// Removed operators are kept for reporting, and can be re-enabled with [AdaptiveSelector::reinstate_all].
+    /// The last active operator is never removed.
+    pub fn removal(mut self, threshold: f32, segments: usize) -> Self {
+        self.removal = Some(OperatorRemoval {
+            threshold,
+            segments,
+        });
         self
     }
+
+    /// Cycle through the operators in order, as long as all active operators have equal weights.
+    ///
+    /// Once feedback differentiates the weights, selection is weighted random.
+    pub fn deterministic_until_differentiated(mut self) -> Self {
+        self.deterministic_until_differentiated = true;
+        self
+    }
+
+    /// Test whether the operator at ```index``` can still be selected.
+    pub fn is_active(&self, index: usize) -> bool {
+        self.active.borrow()[index]
+    }
+
+    /// Return the current weights of all operators, including removed ones.
+    pub fn weights(&self) -> Vec<f32> {
+        self.weights.borrow().clone()
+    }
+
+    /// Re-enable all removed operators.
+    pub fn reinstate_all(&self) {
+        self.active
+            .borrow_mut()
+            .iter_mut()
+            .for_each(|active| *active = true);
+        self.segments_below_threshold
+            .borrow_mut()
+            .iter_mut()
+            .for_each(|segments| *segments = 0);
+    }
+
+    /// Test whether the active operators have different weights.
+    fn differentiated(&self) -> bool {
+        let active = self.active.borrow();
+        let weights = self.weights.borrow();
+        let mut weights = (0..self.options.len())
+            .filter(|&i| active[i])
+            .map(|i| weights[i]);
+        match weights.next() {
+            Some(first) => weights.any(|weight| weight != first),
+            None => false,
+        }
+    }
+
+    fn select_round_robin(&self) -> &dyn Operator<Solution = Solution> {
+        let n = self.options.len();
+        let start = *self.index_round_robin.borrow();
+        let active = self.active.borrow();
+        let index = (0..n)
+            .map(|offset| (start + offset) % n)
+            .find(|&i| active[i])
+            .expect("Could not select operator");
+        self.index_round_robin.replace((index + 1) % n);
+        self.index_last_selection.replace(Some(index));
+        self.options[index].as_ref()
+    }
+
+    fn update_removal(&self, index: usize) {
+        if let Some(removal) = &self.removal {
+            let mut segments_below_threshold = self.segments_below_threshold.borrow_mut();
+            if self.weights.borrow()[index] < removal.threshold {
+                segments_below_threshold[index] += 1;
+            } else {
+                segments_below_threshold[index] = 0;
+            }
+
+            let mut active = self.active.borrow_mut();
+            let number_active = active.iter().filter(|active| **active).count();
+            if segments_below_threshold[index] >= removal.segments && number_active > 1 {
+                active[index] = false;
+            }
+        }
+    }
 }
 
 impl<Solution> OperatorSelector<Solution> for AdaptiveSelector<Solution> {
     fn select(&self, _solution: &dyn Evaluate) -> &dyn Operator<Solution = Solution> {
-        let ref rng = self.rng;
-        let denom: f32 = self.weights.iter().sum();
+        if self.deterministic_until_differentiated && !self.differentiated() {
+            return self.select_round_robin();
+        }
+
+        let rng = &self.rng;
+        let active = self.active.borrow();
+        let weights = self.weights.borrow();
+        let denom: f32 = (0..self.options.len())
+            .filter(|&i| active[i])
+            .map(|i| weights[i])
+            .sum();
         let mut sum = 0.;
         let r = rng.borrow_mut().gen::<f32>() * denom;
-        for i in 0..self.options.len() {
-            sum += self.weights[i];
+        for i in (0..self.options.len()).filter(|&i| active[i]) {
+            sum += weights[i];
             if r <= sum {
                 self.index_last_selection.replace(Some(i));
                 return self.options[i].as_ref();
@@ -120,6 +283,36 @@ impl<Solution> OperatorSelector<Solution> for AdaptiveSelector<Solution> {
 
         panic!("Could not select operator");
     }
+
+    /// Reward the last selected operator based on the last proposed candidate, and remove it if its weight stayed
+    /// too low, see [AdaptiveSelector::removal].
+    fn feedback(&self, status: ProposalEvaluation) {
+        let index_last_selection = *self.index_last_selection.borrow();
+        if let Some(index) = index_last_selection {
+            let weight = match status {
+                ProposalEvaluation::ImprovedBest => self.weight_improve_best,
+                ProposalEvaluation::Accept => self.weight_accept,
+                ProposalEvaluation::Reject => self.weight_reject,
+            };
+            {
+                let mut weights = self.weights.borrow_mut();
+                weights[index] = (1. - self.decay) * weights[index] + self.decay * weight;
+            }
+            self.update_removal(index);
+        }
+    }
+
+    fn last_selected(&self) -> Option<usize> {
+        *self.index_last_selection.borrow()
+    }
+
+    fn operator_count(&self) -> Option<usize> {
+        Some(self.options.len())
+    }
+
+    fn operator(&self, index: usize) -> Option<&dyn Operator<Solution = Solution>> {
+        self.options.get(index).map(|operator| operator.as_ref())
+    }
 }
 
 impl<Solution> RandomSelector<Solution> {
@@ -127,6 +320,7 @@ impl<Solution> RandomSelector<Solution> {
         Self {
             operators: vec![],
             rng: RefCell::new(Box::new(rng)),
+            index_last_selection: RefCell::new(None),
         }
     }
 
@@ -139,39 +333,211 @@ impl<Solution> RandomSelector<Solution> {
 impl<Solution> OperatorSelector<Solution> for RandomSelector<Solution> {
     fn select(&self, _solution: &dyn Evaluate) -> &dyn Operator<Solution = Solution> {
         let index = self.rng.borrow_mut().gen_range(0..self.operators.len());
+        self.index_last_selection.replace(Some(index));
+        self.operators[index].as_ref()
+    }
+
+    fn last_selected(&self) -> Option<usize> {
+        *self.index_last_selection.borrow()
+    }
+
+    fn operator_count(&self) -> Option<usize> {
+        Some(self.operators.len())
+    }
+
+    fn operator(&self, index: usize) -> Option<&dyn Operator<Solution = Solution>> {
+        self.operators.get(index).map(|operator| operator.as_ref())
+    }
+}
+
+impl<Solution> ConditionalSelector<Solution> {
+    pub fn new<F: Fn(&dyn Evaluate) -> usize + 'static>(rule: F) -> Self {
+        Self {
+            operators: vec![],
+            rule: Box::new(rule),
+            index_last_selection: RefCell::new(None),
+        }
+    }
+
+    pub fn option<T: Operator<Solution = Solution> + 'static>(mut self, option: T) -> Self {
+        self.operators.push(Box::new(option));
+        self
+    }
+}
+
+impl<Solution> OperatorSelector<Solution> for ConditionalSelector<Solution> {
+    /// Panics if the rule returns an index without operator.
+    fn select(&self, solution: &dyn Evaluate) -> &dyn Operator<Solution = Solution> {
+        let index = (self.rule)(solution);
+        assert!(
+            index < self.operators.len(),
+            "rule selected operator {index}, but only {} were added",
+            self.operators.len()
+        );
+        self.index_last_selection.replace(Some(index));
         self.operators[index].as_ref()
     }
+
+    fn last_selected(&self) -> Option<usize> {
+        *self.index_last_selection.borrow()
+    }
+
+    fn operator_count(&self) -> Option<usize> {
+        Some(self.operators.len())
+    }
+
+    fn operator(&self, index: usize) -> Option<&dyn Operator<Solution = Solution>> {
+        self.operators.get(index).map(|operator| operator.as_ref())
+    }
 }
 
 impl<Solution> SequentialSelector<Solution> {
     pub fn new() -> Self {
         Self {
             operators: vec![],
-            objective_best: RefCell::new(std::f32::INFINITY),
+            order: RefCell::new(vec![]),
+            objective_best: RefCell::new(f32::INFINITY),
             operator_index: RefCell::new(0),
+            index_last_selection: RefCell::new(None),
+            cyclic: false,
+            objective_epsilon: 0.,
         }
     }
 
     pub fn option<T: Operator<Solution = Solution> + 'static>(mut self, option: T) -> Self {
+        self.order.get_mut().push(self.operators.len());
         self.operators.push(Box::new(option));
         self
     }
+
+    /// Advance to the next operator on every selection, regardless of improvements, i.e. never restart from the first one.
+    pub fn cyclic(mut self) -> Self {
+        self.cyclic = true;
+        self
+    }
+
+    /// Only restart from the first operator when the best objective improves by more than ```epsilon``` (default: 0),
+    /// such that float jitter does not keep resetting the cycle. Usually the epsilon of the heuristic, e.g.
+    /// [crate::algorithms::vns::VNSBuilder::objective_epsilon].
+    pub fn objective_epsilon(mut self, epsilon: f32) -> Self {
+        self.objective_epsilon = epsilon;
+        self
+    }
+
+    /// Return the indices of the operators, in the order in which they are iterated.
+    pub fn order(&self) -> Vec<usize> {
+        self.order.borrow().clone()
+    }
+
+    /// Iterate the operators from most to least promising, i.e. by their average improvement of ```incumbents```
+    /// to their best neighbor, where ties keep their order. Selection restarts from the first operator.
+    pub fn order_by_improvement(&self, incumbents: &[Solution])
+    where
+        Solution: Evaluate + Clone,
+    {
+        let improvements: Vec<f32> = self
+            .operators
+            .iter()
+            .map(|operator| {
+                let total: f32 = incumbents
+                    .iter()
+                    .map(|incumbent| {
+                        let objective = incumbent.evaluate();
+                        // an empty neighborhood does not improve
+                        match operator.try_find_best_neighbor(incumbent.clone()) {
+                            Some(neighbor)
+                                if is_improvement(neighbor.evaluate(), objective, 0.) =>
+                            {
+                                objective - neighbor.evaluate()
+                            }
+                            _ => 0.,
+                        }
+                    })
+                    .sum();
+                total / incumbents.len().max(1) as f32
+            })
+            .collect();
+
+        self.order
+            .borrow_mut()
+            .sort_by_key(|index| std::cmp::Reverse(Objective(improvements[*index])));
+        self.operator_index.replace(0);
+        self.objective_best.replace(f32::INFINITY);
+        self.index_last_selection.replace(None);
+    }
+
+    /// Walk randomly from ```initial```, moving to a random neighbor, of a random operator, every step, and return
+    /// the first ```steps``` solutions of the walk, ```initial``` included.
+    pub(crate) fn random_walk(
+        &self,
+        initial: &Solution,
+        steps: usize,
+        rng: &mut dyn rand::RngCore,
+    ) -> Vec<Solution>
+    where
+        Solution: Evaluate + Clone,
+    {
+        let mut walk = vec![initial.clone()];
+        while walk.len() < steps && !self.operators.is_empty() {
+            let operator = &self.operators[rng.gen_range(0..self.operators.len())];
+            let current = walk
+                .last()
+                .expect("walk starts at the initial solution")
+                .clone();
+            let mut neighbors: Vec<Solution> = operator.construct_neighborhood(current).collect();
+            if !neighbors.is_empty() {
+                let next = neighbors.swap_remove(rng.gen_range(0..neighbors.len()));
+                walk.push(next);
+            }
+        }
+        walk.truncate(steps);
+        walk
+    }
+}
+
+impl<Solution> Default for SequentialSelector<Solution> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<Solution> OperatorSelector<Solution> for SequentialSelector<Solution> {
     fn select(&self, solution: &dyn Evaluate) -> &dyn Operator<Solution = Solution> {
         let objective = solution.evaluate();
         let k = *self.operator_index.borrow();
-        if objective < *self.objective_best.borrow() {
+        if self.cyclic {
+            let position = match *self.index_last_selection.borrow() {
+                Some(_) => (k + 1) % self.operators.len(),
+                None => 0,
+            };
+            self.operator_index.replace(position);
+        } else if is_improvement(
+            objective,
+            *self.objective_best.borrow(),
+            self.objective_epsilon,
+        ) {
             self.objective_best.replace(objective);
             self.operator_index.borrow_mut().sub_assign(k);
         } else {
             self.operator_index.replace((k + 1) % self.operators.len());
         }
 
-        let index = *self.operator_index.borrow();
+        let index = self.order.borrow()[*self.operator_index.borrow()];
+        self.index_last_selection.replace(Some(index));
         self.operators[index].as_ref()
     }
+
+    fn last_selected(&self) -> Option<usize> {
+        *self.index_last_selection.borrow()
+    }
+
+    fn operator_count(&self) -> Option<usize> {
+        Some(self.operators.len())
+    }
+
+    fn operator(&self, index: usize) -> Option<&dyn Operator<Solution = Solution>> {
+        self.operators.get(index).map(|operator| operator.as_ref())
+    }
 }
 
 #[cfg(test)]
@@ -180,7 +546,13 @@ mod tests {
     use assert_approx_eq::assert_approx_eq;
     use rand::SeedableRng;
 
-    use crate::{selectors::AdaptiveSelector, ProposalEvaluation};
+    use crate::{
+        selectors::{
+            AdaptiveSelector, ConditionalSelector, OperatorSelector, RandomSelector,
+            SequentialSelector,
+        },
+        Operator, ProposalEvaluation,
+    };
 
     #[test]
     fn adaptivity_core() {
@@ -188,24 +560,219 @@ mod tests {
         let op1 = NeighborSwap::new(&[1., 2., 3.]);
         let op2 = NeighborSwap::new(&[1., 2., 3.]);
         let op3 = NeighborSwap::new(&[1., 2., 3.]);
-        let mut selector = AdaptiveSelector::default_weights(1., rng)
+        let selector = AdaptiveSelector::default_weights(1., rng)
             .operator(op1)
             .operator(op2)
             .operator(op3);
-        assert_approx_eq!(selector.weights[0], 1.);
-        assert_approx_eq!(selector.weights[1], 1.);
-        assert_approx_eq!(selector.weights[2], 1.);
+        assert_approx_eq!(selector.weights()[0], 1.);
+        assert_approx_eq!(selector.weights()[1], 1.);
+        assert_approx_eq!(selector.weights()[2], 1.);
 
         selector.index_last_selection.replace(Some(0));
         selector.feedback(ProposalEvaluation::ImprovedBest);
-        assert_approx_eq!(selector.weights[0], 3.);
-        assert_approx_eq!(selector.weights[1], 1.);
-        assert_approx_eq!(selector.weights[2], 1.);
+        assert_approx_eq!(selector.weights()[0], 3.);
+        assert_approx_eq!(selector.weights()[1], 1.);
+        assert_approx_eq!(selector.weights()[2], 1.);
 
         selector.index_last_selection.replace(Some(2));
         selector.feedback(ProposalEvaluation::Accept);
-        assert_approx_eq!(selector.weights[0], 3.);
-        assert_approx_eq!(selector.weights[1], 1.);
-        assert_approx_eq!(selector.weights[2], 1.);
+        assert_approx_eq!(selector.weights()[0], 3.);
+        assert_approx_eq!(selector.weights()[1], 1.);
+        assert_approx_eq!(selector.weights()[2], 1.);
+    }
+
+    #[test]
+    fn adaptive_removal() {
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let numbers = [1., 2., 3.];
+        let selector = AdaptiveSelector::default_weights(0.5, rng)
+            .operator(NeighborSwap::new(&numbers))
+            .operator(NeighborSwap::new(&numbers))
+            .operator(NeighborSwap::new(&numbers))
+            .removal(0.5, 2);
+
+        // weights of the first operator: 0.5, 0.25, 0.125
+        selector.index_last_selection.replace(Some(0));
+        selector.feedback(ProposalEvaluation::Reject);
+        selector.feedback(ProposalEvaluation::Reject);
+        assert!(selector.is_active(0));
+        selector.feedback(ProposalEvaluation::Reject);
+        assert!(!selector.is_active(0));
+
+        let solution = Number::new(0, 1.);
+        for _ in 0..100 {
+            selector.select(&solution);
+            assert_ne!(*selector.index_last_selection.borrow(), Some(0));
+        }
+
+        selector.reinstate_all();
+        assert!(selector.is_active(0));
+        let selected_first = (0..100).any(|_| {
+            selector.select(&solution);
+            *selector.index_last_selection.borrow() == Some(0)
+        });
+        assert!(selected_first);
+    }
+
+    #[test]
+    fn adaptive_round_robin_until_differentiated() {
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let numbers = [1., 2., 3.];
+        let selector = AdaptiveSelector::default_weights(1., rng)
+            .operator(NeighborSwap::new(&numbers))
+            .operator(NeighborSwap::new(&numbers))
+            .operator(NeighborSwap::new(&numbers))
+            .deterministic_until_differentiated();
+
+        let solution = Number::new(0, 1.);
+        let mut selections = vec![];
+        for _ in 0..6 {
+            selector.select(&solution);
+            selections.push(selector.index_last_selection.borrow().unwrap());
+        }
+        assert_eq!(selections, vec![0, 1, 2, 0, 1, 2]);
+
+        // the first operator now has weight 3, the others weight 1
+        selector.index_last_selection.replace(Some(0));
+        selector.feedback(ProposalEvaluation::ImprovedBest);
+        let mut counts = [0; 3];
+        for _ in 0..500 {
+            selector.select(&solution);
+            counts[selector.index_last_selection.borrow().unwrap()] += 1;
+        }
+        // expected shares are 60%, 20%, and 20%
+        assert!(counts[0] > counts[1] && counts[0] > counts[2]);
+        assert!(counts[0] > 200);
+    }
+
+    /// Return the position of ```operator``` in ```operators```.
+    fn position(
+        operators: &[Box<dyn Operator<Solution = Number>>],
+        operator: &dyn Operator<Solution = Number>,
+    ) -> usize {
+        operators
+            .iter()
+            .position(|x| std::ptr::addr_eq(x.as_ref(), operator))
+            .unwrap()
+    }
+
+    #[test]
+    fn last_selected() {
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let numbers = [1., 2., 3.];
+        let sequential = SequentialSelector::new()
+            .option(NeighborSwap::new(&numbers))
+            .option(NeighborSwap::new(&numbers));
+        let random = RandomSelector::new(rng.clone())
+            .option(NeighborSwap::new(&numbers))
+            .option(NeighborSwap::new(&numbers));
+        let adaptive = AdaptiveSelector::default_weights(0.5, rng)
+            .operator(NeighborSwap::new(&numbers))
+            .operator(NeighborSwap::new(&numbers));
+        assert_eq!(
+            crate::selectors::OperatorSelector::last_selected(&sequential),
+            None
+        );
+        assert_eq!(
+            crate::selectors::OperatorSelector::last_selected(&random),
+            None
+        );
+        assert_eq!(
+            crate::selectors::OperatorSelector::last_selected(&adaptive),
+            None
+        );
+
+        for value in [3., 2., 2., 2., 1.] {
+            let solution = Number::new(0, value);
+            let operator = crate::selectors::OperatorSelector::select(&sequential, &solution);
+            assert_eq!(
+                crate::selectors::OperatorSelector::last_selected(&sequential),
+                Some(position(&sequential.operators, operator))
+            );
+            let operator = crate::selectors::OperatorSelector::select(&random, &solution);
+            assert_eq!(
+                crate::selectors::OperatorSelector::last_selected(&random),
+                Some(position(&random.operators, operator))
+            );
+            let operator = crate::selectors::OperatorSelector::select(&adaptive, &solution);
+            assert_eq!(
+                crate::selectors::OperatorSelector::last_selected(&adaptive),
+                Some(position(&adaptive.options, operator))
+            );
+        }
+    }
+
+    #[test]
+    fn sequential_cyclic() {
+        let numbers = [1., 2., 3.];
+        let restarting = SequentialSelector::new()
+            .option(NeighborSwap::new(&numbers))
+            .option(NeighborSwap::new(&numbers))
+            .option(NeighborSwap::new(&numbers));
+        let cyclic = SequentialSelector::new()
+            .option(NeighborSwap::new(&numbers))
+            .option(NeighborSwap::new(&numbers))
+            .option(NeighborSwap::new(&numbers))
+            .cyclic();
+
+        let mut indices_restarting = vec![];
+        let mut indices_cyclic = vec![];
+        for value in [5., 4., 4., 3., 3., 3., 2.] {
+            let solution = Number::new(0, value);
+            crate::selectors::OperatorSelector::select(&restarting, &solution);
+            crate::selectors::OperatorSelector::select(&cyclic, &solution);
+            indices_restarting
+                .push(crate::selectors::OperatorSelector::last_selected(&restarting).unwrap());
+            indices_cyclic
+                .push(crate::selectors::OperatorSelector::last_selected(&cyclic).unwrap());
+        }
+        assert_eq!(indices_restarting, vec![0, 0, 1, 0, 1, 2, 0]);
+        assert_eq!(indices_cyclic, vec![0, 1, 2, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn conditional_by_objective() {
+        let numbers = [1., 2., 3.];
+        let selector = ConditionalSelector::new(|solution| usize::from(solution.evaluate() > 2.))
+            .option(NeighborSwap::new(&numbers))
+            .option(NeighborSwap::new(&numbers));
+
+        for (value, expected) in [(1., 0), (3., 1), (2., 0), (2.5, 1)] {
+            let solution = Number::new(0, value);
+            let operator = crate::selectors::OperatorSelector::select(&selector, &solution);
+            assert_eq!(position(&selector.operators, operator), expected);
+            assert_eq!(
+                crate::selectors::OperatorSelector::last_selected(&selector),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn sequential_ignores_sub_epsilon_improvements() {
+        let numbers = [1., 2., 3.];
+        let selector = |epsilon| {
+            SequentialSelector::new()
+                .option(NeighborSwap::new(&numbers))
+                .option(NeighborSwap::new(&numbers))
+                .option(NeighborSwap::new(&numbers))
+                .objective_epsilon(epsilon)
+        };
+        let (strict, tolerant) = (selector(0.), selector(1e-3));
+
+        let mut indices_strict = vec![];
+        let mut indices_tolerant = vec![];
+        // improves by 1e-4 every selection, and by more than the epsilon in total at the end
+        for value in (0..7).map(|i| 1. - 1e-4 * i as f32).chain([0.99]) {
+            let solution = Number::new(0, value);
+            crate::selectors::OperatorSelector::select(&strict, &solution);
+            crate::selectors::OperatorSelector::select(&tolerant, &solution);
+            indices_strict
+                .push(crate::selectors::OperatorSelector::last_selected(&strict).unwrap());
+            indices_tolerant
+                .push(crate::selectors::OperatorSelector::last_selected(&tolerant).unwrap());
+        }
+        assert_eq!(indices_strict, vec![0; 8]);
+        assert_eq!(indices_tolerant, vec![0, 1, 2, 0, 1, 2, 0, 0]);
     }
 }
@@ -0,0 +1,215 @@
+//! Assignment-encoded solutions, as used for load balancing and parallel machine scheduling
+use std::rc::Rc;
+
+use rand::Rng;
+
+use crate::{hash_key, solutions::FrozenMask, Distance, Evaluate, Operator, SolutionKey};
+
+/// An assignment of jobs to machines, evaluated by its makespan, i.e. the largest total processing time of any
+/// machine.
+///
+/// The load of every machine is maintained whenever a job is reassigned, such that a neighbor is evaluated in
+/// O(machines) rather than O(jobs).
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobAssignment {
+    machines: Vec<usize>,
+    processing_times: Rc<[f32]>,
+    loads: Vec<f32>,
+    makespan: f32,
+}
+
+/// Move a single job to another machine.
+///
+/// The neighborhood contains jobs * (machines - 1) neighbors.
+#[derive(Default)]
+pub struct ReassignOperator {
+    frozen: FrozenMask,
+}
+
+impl JobAssignment {
+    /// Assign job ```j``` to machine ```machines[j]```, one of ```0..machine_count```, where it takes
+    /// ```processing_times[j]```.
+    pub fn new(machines: Vec<usize>, machine_count: usize, processing_times: Rc<[f32]>) -> Self {
+        assert_eq!(
+            machines.len(),
+            processing_times.len(),
+            "every job needs a machine and a processing time"
+        );
+        let mut loads = vec![0.; machine_count];
+        for (machine, time) in machines.iter().zip(processing_times.iter()) {
+            loads[*machine] += time;
+        }
+        Self {
+            makespan: makespan(&loads),
+            machines,
+            processing_times,
+            loads,
+        }
+    }
+
+    /// Return the machine of every job.
+    pub fn machines(&self) -> &[usize] {
+        &self.machines
+    }
+
+    /// Return the total processing time of every machine.
+    pub fn loads(&self) -> &[f32] {
+        &self.loads
+    }
+
+    /// Return the number of machines.
+    pub fn machine_count(&self) -> usize {
+        self.loads.len()
+    }
+
+    /// Return the number of jobs.
+    pub fn len(&self) -> usize {
+        self.machines.len()
+    }
+
+    /// Test whether there are no jobs.
+    pub fn is_empty(&self) -> bool {
+        self.machines.is_empty()
+    }
+
+    /// Return the assignment in which ```job``` is moved to ```machine```.
+    pub fn reassign(&self, job: usize, machine: usize) -> Self {
+        let mut neighbor = self.clone();
+        let time = self.processing_times[job];
+        neighbor.loads[self.machines[job]] -= time;
+        neighbor.loads[machine] += time;
+        neighbor.machines[job] = machine;
+        neighbor.makespan = makespan(&neighbor.loads);
+        neighbor
+    }
+}
+
+fn makespan(loads: &[f32]) -> f32 {
+    loads.iter().cloned().fold(0., f32::max)
+}
+
+impl Evaluate for JobAssignment {
+    fn evaluate(&self) -> f32 {
+        self.makespan
+    }
+}
+
+/// Assignments with the same machines have the same key, regardless of their objective.
+impl SolutionKey for JobAssignment {
+    fn key(&self) -> u64 {
+        hash_key(&self.machines)
+    }
+}
+
+/// The Hamming distance: the number of jobs assigned to different machines.
+impl Distance for JobAssignment {
+    fn distance(&self, other: &Self) -> f32 {
+        let mismatches = self
+            .machines
+            .iter()
+            .zip(other.machines.iter())
+            .filter(|(x, y)| x != y)
+            .count();
+        (mismatches + self.len().abs_diff(other.len())) as f32
+    }
+}
+
+impl ReassignOperator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never move a frozen job
+    pub fn frozen(mut self, frozen: FrozenMask) -> Self {
+        self.frozen = frozen;
+        self
+    }
+}
+
+impl Operator for ReassignOperator {
+    type Solution = JobAssignment;
+
+    fn construct_neighborhood(
+        &self,
+        solution: JobAssignment,
+    ) -> Box<dyn Iterator<Item = JobAssignment>> {
+        let moves: Vec<(usize, usize)> = self
+            .frozen
+            .free(solution.len())
+            .into_iter()
+            .flat_map(|job| {
+                let current = solution.machines[job];
+                (0..solution.machine_count())
+                    .filter(move |machine| *machine != current)
+                    .map(move |machine| (job, machine))
+            })
+            .collect();
+        Box::new(
+            moves
+                .into_iter()
+                .map(move |(job, machine)| solution.reassign(job, machine)),
+        )
+    }
+
+    fn shake(&self, solution: JobAssignment, rng: &mut dyn rand::RngCore) -> JobAssignment {
+        let free = self.frozen.free(solution.len());
+        let machine_count = solution.machine_count();
+        if free.is_empty() || machine_count < 2 {
+            return solution;
+        }
+        let job = free[rng.gen_range(0..free.len())];
+        let machine = (solution.machines[job] + rng.gen_range(1..machine_count)) % machine_count;
+        solution.reassign(job, machine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use rand::SeedableRng;
+
+    use crate::{
+        solutions::assignment::{JobAssignment, ReassignOperator},
+        Distance, Evaluate, Operator,
+    };
+
+    fn assignment(machines: Vec<usize>) -> JobAssignment {
+        JobAssignment::new(machines, 3, Rc::from(vec![4., 3., 2., 1., 5.]))
+    }
+
+    #[test]
+    fn reassign_neighborhood() {
+        // machine 0 is the bottleneck, with a load of 7
+        let solution = assignment(vec![0, 0, 1, 1, 2]);
+        assert_eq!(solution.loads(), &[7., 3., 5.]);
+        assert_eq!(solution.evaluate(), 7.);
+
+        let neighbors: Vec<JobAssignment> = ReassignOperator::new()
+            .construct_neighborhood(solution.clone())
+            .collect();
+        assert_eq!(neighbors.len(), 5 * (3 - 1));
+        for neighbor in &neighbors {
+            assert_eq!(neighbor.distance(&solution), 1.);
+            let recomputed = assignment(neighbor.machines().to_vec());
+            assert_eq!(neighbor.evaluate(), recomputed.evaluate());
+        }
+
+        // moving a job off the bottleneck machine lowers the makespan
+        let relieved = solution.reassign(1, 1);
+        assert_eq!(relieved.loads(), &[4., 6., 5.]);
+        assert!(relieved.evaluate() < solution.evaluate());
+    }
+
+    #[test]
+    fn reassign_shake() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let operator = ReassignOperator::new();
+        let solution = assignment(vec![0, 0, 1, 1, 2]);
+        for _ in 0..20 {
+            let shaken = operator.shake(solution.clone(), &mut rng);
+            assert_eq!(shaken.distance(&solution), 1.);
+            assert!(shaken.machines().iter().all(|machine| *machine < 3));
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Binary-encoded solutions, as used for knapsack and subset selection problems
+use rand::seq::index::sample;
+
+use crate::{hash_key, solutions::FrozenMask, Distance, Evaluate, Operator, SolutionKey};
+
+/// A vector of bits, decorated with its objective value.
+///
+/// The objective is computed once, by the user-supplied cost function, whenever a bit vector is created.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitVector {
+    bits: Vec<bool>,
+    objective: f32,
+}
+
+/// Flip a single bit.
+///
+/// The neighborhood contains n neighbors.
+pub struct BitFlipOperator<F> {
+    cost: F,
+    frozen: FrozenMask,
+}
+
+/// Flip ```k``` distinct bits at random.
+///
+/// Only supports ```shake```, as the full neighborhood grows as n choose k.
+pub struct MultiBitFlip<F> {
+    k: usize,
+    cost: F,
+    frozen: FrozenMask,
+}
+
+impl BitVector {
+    /// Create a bit vector from ```bits``` and evaluate it with ```cost```.
+    pub fn new<F: Fn(&BitVector) -> f32>(bits: Vec<bool>, cost: &F) -> Self {
+        let mut bit_vector = Self {
+            bits,
+            objective: f32::NAN,
+        };
+        bit_vector.objective = cost(&bit_vector);
+        bit_vector
+    }
+
+    /// Create a bit vector of ```n``` unset bits.
+    pub fn zeros<F: Fn(&BitVector) -> f32>(n: usize, cost: &F) -> Self {
+        Self::new(vec![false; n], cost)
+    }
+
+    /// Return the bits.
+    pub fn bits(&self) -> &[bool] {
+        &self.bits
+    }
+
+    /// Return the number of bits.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Test whether there are no bits.
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Return the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.bits.iter().filter(|bit| **bit).count()
+    }
+}
+
+/// Bit vectors with the same bits have the same key, regardless of their objective.
+impl SolutionKey for BitVector {
+    fn key(&self) -> u64 {
+        hash_key(&self.bits)
+    }
+}
+
+impl Evaluate for BitVector {
+    fn evaluate(&self) -> f32 {
+        self.objective
+    }
+}
+
+/// The Hamming distance: the number of bits at which the vectors differ.
+impl Distance for BitVector {
+    fn distance(&self, other: &Self) -> f32 {
+        let mismatches = self
+            .bits
+            .iter()
+            .zip(other.bits.iter())
+            .filter(|(x, y)| x != y)
+            .count();
+        (mismatches + self.len().abs_diff(other.len())) as f32
+    }
+}
+
+impl<F: Fn(&BitVector) -> f32> BitFlipOperator<F> {
+    pub fn new(cost: F) -> Self {
+        Self {
+            cost,
+            frozen: FrozenMask::default(),
+        }
+    }
+
+    /// Never flip a frozen bit
+    pub fn frozen(mut self, frozen: FrozenMask) -> Self {
+        self.frozen = frozen;
+        self
+    }
+}
+
+impl<F: Fn(&BitVector) -> f32> MultiBitFlip<F> {
+    pub fn new(k: usize, cost: F) -> Self {
+        Self {
+            k,
+            cost,
+            frozen: FrozenMask::default(),
+        }
+    }
+
+    /// Never flip a frozen bit
+    pub fn frozen(mut self, frozen: FrozenMask) -> Self {
+        self.frozen = frozen;
+        self
+    }
+}
+
+fn flip<F: Fn(&BitVector) -> f32>(solution: &BitVector, indices: &[usize], cost: &F) -> BitVector {
+    let mut bits = solution.bits.clone();
+    for &index in indices {
+        bits[index] = !bits[index];
+    }
+    BitVector::new(bits, cost)
+}
+
+impl<F: Fn(&BitVector) -> f32 + Clone + 'static> Operator for BitFlipOperator<F> {
+    type Solution = BitVector;
+
+    fn construct_neighborhood(&self, solution: BitVector) -> Box<dyn Iterator<Item = BitVector>> {
+        let cost = self.cost.clone();
+        let free = self.frozen.free(solution.len());
+        Box::new(
+            free.into_iter()
+                .map(move |index| flip(&solution, &[index], &cost)),
+        )
+    }
+
+    fn shake(&self, solution: BitVector, rng: &mut dyn rand::RngCore) -> BitVector {
+        let free = self.frozen.free(solution.len());
+        if free.is_empty() {
+            return solution;
+        }
+        let index = free[sample(rng, free.len(), 1).index(0)];
+        flip(&solution, &[index], &self.cost)
+    }
+}
+
+impl<F: Fn(&BitVector) -> f32 + Clone + 'static> Operator for MultiBitFlip<F> {
+    type Solution = BitVector;
+
+    fn shake(&self, solution: BitVector, rng: &mut dyn rand::RngCore) -> BitVector {
+        let free = self.frozen.free(solution.len());
+        let k = self.k.min(free.len());
+        let indices: Vec<usize> = sample(rng, free.len(), k)
+            .into_iter()
+            .map(|index| free[index])
+            .collect();
+        flip(&solution, &indices, &self.cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::{
+        solutions::{
+            bitvector::{BitFlipOperator, BitVector, MultiBitFlip},
+            FrozenMask,
+        },
+        Distance, Evaluate, Operator,
+    };
+
+    // a small knapsack: maximize value, heavily penalize exceeding the capacity
+    fn cost(solution: &BitVector) -> f32 {
+        let values = [4., 2., 10., 1., 2.];
+        let weights = [12., 2., 4., 1., 1.];
+        let capacity = 16.;
+        let (value, weight) = solution
+            .bits()
+            .iter()
+            .zip(values.iter().zip(weights.iter()))
+            .filter(|(bit, _)| **bit)
+            .fold((0., 0.), |(v, w), (_, (value, weight))| {
+                (v + value, w + weight)
+            });
+        let penalty = if weight > capacity { 100. } else { 0. };
+        penalty - value
+    }
+
+    #[test]
+    fn bit_flip_neighborhood() {
+        let solution = BitVector::new(vec![true, false, true, false, false], &cost);
+        assert_eq!(solution.evaluate(), -14.);
+
+        let operator = BitFlipOperator::new(cost);
+        let neighbors: Vec<BitVector> = operator.construct_neighborhood(solution.clone()).collect();
+        assert_eq!(neighbors.len(), solution.len());
+        for neighbor in neighbors {
+            assert_eq!(neighbor.evaluate(), cost(&neighbor));
+            assert_eq!(neighbor.distance(&solution), 1.);
+        }
+    }
+
+    #[test]
+    fn multi_bit_flip_shake() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let operator = MultiBitFlip::new(3, cost);
+        let solution = BitVector::zeros(5, &cost);
+        for _ in 0..20 {
+            let neighbor = operator.shake(solution.clone(), &mut rng);
+            assert_eq!(neighbor.count_ones(), 3);
+            assert_eq!(neighbor.evaluate(), cost(&neighbor));
+        }
+    }
+
+    #[test]
+    fn frozen_bits_never_flip() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let frozen = FrozenMask::new([0, 3]);
+        let operator = MultiBitFlip::new(2, cost).frozen(frozen.clone());
+        let mut solution = BitVector::zeros(5, &cost);
+        for _ in 0..100 {
+            solution = operator.shake(solution, &mut rng);
+            assert!(!solution.bits()[0] && !solution.bits()[3]);
+        }
+
+        let operator = BitFlipOperator::new(cost).frozen(frozen);
+        let neighbors: Vec<BitVector> = operator.construct_neighborhood(solution.clone()).collect();
+        assert_eq!(neighbors.len(), 3);
+        assert!(neighbors
+            .iter()
+            .all(|neighbor| neighbor.bits()[0] == solution.bits()[0]
+                && neighbor.bits()[3] == solution.bits()[3]));
+    }
+}
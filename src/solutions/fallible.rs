@@ -0,0 +1,46 @@
+//! Solutions whose evaluation can fail
+use crate::{EvalError, Evaluate, TryEvaluate};
+
+/// Adapt a solution with a fallible objective, see [TryEvaluate], such that it can be optimized.
+///
+/// The objective is computed once, when the solution is wrapped. A failed evaluation is reported as NaN by
+/// [Evaluate], such that heuristics reject the solution automatically.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fallible<Solution> {
+    solution: Solution,
+    objective: Result<f32, EvalError>,
+}
+
+impl<Solution: TryEvaluate> Fallible<Solution> {
+    /// Wrap ```solution``` and evaluate it.
+    pub fn new(solution: Solution) -> Self {
+        let objective = solution.try_evaluate();
+        Self {
+            solution,
+            objective,
+        }
+    }
+}
+
+impl<Solution> Fallible<Solution> {
+    /// Return the wrapped solution.
+    pub fn solution(&self) -> &Solution {
+        &self.solution
+    }
+
+    /// Unwrap the solution.
+    pub fn into_inner(self) -> Solution {
+        self.solution
+    }
+
+    /// Return the error, if the evaluation failed.
+    pub fn error(&self) -> Option<&EvalError> {
+        self.objective.as_ref().err()
+    }
+}
+
+impl<Solution> Evaluate for Fallible<Solution> {
+    fn evaluate(&self) -> f32 {
+        *self.objective.as_ref().unwrap_or(&f32::NAN)
+    }
+}
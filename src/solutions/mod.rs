@@ -0,0 +1,47 @@
+//! Ready-made solution types and operators for common problem encodings
+use std::{collections::BTreeSet, ops::RangeInclusive, rc::Rc};
+
+pub mod assignment;
+pub mod bitvector;
+pub mod fallible;
+pub mod permutation;
+
+/// Positions which operators must not alter, e.g. a city which must be visited first, or a locked assignment.
+///
+/// The ready-made operators accept a mask, e.g. ```SwapOperator::new(cost).frozen(FrozenMask::new([0]))```, and skip
+/// every move which touches a frozen position, i.e. which may change the element at it. Shakes draw moves until one
+/// leaves the frozen positions alone, and return the solution unchanged if none is found.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrozenMask {
+    positions: Rc<BTreeSet<usize>>,
+}
+
+impl FrozenMask {
+    pub fn new<I: IntoIterator<Item = usize>>(positions: I) -> Self {
+        Self {
+            positions: Rc::new(positions.into_iter().collect()),
+        }
+    }
+
+    /// Test whether ```position``` is frozen.
+    pub fn contains(&self, position: usize) -> bool {
+        self.positions.contains(&position)
+    }
+
+    /// Test whether any position of ```positions``` is frozen.
+    pub fn touches(&self, positions: RangeInclusive<usize>) -> bool {
+        self.positions.range(positions).next().is_some()
+    }
+
+    /// Test whether no position is frozen.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Return the positions of ```0..n``` which are not frozen, in increasing order.
+    pub fn free(&self, n: usize) -> Vec<usize> {
+        (0..n)
+            .filter(|position| !self.contains(*position))
+            .collect()
+    }
+}
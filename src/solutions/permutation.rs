@@ -0,0 +1,1350 @@
+//! Permutation-encoded solutions, as used for routing and sequencing problems
+use std::{cell::RefCell, rc::Rc};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{hash_key, solutions::FrozenMask, Distance, Evaluate, Operator, SolutionKey, Validate};
+
+/// A permutation of ```0..n```, decorated with its objective value.
+///
+/// The objective is computed once, by the user-supplied cost function, whenever a permutation is created.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Permutation {
+    order: Vec<usize>,
+    objective: f32,
+}
+
+/// The cost of travelling from one element to another, e.g. a distance or travel time.
+///
+/// Costs need not be symmetric, so cost(from, to) may differ from cost(to, from).
+pub trait CostProvider {
+    fn cost(&self, from: usize, to: usize) -> f32;
+}
+
+/// A cost provider backed by a (possibly asymmetric) matrix, where ```matrix[from][to]``` is the cost.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostMatrix {
+    matrix: Vec<Vec<f32>>,
+}
+
+/// Swap the elements at two positions.
+///
+/// The neighborhood contains n(n-1)/2 neighbors.
+pub struct SwapOperator<F> {
+    cost: F,
+    frozen: FrozenMask,
+}
+
+/// Remove an element and reinsert it at another position.
+///
+/// The neighborhood contains n(n-1) neighbors, where moves of adjacent elements appear twice.
+pub struct InsertionOperator<F> {
+    cost: F,
+    frozen: FrozenMask,
+}
+
+/// Reverse the segment between two positions, which is the 2-opt move for tours.
+///
+/// The neighborhood contains n(n-1)/2 neighbors.
+pub struct TwoOptOperator<F> {
+    cost: F,
+    frozen: FrozenMask,
+}
+
+/// The 2-opt move by its descriptive name: reverse the segment between two positions, see [TwoOptOperator].
+pub type SegmentReversal<F> = TwoOptOperator<F>;
+
+/// Cut three edges of a closed tour, and reconnect the three resulting paths in one of the seven other ways.
+///
+/// With cut edges after positions i < j < k, the tour A B C D, where D wraps around to A, becomes one of
+/// A B' C D, A B C' D, A C' B' D (the 2-opt moves) or A B' C' D, A C B D, A C' B D, A C B' D, where ' denotes
+/// reversal. Many of these coincide, e.g. the same path is reversed by the 2-opt moves of several cuts, and
+/// reversing a path of a single element changes nothing. The neighborhood contains every distinct tour once, and
+/// never the tour itself: the (n-1)(n-2)/2 reversals of a path, and fewer than 4 * n(n-1)(n-2)/6 other moves. It
+/// thus grows as O(n^3), which makes enumerating it impractical for large tours. For those, sample moves with
+/// ```shake``` instead.
+///
+/// Neighbors are evaluated by delta evaluation in constant time, based on the objective of the tour they originate
+/// from. Hence, tours must be evaluated with [tour_cost] of the same provider.
+pub struct ThreeOptOperator<P> {
+    provider: Rc<P>,
+    frozen: FrozenMask,
+}
+
+/// Relocate a segment of consecutive elements to another position.
+///
+/// By default, segments of length 1 up to 3 are relocated. Each segment is moved to every position other than
+/// its own, so the segment never overlaps with its destination. Moving a segment backward past d elements
+/// coincides with moving those d elements forward, hence backward moves are only enumerated if d exceeds the
+/// maximum segment length, such that every neighbor is distinct.
+pub struct OrOptOperator<F> {
+    cost: F,
+    max_segment_len: usize,
+    provider: Option<Rc<dyn CostProvider>>,
+    frozen: FrozenMask,
+}
+
+/// Cut the permutation before ```k``` positions, and reconnect the segments between the cuts in any other order and
+/// orientation, which generalizes the 2-opt and 3-opt moves.
+///
+/// With cuts before positions c1 < ... < ck, the ```k - 1``` segments between the cuts are permuted, and each is
+/// possibly reversed, while the elements before c1 and from ck onwards stay in place. Only pure k-opt moves are
+/// neighbors, i.e. moves which break every cut edge between two elements, such that smaller moves are not
+/// enumerated again under other cuts. For ```k = 2```, this is exactly the neighborhood of [TwoOptOperator].
+///
+/// The neighborhood grows combinatorially: there are C(n + 1, k) ways to cut, each reconnected in up to
+/// (k - 1)! 2^(k - 1) - 1 ways, i.e. O(n^k) neighbors for fixed k, with a factor that grows faster than k!. Once this
+/// estimate exceeds ```max_neighbors``` (default: 100 000), the neighborhood is a sample of ```max_neighbors``` random
+/// moves instead.
+pub struct KOpt<F> {
+    k: usize,
+    cost: F,
+    max_neighbors: usize,
+    rng: Rc<RefCell<StdRng>>,
+    frozen: FrozenMask,
+}
+
+impl Permutation {
+    /// Create a permutation from ```order``` and evaluate it with ```cost```.
+    pub fn new<F: Fn(&Permutation) -> f32>(order: Vec<usize>, cost: &F) -> Self {
+        let mut permutation = Self {
+            order,
+            objective: f32::NAN,
+        };
+        permutation.objective = cost(&permutation);
+        permutation
+    }
+
+    /// Create the identity permutation ```0, 1, ..., n-1```.
+    pub fn identity<F: Fn(&Permutation) -> f32>(n: usize, cost: &F) -> Self {
+        Self::new((0..n).collect(), cost)
+    }
+
+    /// Return the order of the elements.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Return the number of elements.
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Test whether the permutation has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Test whether every element of ```0..n``` occurs exactly once.
+    pub fn is_valid(&self) -> bool {
+        let mut seen = vec![false; self.order.len()];
+        for &element in &self.order {
+            if element >= seen.len() || seen[element] {
+                return false;
+            }
+            seen[element] = true;
+        }
+        true
+    }
+
+    /// Compute the cost of the closed tour which visits the elements in order, and returns to the first.
+    pub fn tour_cost<P: CostProvider + ?Sized>(&self, provider: &P) -> f32 {
+        let n = self.order.len();
+        (0..n)
+            .map(|i| provider.cost(self.order[i], self.order[(i + 1) % n]))
+            .sum()
+    }
+}
+
+/// Turn a [CostProvider] into a cost function for [Permutation]s which evaluates closed tours.
+///
+/// The result can be passed to the permutation operators, e.g. ```TwoOptOperator::new(tour_cost(matrix))```.
+pub fn tour_cost<P: CostProvider + 'static>(provider: P) -> impl Fn(&Permutation) -> f32 + Clone {
+    let provider = Rc::new(provider);
+    move |permutation: &Permutation| permutation.tour_cost(provider.as_ref())
+}
+
+impl<F: Fn(usize, usize) -> f32> CostProvider for F {
+    fn cost(&self, from: usize, to: usize) -> f32 {
+        self(from, to)
+    }
+}
+
+impl CostMatrix {
+    /// Create a cost matrix, which must be square.
+    pub fn new(matrix: Vec<Vec<f32>>) -> Self {
+        assert!(
+            matrix.iter().all(|row| row.len() == matrix.len()),
+            "cost matrix is not square"
+        );
+        Self { matrix }
+    }
+
+    /// Return the number of elements.
+    pub fn len(&self) -> usize {
+        self.matrix.len()
+    }
+
+    /// Test whether the matrix has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.matrix.is_empty()
+    }
+}
+
+impl CostProvider for CostMatrix {
+    fn cost(&self, from: usize, to: usize) -> f32 {
+        self.matrix[from][to]
+    }
+}
+
+impl Evaluate for Permutation {
+    fn evaluate(&self) -> f32 {
+        self.objective
+    }
+}
+
+impl Validate for Permutation {
+    fn validate(&self) -> Result<(), String> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(format!("{:?} is not a permutation", self.order))
+        }
+    }
+}
+
+/// Permutations with the same order have the same key, regardless of their objective.
+impl SolutionKey for Permutation {
+    fn key(&self) -> u64 {
+        hash_key(&self.order)
+    }
+}
+
+/// The positional Hamming distance: the number of positions at which the permutations differ.
+impl Distance for Permutation {
+    fn distance(&self, other: &Self) -> f32 {
+        let mismatches = self
+            .order
+            .iter()
+            .zip(other.order.iter())
+            .filter(|(x, y)| x != y)
+            .count();
+        let length_difference = self.len().abs_diff(other.len());
+        (mismatches + length_difference) as f32
+    }
+}
+
+impl<F: Fn(&Permutation) -> f32> SwapOperator<F> {
+    pub fn new(cost: F) -> Self {
+        Self {
+            cost,
+            frozen: FrozenMask::default(),
+        }
+    }
+
+    /// Skip every move which touches a frozen position
+    pub fn frozen(mut self, frozen: FrozenMask) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    fn apply(&self, solution: &Permutation, index1: usize, index2: usize) -> Permutation {
+        let mut order = solution.order.clone();
+        order.swap(index1, index2);
+        Permutation::new(order, &self.cost)
+    }
+}
+
+impl<F: Fn(&Permutation) -> f32> InsertionOperator<F> {
+    pub fn new(cost: F) -> Self {
+        Self {
+            cost,
+            frozen: FrozenMask::default(),
+        }
+    }
+
+    /// Skip every move which touches a frozen position
+    pub fn frozen(mut self, frozen: FrozenMask) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    fn apply(&self, solution: &Permutation, from: usize, to: usize) -> Permutation {
+        let mut order = solution.order.clone();
+        let element = order.remove(from);
+        order.insert(to, element);
+        Permutation::new(order, &self.cost)
+    }
+}
+
+impl<F: Fn(&Permutation) -> f32> TwoOptOperator<F> {
+    pub fn new(cost: F) -> Self {
+        Self {
+            cost,
+            frozen: FrozenMask::default(),
+        }
+    }
+
+    /// Skip every move which touches a frozen position
+    pub fn frozen(mut self, frozen: FrozenMask) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    fn apply(&self, solution: &Permutation, first: usize, last: usize) -> Permutation {
+        let mut order = solution.order.clone();
+        order[first..=last].reverse();
+        Permutation::new(order, &self.cost)
+    }
+}
+
+impl<F: Fn(&Permutation) -> f32> OrOptOperator<F> {
+    pub fn new(cost: F) -> Self {
+        Self {
+            cost,
+            max_segment_len: 3,
+            provider: None,
+            frozen: FrozenMask::default(),
+        }
+    }
+
+    /// Skip every move which touches a frozen position
+    pub fn frozen(mut self, frozen: FrozenMask) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    /// Set the maximum length of a relocated segment (default: 3)
+    pub fn max_segment_len(mut self, max_segment_len: usize) -> Self {
+        assert!(
+            max_segment_len > 0,
+            "maximum segment length must be positive"
+        );
+        self.max_segment_len = max_segment_len;
+        self
+    }
+
+    /// Evaluate neighbors by delta evaluation in constant time, instead of by the cost function.
+    ///
+    /// A relocation replaces three edges of the closed tour, hence tours must be evaluated with [tour_cost] of ```provider```.
+    pub fn delta_evaluation<P: CostProvider + 'static>(mut self, provider: P) -> Self {
+        self.provider = Some(Rc::new(provider));
+        self
+    }
+
+    /// Move the segment of ```length``` elements starting at ```from```, such that it starts at ```to``` afterwards.
+    fn apply(&self, solution: &Permutation, from: usize, length: usize, to: usize) -> Permutation {
+        let mut order = solution.order.clone();
+        let segment: Vec<usize> = order.drain(from..from + length).collect();
+        order.splice(to..to, segment);
+        match &self.provider {
+            Some(provider) => Permutation {
+                objective: solution.objective
+                    + Self::delta(provider.as_ref(), &solution.order, from, length, to),
+                order,
+            },
+            None => Permutation::new(order, &self.cost),
+        }
+    }
+
+    /// The change in tour cost of a relocation, for a closed tour visiting ```order```.
+    fn delta(
+        provider: &dyn CostProvider,
+        order: &[usize],
+        from: usize,
+        length: usize,
+        to: usize,
+    ) -> f32 {
+        let n = order.len();
+        let (first, last) = (order[from], order[from + length - 1]);
+        let (prev, next) = (order[(from + n - 1) % n], order[(from + length) % n]);
+        // the tour without the segment, in which the segment is inserted between positions to - 1 and to
+        let m = n - length;
+        let remainder = |t: usize| {
+            if t < from {
+                order[t]
+            } else {
+                order[t + length]
+            }
+        };
+        let (p, q) = (remainder((to + m - 1) % m), remainder(to % m));
+        let cost = |from: usize, to: usize| provider.cost(from, to);
+        cost(prev, next) - cost(prev, first) - cost(last, next) + cost(p, first) + cost(last, q)
+            - cost(p, q)
+    }
+
+    fn segment_lengths(&self, n: usize) -> std::ops::RangeInclusive<usize> {
+        1..=self.max_segment_len.min(n.saturating_sub(1))
+    }
+}
+
+/// The positions changed by relocating the segment of ```length``` elements starting at ```from``` to ```to```.
+fn relocated(&(from, length, to): &(usize, usize, usize)) -> std::ops::RangeInclusive<usize> {
+    from.min(to)..=from.max(to) + length - 1
+}
+
+/// How the paths B and C are reconnected by a 3-opt move: their order, and whether each is reversed.
+#[derive(Clone, Copy)]
+struct Reconnection {
+    exchange: bool,
+    reverse_b: bool,
+    reverse_c: bool,
+}
+
+const RECONNECTIONS: [Reconnection; 7] = [
+    Reconnection::new(false, true, false),
+    Reconnection::new(false, false, true),
+    Reconnection::new(true, true, true),
+    Reconnection::new(false, true, true),
+    Reconnection::new(true, false, false),
+    Reconnection::new(true, false, true),
+    Reconnection::new(true, true, false),
+];
+
+impl Reconnection {
+    const fn new(exchange: bool, reverse_b: bool, reverse_c: bool) -> Self {
+        Self {
+            exchange,
+            reverse_b,
+            reverse_c,
+        }
+    }
+
+    /// Test whether the neighborhood contains this reconnection of paths B and C of ```b``` and ```c``` elements.
+    ///
+    /// Every reversal of a path is enumerated once, as A C' B' D with a single element in B. The other
+    /// reconnections are skipped whenever reversing a single element reduces them to such a reversal, or to
+    /// another reconnection of the same cuts.
+    fn is_enumerated(&self, b: usize, c: usize) -> bool {
+        match (self.exchange, self.reverse_b, self.reverse_c) {
+            // A C' B' D
+            (true, true, true) => b == 1,
+            // A B' C D and A B C' D, enumerated as A C' B' D
+            (false, true, false) | (false, false, true) => false,
+            // A C B D
+            (true, false, false) => b > 1 || c > 1,
+            // A B' C' D, A C' B D, and A C B' D
+            _ => b > 1 && c > 1,
+        }
+    }
+}
+
+/// Cumulative edge costs along a tour, in both directions, such that the cost of a path is known in constant time.
+struct PathCosts {
+    forward: Vec<f32>,
+    backward: Vec<f32>,
+}
+
+impl PathCosts {
+    fn new<P: CostProvider + ?Sized>(order: &[usize], provider: &P) -> Self {
+        let mut forward = vec![0.; order.len()];
+        let mut backward = vec![0.; order.len()];
+        for t in 1..order.len() {
+            forward[t] = forward[t - 1] + provider.cost(order[t - 1], order[t]);
+            backward[t] = backward[t - 1] + provider.cost(order[t], order[t - 1]);
+        }
+        Self { forward, backward }
+    }
+
+    /// The cost of traversing positions ```first..=last```, forward or in reverse.
+    fn path(&self, first: usize, last: usize, reversed: bool) -> f32 {
+        if reversed {
+            self.backward[last] - self.backward[first]
+        } else {
+            self.forward[last] - self.forward[first]
+        }
+    }
+}
+
+impl<P: CostProvider> ThreeOptOperator<P> {
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider: Rc::new(provider),
+            frozen: FrozenMask::default(),
+        }
+    }
+
+    /// Skip every move which touches a frozen position
+    pub fn frozen(mut self, frozen: FrozenMask) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    fn apply(
+        &self,
+        solution: &Permutation,
+        costs: &PathCosts,
+        (i, j, k): (usize, usize, usize),
+        reconnection: Reconnection,
+    ) -> Permutation {
+        let order = &solution.order;
+        let n = order.len();
+        // the paths B = i+1..=j and C = j+1..=k, as (first, last, reversed)
+        let b = (i + 1, j, reconnection.reverse_b);
+        let c = (j + 1, k, reconnection.reverse_c);
+        let (x, y) = if reconnection.exchange {
+            (c, b)
+        } else {
+            (b, c)
+        };
+        let ends = |(first, last, reversed): (usize, usize, bool)| {
+            if reversed {
+                (order[last], order[first])
+            } else {
+                (order[first], order[last])
+            }
+        };
+        let cost = |from: usize, to: usize| self.provider.cost(from, to);
+
+        let (a, d) = (order[i], order[(k + 1) % n]);
+        let removed = cost(a, order[i + 1])
+            + cost(order[j], order[j + 1])
+            + cost(order[k], d)
+            + costs.path(i + 1, j, false)
+            + costs.path(j + 1, k, false);
+        let ((x_first, x_last), (y_first, y_last)) = (ends(x), ends(y));
+        let added = cost(a, x_first)
+            + costs.path(x.0, x.1, x.2)
+            + cost(x_last, y_first)
+            + costs.path(y.0, y.1, y.2)
+            + cost(y_last, d);
+
+        let mut neighbor = Vec::with_capacity(n);
+        neighbor.extend_from_slice(&order[..=i]);
+        for (first, last, reversed) in [x, y] {
+            if reversed {
+                neighbor.extend(order[first..=last].iter().rev());
+            } else {
+                neighbor.extend_from_slice(&order[first..=last]);
+            }
+        }
+        neighbor.extend_from_slice(&order[k + 1..]);
+        Permutation {
+            order: neighbor,
+            objective: solution.objective - removed + added,
+        }
+    }
+}
+
+/// A k-opt move: the cut positions, the order in which the segments between them are reconnected, and which
+/// segments are reversed.
+struct KOptMove {
+    cuts: Vec<usize>,
+    segments: Vec<usize>,
+    reversed: Vec<bool>,
+}
+
+impl<F: Fn(&Permutation) -> f32> KOpt<F> {
+    pub fn new(k: usize, cost: F) -> Self {
+        assert!(k >= 2, "k-opt needs at least 2 cuts");
+        Self {
+            k,
+            cost,
+            max_neighbors: 100_000,
+            rng: Rc::new(RefCell::new(StdRng::seed_from_u64(0))),
+            frozen: FrozenMask::default(),
+        }
+    }
+
+    /// Skip every move which touches a frozen position
+    pub fn frozen(mut self, frozen: FrozenMask) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    /// Sample the neighborhood once it would contain more than ```max_neighbors``` neighbors
+    pub fn max_neighbors(mut self, max_neighbors: usize) -> Self {
+        self.max_neighbors = max_neighbors;
+        self
+    }
+
+    /// Seed the source of randomness of sampled neighborhoods (default: 0)
+    pub fn seed(self, seed: u64) -> Self {
+        self.rng.replace(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Estimate the size of the neighborhood of a permutation of ```n``` elements, before degenerate moves are excluded.
+    fn neighborhood_size(&self, n: usize) -> f64 {
+        let cuts = (0..self.k).fold(1., |acc, i| acc * (n + 1 - i) as f64 / (i + 1) as f64);
+        let orders: f64 = (1..self.k).map(|i| i as f64).product();
+        cuts * (orders * 2f64.powi(self.k as i32 - 1) - 1.)
+    }
+
+    /// Apply ```m```, or return ```None``` if it is degenerate, i.e. not a pure k-opt move.
+    fn apply(&self, solution: &Permutation, m: &KOptMove) -> Option<Permutation> {
+        let order = &solution.order;
+        let cuts = &m.cuts;
+        let identity = m.segments.iter().enumerate().all(|(i, s)| i == *s);
+        if identity && !m.reversed.contains(&true) {
+            return None;
+        }
+        if self.frozen.touches(cuts[0]..=cuts[self.k - 1] - 1) {
+            return None;
+        }
+
+        let mut neighbor = Vec::with_capacity(order.len());
+        neighbor.extend_from_slice(&order[..cuts[0]]);
+        for (segment, reversed) in m.segments.iter().zip(&m.reversed) {
+            let (first, end) = (cuts[*segment], cuts[segment + 1]);
+            if !reversed {
+                neighbor.extend_from_slice(&order[first..end]);
+            } else if end - first > 1 {
+                neighbor.extend(order[first..end].iter().rev());
+            } else {
+                // reversing a single element is no move
+                return None;
+            }
+        }
+        neighbor.extend_from_slice(&order[cuts[self.k - 1]..]);
+
+        // every edge at a cut between two elements must be broken
+        let mut position = vec![0; order.len()];
+        for (t, element) in neighbor.iter().enumerate() {
+            position[*element] = t;
+        }
+        let kept = cuts
+            .iter()
+            .filter(|c| **c > 0 && **c < order.len())
+            .any(|c| position[order[c - 1]].abs_diff(position[order[*c]]) == 1);
+        match kept {
+            true => None,
+            false => Some(Permutation::new(neighbor, &self.cost)),
+        }
+    }
+
+    /// Draw random moves until one is not degenerate, or give up after a number of attempts.
+    fn sample(&self, solution: &Permutation, rng: &mut dyn rand::RngCore) -> Option<Permutation> {
+        let n = solution.len();
+        if n + 1 < self.k {
+            return None;
+        }
+        (0..100).find_map(|_| {
+            let mut cuts = rand::seq::index::sample(rng, n + 1, self.k).into_vec();
+            cuts.sort_unstable();
+            let mut segments: Vec<usize> = (0..self.k - 1).collect();
+            segments.shuffle(rng);
+            let reversed = (0..self.k - 1).map(|_| rng.gen()).collect();
+            self.apply(
+                solution,
+                &KOptMove {
+                    cuts,
+                    segments,
+                    reversed,
+                },
+            )
+        })
+    }
+}
+
+/// Draw moves until one does not touch a frozen position, where ```changed``` returns the positions a move may
+/// change, or give up after a number of attempts.
+fn draw_free<M>(
+    frozen: &FrozenMask,
+    rng: &mut dyn rand::RngCore,
+    draw: impl Fn(&mut dyn rand::RngCore) -> M,
+    changed: impl Fn(&M) -> std::ops::RangeInclusive<usize>,
+) -> Option<M> {
+    (0..100)
+        .map(|_| draw(rng))
+        .find(|m| !frozen.touches(changed(m)))
+}
+
+/// All orders of ```0..n```.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    permutations(n - 1)
+        .into_iter()
+        .flat_map(|order| {
+            (0..n).map(move |t| {
+                let mut order = order.clone();
+                order.insert(t, n - 1);
+                order
+            })
+        })
+        .collect()
+}
+
+/// Advance ```cuts``` to the next increasing sequence of positions in ```0..=n```, in lexicographic order.
+fn next_cuts(cuts: &mut [usize], n: usize) -> bool {
+    let k = cuts.len();
+    match (0..k).rev().find(|&i| cuts[i] < n + 1 - k + i) {
+        Some(i) => {
+            cuts[i] += 1;
+            for j in i + 1..k {
+                cuts[j] = cuts[j - 1] + 1;
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+impl<F: Fn(&Permutation) -> f32 + Clone + 'static> Operator for SwapOperator<F> {
+    type Solution = Permutation;
+
+    fn construct_neighborhood(
+        &self,
+        solution: Permutation,
+    ) -> Box<dyn Iterator<Item = Permutation>> {
+        let operator = Self::new(self.cost.clone());
+        let free = self.frozen.free(solution.len());
+        let n = free.len();
+        Box::new(
+            (0..n)
+                .flat_map(move |index1| (index1 + 1..n).map(move |index2| (index1, index2)))
+                .map(move |(index1, index2)| operator.apply(&solution, free[index1], free[index2])),
+        )
+    }
+
+    fn shake(&self, solution: Permutation, rng: &mut dyn rand::RngCore) -> Permutation {
+        let free = self.frozen.free(solution.len());
+        let n = free.len();
+        if n < 2 {
+            return solution;
+        }
+        let index1 = rng.gen_range(0..n);
+        let index2 = (index1 + rng.gen_range(1..n)) % n;
+        self.apply(&solution, free[index1], free[index2])
+    }
+}
+
+impl<F: Fn(&Permutation) -> f32 + Clone + 'static> Operator for InsertionOperator<F> {
+    type Solution = Permutation;
+
+    fn construct_neighborhood(
+        &self,
+        solution: Permutation,
+    ) -> Box<dyn Iterator<Item = Permutation>> {
+        let operator = Self::new(self.cost.clone());
+        let frozen = self.frozen.clone();
+        let n = solution.len();
+        Box::new(
+            (0..n)
+                .flat_map(move |from| {
+                    (0..n)
+                        .filter(move |&to| to != from)
+                        .map(move |to| (from, to))
+                })
+                .filter(move |&(from, to)| !frozen.touches(from.min(to)..=from.max(to)))
+                .map(move |(from, to)| operator.apply(&solution, from, to)),
+        )
+    }
+
+    fn shake(&self, solution: Permutation, rng: &mut dyn rand::RngCore) -> Permutation {
+        let n = solution.len();
+        if n < 2 {
+            return solution;
+        }
+        let draw = |rng: &mut dyn rand::RngCore| {
+            let from = rng.gen_range(0..n);
+            (from, (from + rng.gen_range(1..n)) % n)
+        };
+        match draw_free(&self.frozen, rng, draw, |&(from, to)| {
+            from.min(to)..=from.max(to)
+        }) {
+            Some((from, to)) => self.apply(&solution, from, to),
+            None => solution,
+        }
+    }
+}
+
+impl<F: Fn(&Permutation) -> f32 + Clone + 'static> Operator for TwoOptOperator<F> {
+    type Solution = Permutation;
+
+    fn construct_neighborhood(
+        &self,
+        solution: Permutation,
+    ) -> Box<dyn Iterator<Item = Permutation>> {
+        let operator = Self::new(self.cost.clone());
+        let frozen = self.frozen.clone();
+        let n = solution.len();
+        Box::new(
+            (0..n)
+                .flat_map(move |first| (first + 1..n).map(move |last| (first, last)))
+                .filter(move |&(first, last)| !frozen.touches(first..=last))
+                .map(move |(first, last)| operator.apply(&solution, first, last)),
+        )
+    }
+
+    fn shake(&self, solution: Permutation, rng: &mut dyn rand::RngCore) -> Permutation {
+        let n = solution.len();
+        if n < 2 {
+            return solution;
+        }
+        let draw = |rng: &mut dyn rand::RngCore| {
+            let index1 = rng.gen_range(0..n);
+            let index2 = (index1 + rng.gen_range(1..n)) % n;
+            (index1.min(index2), index1.max(index2))
+        };
+        match draw_free(&self.frozen, rng, draw, |&(first, last)| first..=last) {
+            Some((first, last)) => self.apply(&solution, first, last),
+            None => solution,
+        }
+    }
+}
+
+impl<F: Fn(&Permutation) -> f32 + Clone + 'static> Operator for OrOptOperator<F> {
+    type Solution = Permutation;
+
+    fn construct_neighborhood(
+        &self,
+        solution: Permutation,
+    ) -> Box<dyn Iterator<Item = Permutation>> {
+        let operator = Self {
+            cost: self.cost.clone(),
+            max_segment_len: self.max_segment_len,
+            provider: self.provider.clone(),
+            frozen: self.frozen.clone(),
+        };
+        let n = solution.len();
+        let max_segment_len = self.max_segment_len;
+        let moves: Vec<(usize, usize, usize)> = self
+            .segment_lengths(n)
+            .flat_map(|length| {
+                (0..=n - length).flat_map(move |from| {
+                    (0..=n - length)
+                        .filter(move |&to| to > from || to + max_segment_len < from)
+                        .map(move |to| (from, length, to))
+                })
+            })
+            .filter(|m| !self.frozen.touches(relocated(m)))
+            .collect();
+        Box::new(
+            moves
+                .into_iter()
+                .map(move |(from, length, to)| operator.apply(&solution, from, length, to)),
+        )
+    }
+
+    fn shake(&self, solution: Permutation, rng: &mut dyn rand::RngCore) -> Permutation {
+        let n = solution.len();
+        if n < 2 {
+            return solution;
+        }
+        let draw = |rng: &mut dyn rand::RngCore| {
+            let length = rng.gen_range(self.segment_lengths(n));
+            let from = rng.gen_range(0..=n - length);
+            let to = (from + rng.gen_range(1..=n - length)) % (n - length + 1);
+            (from, length, to)
+        };
+        match draw_free(&self.frozen, rng, draw, relocated) {
+            Some((from, length, to)) => self.apply(&solution, from, length, to),
+            None => solution,
+        }
+    }
+}
+
+impl<P: CostProvider + 'static> Operator for ThreeOptOperator<P> {
+    type Solution = Permutation;
+
+    fn construct_neighborhood(
+        &self,
+        solution: Permutation,
+    ) -> Box<dyn Iterator<Item = Permutation>> {
+        let operator = Self {
+            provider: self.provider.clone(),
+            frozen: self.frozen.clone(),
+        };
+        let frozen = self.frozen.clone();
+        let costs = PathCosts::new(&solution.order, self.provider.as_ref());
+        let n = solution.len();
+        Box::new(
+            (0..n)
+                .flat_map(move |i| (i + 1..n).flat_map(move |j| (j + 1..n).map(move |k| (i, j, k))))
+                .filter(move |&(i, _, k)| !frozen.touches(i + 1..=k))
+                .flat_map(|cuts| RECONNECTIONS.iter().map(move |r| (cuts, *r)))
+                .filter(|&((i, j, k), reconnection)| reconnection.is_enumerated(j - i, k - j))
+                .map(move |(cuts, reconnection)| {
+                    operator.apply(&solution, &costs, cuts, reconnection)
+                }),
+        )
+    }
+
+    fn shake(&self, solution: Permutation, rng: &mut dyn rand::RngCore) -> Permutation {
+        let n = solution.len();
+        if n < 3 {
+            return solution;
+        }
+        let draw = |rng: &mut dyn rand::RngCore| {
+            let mut cuts: Vec<usize> = rand::seq::index::sample(rng, n, 3).into_vec();
+            cuts.sort_unstable();
+            let reconnection = RECONNECTIONS[rng.gen_range(0..RECONNECTIONS.len())];
+            ((cuts[0], cuts[1], cuts[2]), reconnection)
+        };
+        match draw_free(&self.frozen, rng, draw, |&((i, _, k), _)| i + 1..=k) {
+            Some((cuts, reconnection)) => {
+                let costs = PathCosts::new(&solution.order, self.provider.as_ref());
+                self.apply(&solution, &costs, cuts, reconnection)
+            }
+            None => solution,
+        }
+    }
+}
+
+impl<F: Fn(&Permutation) -> f32 + Clone + 'static> Operator for KOpt<F> {
+    type Solution = Permutation;
+
+    /// Enumerate all pure k-opt moves, or sample them if there are too many, see [KOpt].
+    fn construct_neighborhood(
+        &self,
+        solution: Permutation,
+    ) -> Box<dyn Iterator<Item = Permutation>> {
+        let operator = Self {
+            k: self.k,
+            cost: self.cost.clone(),
+            max_neighbors: self.max_neighbors,
+            rng: self.rng.clone(),
+            frozen: self.frozen.clone(),
+        };
+        let n = solution.len();
+        if n + 1 < self.k {
+            return Box::new(std::iter::empty());
+        }
+        if self.neighborhood_size(n) > self.max_neighbors as f64 {
+            let rng = self.rng.clone();
+            return Box::new(
+                (0..self.max_neighbors)
+                    .filter_map(move |_| operator.sample(&solution, &mut *rng.borrow_mut())),
+            );
+        }
+
+        let reconnections: Rc<Vec<(Vec<usize>, Vec<bool>)>> = Rc::new(
+            permutations(self.k - 1)
+                .into_iter()
+                .flat_map(|segments| {
+                    (0..1 << (self.k - 1)).map(move |mask: usize| {
+                        let reversed = (0..segments.len()).map(|i| mask >> i & 1 == 1).collect();
+                        (segments.clone(), reversed)
+                    })
+                })
+                .collect(),
+        );
+        let mut cuts = Some((0..self.k).collect::<Vec<usize>>());
+        let all_cuts = std::iter::from_fn(move || {
+            let current = cuts.take()?;
+            let mut next = current.clone();
+            if next_cuts(&mut next, n) {
+                cuts = Some(next);
+            }
+            Some(current)
+        });
+        Box::new(
+            all_cuts
+                .flat_map(move |cuts| {
+                    let reconnections = reconnections.clone();
+                    (0..reconnections.len()).map(move |r| KOptMove {
+                        cuts: cuts.clone(),
+                        segments: reconnections[r].0.clone(),
+                        reversed: reconnections[r].1.clone(),
+                    })
+                })
+                .filter_map(move |m| operator.apply(&solution, &m)),
+        )
+    }
+
+    fn shake(&self, solution: Permutation, rng: &mut dyn rand::RngCore) -> Permutation {
+        match self.sample(&solution, rng) {
+            Some(neighbor) => neighbor,
+            None => solution,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use rand::SeedableRng;
+
+    use crate::{
+        solutions::{
+            permutation::{
+                tour_cost, CostMatrix, InsertionOperator, KOpt, OrOptOperator, Permutation,
+                SegmentReversal, SwapOperator, ThreeOptOperator, TwoOptOperator,
+            },
+            FrozenMask,
+        },
+        Distance, Evaluate, Operator,
+    };
+
+    fn cost(permutation: &Permutation) -> f32 {
+        permutation
+            .order()
+            .windows(2)
+            .map(|pair| (pair[0] as f32 - pair[1] as f32).abs())
+            .sum()
+    }
+
+    fn assert_neighborhood<O: Operator<Solution = Permutation>>(
+        operator: &O,
+        expected_size: usize,
+    ) {
+        let solution = Permutation::new(vec![3, 0, 4, 1, 2], &cost);
+        let neighbors: Vec<Permutation> =
+            operator.construct_neighborhood(solution.clone()).collect();
+        assert_eq!(neighbors.len(), expected_size);
+        for neighbor in neighbors {
+            assert!(neighbor.is_valid());
+            assert_ne!(neighbor.order(), solution.order());
+            assert_eq!(neighbor.evaluate(), cost(&neighbor));
+        }
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut shaken = solution;
+        for _ in 0..100 {
+            shaken = operator.shake(shaken, &mut rng);
+            assert!(shaken.is_valid());
+        }
+    }
+
+    fn neighbor_orders<O: Operator<Solution = Permutation>>(operator: &O) -> Vec<Vec<usize>> {
+        operator
+            .construct_neighborhood(Permutation::new(vec![3, 0, 4, 1, 2], &cost))
+            .map(|x| x.order().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn swap_neighborhood() {
+        assert_neighborhood(&SwapOperator::new(cost), 5 * 4 / 2);
+        // the pairs of positions, in lexicographic order
+        assert_eq!(
+            neighbor_orders(&SwapOperator::new(cost)),
+            vec![
+                vec![0, 3, 4, 1, 2],
+                vec![4, 0, 3, 1, 2],
+                vec![1, 0, 4, 3, 2],
+                vec![2, 0, 4, 1, 3],
+                vec![3, 4, 0, 1, 2],
+                vec![3, 1, 4, 0, 2],
+                vec![3, 2, 4, 1, 0],
+                vec![3, 0, 1, 4, 2],
+                vec![3, 0, 2, 1, 4],
+                vec![3, 0, 4, 2, 1],
+            ]
+        );
+    }
+
+    #[test]
+    fn insertion_neighborhood() {
+        assert_neighborhood(&InsertionOperator::new(cost), 5 * 4);
+        // every element, in order, to every other position, where moving adjacent elements coincides
+        assert_eq!(
+            neighbor_orders(&InsertionOperator::new(cost)),
+            vec![
+                vec![0, 3, 4, 1, 2],
+                vec![0, 4, 3, 1, 2],
+                vec![0, 4, 1, 3, 2],
+                vec![0, 4, 1, 2, 3],
+                vec![0, 3, 4, 1, 2],
+                vec![3, 4, 0, 1, 2],
+                vec![3, 4, 1, 0, 2],
+                vec![3, 4, 1, 2, 0],
+                vec![4, 3, 0, 1, 2],
+                vec![3, 4, 0, 1, 2],
+                vec![3, 0, 1, 4, 2],
+                vec![3, 0, 1, 2, 4],
+                vec![1, 3, 0, 4, 2],
+                vec![3, 1, 0, 4, 2],
+                vec![3, 0, 1, 4, 2],
+                vec![3, 0, 4, 2, 1],
+                vec![2, 3, 0, 4, 1],
+                vec![3, 2, 0, 4, 1],
+                vec![3, 0, 2, 4, 1],
+                vec![3, 0, 4, 2, 1],
+            ]
+        );
+    }
+
+    #[test]
+    fn two_opt_neighborhood() {
+        assert_neighborhood(&TwoOptOperator::new(cost), 5 * 4 / 2);
+        // the reversed segments, in lexicographic order of their ends
+        assert_eq!(
+            neighbor_orders(&TwoOptOperator::new(cost)),
+            vec![
+                vec![0, 3, 4, 1, 2],
+                vec![4, 0, 3, 1, 2],
+                vec![1, 4, 0, 3, 2],
+                vec![2, 1, 4, 0, 3],
+                vec![3, 4, 0, 1, 2],
+                vec![3, 1, 4, 0, 2],
+                vec![3, 2, 1, 4, 0],
+                vec![3, 0, 1, 4, 2],
+                vec![3, 0, 2, 1, 4],
+                vec![3, 0, 4, 2, 1],
+            ]
+        );
+    }
+
+    #[test]
+    fn or_opt_neighborhood() {
+        // segments of length l have (n - l + 1) start positions, and move forward to (n - l + 1 choose 2)
+        // destinations, while backward moves past at most 3 elements are forward moves of those elements
+        assert_neighborhood(&OrOptOperator::new(cost), 10 + 1 + 6 + 3);
+        assert_neighborhood(&OrOptOperator::new(cost).max_segment_len(1), 10 + 6);
+    }
+
+    #[test]
+    fn or_opt_relocations() {
+        let solution = Permutation::new(vec![0, 1, 2, 3], &cost);
+        let orders: Vec<Vec<usize>> = OrOptOperator::new(cost)
+            .max_segment_len(2)
+            .construct_neighborhood(solution)
+            .map(|x| x.order().to_vec())
+            .collect();
+        assert_eq!(
+            orders,
+            vec![
+                // single elements forward
+                vec![1, 0, 2, 3],
+                vec![1, 2, 0, 3],
+                vec![1, 2, 3, 0],
+                vec![0, 2, 1, 3],
+                vec![0, 2, 3, 1],
+                vec![0, 1, 3, 2],
+                // the last element backward, past more elements than fit in a segment
+                vec![3, 0, 1, 2],
+                // pairs forward
+                vec![2, 0, 1, 3],
+                vec![2, 3, 0, 1],
+                vec![0, 3, 1, 2],
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "maximum segment length must be positive")]
+    fn or_opt_empty_segments() {
+        OrOptOperator::new(cost).max_segment_len(0);
+    }
+
+    #[test]
+    fn or_opt_delta_evaluation() {
+        let provider = |from: usize, to: usize| (from * 7 + to * 3) as f32 % 11. + 1.;
+        let cost = tour_cost(provider);
+        let solution = Permutation::new(vec![0, 1, 2, 3, 4, 5], &cost);
+        let operator = OrOptOperator::new(cost.clone()).delta_evaluation(provider);
+        let neighbors: Vec<Permutation> =
+            operator.construct_neighborhood(solution.clone()).collect();
+        assert_eq!(neighbors.len(), (15 + 3) + (10 + 1) + 6);
+        for neighbor in &neighbors {
+            assert!(neighbor.is_valid());
+            assert_ne!(neighbor.order(), solution.order());
+            assert!((neighbor.evaluate() - cost(neighbor)).abs() < 1e-4);
+        }
+
+        // the segment 1, 2 is relocated forward, and the segment 3, 4, 5 to the front
+        let orders: Vec<&[usize]> = neighbors.iter().map(|x| x.order()).collect();
+        assert_eq!(orders.iter().collect::<BTreeSet<_>>().len(), orders.len());
+        assert!(orders.contains(&&[0, 3, 4, 1, 2, 5][..]));
+        assert!(orders.contains(&&[3, 4, 5, 0, 1, 2][..]));
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut shaken = solution;
+        for _ in 0..100 {
+            shaken = operator.shake(shaken, &mut rng);
+            assert!(shaken.is_valid());
+            assert!((shaken.evaluate() - cost(&shaken)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn hamming_distance() {
+        let permutation = Permutation::new(vec![3, 0, 4, 1, 2], &cost);
+        let swapped = Permutation::new(vec![3, 1, 4, 0, 2], &cost);
+        assert_eq!(permutation.distance(&permutation.clone()), 0.);
+        assert_eq!(permutation.distance(&swapped), 2.);
+        assert_eq!(swapped.distance(&permutation), 2.);
+    }
+
+    #[test]
+    fn invalid_permutation() {
+        assert!(Permutation::new(vec![0, 2, 1], &cost).is_valid());
+        assert!(!Permutation::new(vec![0, 2, 2], &cost).is_valid());
+        assert!(!Permutation::new(vec![0, 3, 1], &cost).is_valid());
+    }
+
+    #[test]
+    fn asymmetric_tour_cost() {
+        let matrix = CostMatrix::new(vec![
+            vec![0., 1., 10., 10.],
+            vec![10., 0., 1., 10.],
+            vec![10., 10., 0., 1.],
+            vec![1., 10., 10., 0.],
+        ]);
+        let cost = tour_cost(matrix);
+        let forward = Permutation::new(vec![0, 1, 2, 3], &cost);
+        let reverse = Permutation::new(vec![3, 2, 1, 0], &cost);
+        assert_eq!(forward.evaluate(), 4.);
+        assert_eq!(reverse.evaluate(), 40.);
+
+        // the operators evaluate their neighbors with the provider
+        let operator = TwoOptOperator::new(cost.clone());
+        let best = operator.find_best_neighbor(reverse.clone());
+        assert_eq!(best.evaluate(), cost(&best));
+        assert!(best.evaluate() < 40.);
+
+        // closures are providers too
+        let uphill = |from: usize, to: usize| if to > from { 2. } else { 1. };
+        assert_eq!(forward.tour_cost(&uphill), 7.);
+        assert_eq!(reverse.tour_cost(&uphill), 5.);
+    }
+
+    #[test]
+    fn segment_reversal_differs_from_swap() {
+        let solution = Permutation::new(vec![0, 1, 2, 3, 4, 5], &cost);
+        let reversal = SegmentReversal::new(cost);
+        let neighbors: Vec<Vec<usize>> = reversal
+            .construct_neighborhood(solution.clone())
+            .map(|x| x.order().to_vec())
+            .collect();
+        assert_eq!(neighbors.len(), 6 * 5 / 2);
+        // the segment 1..=4 is reversed, while a swap would leave 2 and 3 in place
+        assert!(neighbors.contains(&vec![0, 4, 3, 2, 1, 5]));
+        assert!(!neighbors.contains(&vec![0, 4, 2, 3, 1, 5]));
+
+        // no reversal is enumerated twice
+        let mut distinct = neighbors.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), neighbors.len());
+
+        let swaps: Vec<Vec<usize>> = SwapOperator::new(cost)
+            .construct_neighborhood(solution)
+            .map(|x| x.order().to_vec())
+            .collect();
+        assert_ne!(swaps, neighbors);
+    }
+
+    /// Rotate and orient a tour such that equal tours have equal orders.
+    fn canonical(order: &[usize]) -> Vec<usize> {
+        let n = order.len();
+        let start = order.iter().position(|&x| x == 0).unwrap();
+        let rotated: Vec<usize> = (0..n).map(|t| order[(start + t) % n]).collect();
+        let mut reversed = rotated.clone();
+        reversed[1..].reverse();
+        rotated.min(reversed)
+    }
+
+    #[test]
+    fn three_opt_neighborhood() {
+        // asymmetric, such that the orientation of reversed paths matters
+        let provider = |from: usize, to: usize| (from * 7 + to * 3) as f32 % 11. + 1.;
+        let cost = tour_cost(provider);
+        let solution = Permutation::new(vec![0, 1, 2, 3, 4, 5], &cost);
+        let operator = ThreeOptOperator::new(provider);
+        let neighbors: Vec<Permutation> =
+            operator.construct_neighborhood(solution.clone()).collect();
+        for neighbor in &neighbors {
+            assert!(neighbor.is_valid());
+            assert!((neighbor.evaluate() - cost(neighbor)).abs() < 1e-4);
+        }
+
+        // every distinct reconnection of every cut, except for the tour itself, exactly once
+        let order = solution.order();
+        let mut expected = BTreeSet::new();
+        for i in 0..6 {
+            for j in i + 1..6 {
+                for k in j + 1..6 {
+                    let (b, c) = (&order[i + 1..=j], &order[j + 1..=k]);
+                    let reversed = |path: &[usize]| path.iter().rev().copied().collect::<Vec<_>>();
+                    for (x, y) in [
+                        (reversed(b), c.to_vec()),
+                        (b.to_vec(), reversed(c)),
+                        (reversed(c), reversed(b)),
+                        (reversed(b), reversed(c)),
+                        (c.to_vec(), b.to_vec()),
+                        (reversed(c), b.to_vec()),
+                        (c.to_vec(), reversed(b)),
+                    ] {
+                        expected.insert([&order[..=i], &x, &y, &order[k + 1..]].concat());
+                    }
+                }
+            }
+        }
+        expected.remove(order);
+        let orders: Vec<Vec<usize>> = neighbors.iter().map(|x| x.order().to_vec()).collect();
+        assert_eq!(orders.len(), expected.len());
+        assert_eq!(orders.iter().cloned().collect::<BTreeSet<_>>(), expected);
+        // the (n-1)(n-2)/2 reversals, and the other moves
+        assert_eq!(orders.len(), 5 * 4 / 2 + 28);
+
+        // exchanging B and C without reversal is beyond 2-opt
+        let two_opt: Vec<Vec<usize>> = TwoOptOperator::new(cost.clone())
+            .construct_neighborhood(solution.clone())
+            .map(|x| canonical(x.order()))
+            .collect();
+        let exchanged = canonical(&[0, 3, 4, 1, 2, 5]);
+        assert!(neighbors.iter().any(|x| canonical(x.order()) == exchanged));
+        assert!(!two_opt.contains(&exchanged));
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut shaken = solution;
+        for _ in 0..100 {
+            shaken = operator.shake(shaken, &mut rng);
+            assert!(shaken.is_valid());
+            assert!((shaken.evaluate() - cost(&shaken)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn k_opt_neighborhood() {
+        let solution = Permutation::new(vec![3, 0, 4, 1, 2], &cost);
+        let orders = |operator: &dyn Operator<Solution = Permutation>| -> Vec<Vec<usize>> {
+            operator
+                .construct_neighborhood(solution.clone())
+                .map(|x| x.order().to_vec())
+                .collect()
+        };
+        assert_eq!(
+            orders(&KOpt::new(2, cost)),
+            orders(&TwoOptOperator::new(cost))
+        );
+        assert_neighborhood(&KOpt::new(2, cost), 5 * 4 / 2);
+
+        // exchanging the segments 0 and 4, 1 breaks all three cut edges
+        let three_opt = orders(&KOpt::new(3, cost));
+        assert!(three_opt.contains(&vec![3, 4, 1, 0, 2]));
+        assert_neighborhood(&KOpt::new(3, cost), three_opt.len());
+
+        // too large neighborhoods are sampled
+        let sampled = KOpt::new(4, cost).max_neighbors(10).seed(1);
+        assert_eq!(orders(&sampled).len(), 10);
+        assert_neighborhood(&sampled, 10);
+    }
+
+    #[test]
+    fn frozen_positions_never_change() {
+        let provider = |from: usize, to: usize| (from * 7 + to * 3) as f32 % 11. + 1.;
+        let frozen = FrozenMask::new([0, 3]);
+        let operators: Vec<Box<dyn Operator<Solution = Permutation>>> = vec![
+            Box::new(SwapOperator::new(cost).frozen(frozen.clone())),
+            Box::new(InsertionOperator::new(cost).frozen(frozen.clone())),
+            Box::new(TwoOptOperator::new(cost).frozen(frozen.clone())),
+            Box::new(OrOptOperator::new(cost).frozen(frozen.clone())),
+            Box::new(ThreeOptOperator::new(provider).frozen(frozen.clone())),
+            Box::new(KOpt::new(3, cost).frozen(frozen)),
+        ];
+        let unchanged =
+            |permutation: &Permutation| permutation.order()[0] == 0 && permutation.order()[3] == 3;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        for operator in operators {
+            let solution = Permutation::identity(7, &cost);
+            let mut neighbors = operator.construct_neighborhood(solution.clone()).peekable();
+            assert!(neighbors.peek().is_some());
+            assert!(neighbors.all(|neighbor| unchanged(&neighbor)));
+
+            let mut shaken = solution;
+            for _ in 0..200 {
+                shaken = operator.shake(shaken, &mut rng);
+                assert!(shaken.is_valid());
+                assert!(unchanged(&shaken));
+            }
+        }
+    }
+}
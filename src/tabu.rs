@@ -0,0 +1,389 @@
+//! Short-term memory of recently visited solutions or moves, as used by _tabu search_
+use std::collections::{HashMap, VecDeque};
+
+use crate::{is_improvement, KeyFn, SolutionKey};
+
+/// Remembers the keys of the last ```tenure``` solutions inserted, which are tabu until they are forgotten.
+///
+/// Combine with [crate::Operator::find_best_neighbor_filtered] to move to the best non-tabu neighbor.
+pub struct TabuList<Solution> {
+    tenure: usize,
+    key: KeyFn<Solution>,
+    keys: VecDeque<u64>,
+}
+
+/// The attributes of a move, e.g. the edges it removes from a tour and the edges it adds, each identified by a key.
+///
+/// See [crate::MoveOperator::move_attributes].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MoveAttributes {
+    /// Attributes the move removes from the solution
+    pub removed: Vec<u64>,
+    /// Attributes the move adds to the solution
+    pub added: Vec<u64>,
+}
+
+/// Remembers recently removed move attributes, e.g. edges, which are tabu to add again until their tenure expires.
+///
+/// Attribute-based memory forbids whole classes of moves, rather than single solutions, and is thus cheaper and
+/// often more effective than a [TabuList]. Every attribute has its own tenure, counted in calls to
+/// [AttributeTabuList::step].
+pub struct AttributeTabuList {
+    tenure: usize,
+    iteration: usize,
+    expiry: HashMap<u64, usize>,
+}
+
+/// Overrides the tabu status of a move whose objective is good enough, as judged by the criterion.
+#[allow(unused_variables)]
+pub trait Aspiration {
+    /// Test whether a tabu move with ```attributes```, which leads to ```objective```, is allowed anyway, where
+    /// ```best``` is the objective of the best solution found so far.
+    fn aspirates(&self, attributes: &MoveAttributes, objective: f32, best: f32) -> bool;
+
+    /// Observe a move with ```attributes``` which was made, and led to ```objective```.
+    fn record(&mut self, attributes: &MoveAttributes, objective: f32) {}
+}
+
+/// Aspiration by global best: allow a tabu move if it leads to a new best solution.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GlobalBest;
+
+/// Aspiration by objective per attribute: allow a tabu move if it leads to a better objective than every move made
+/// so far which added one of its attributes.
+///
+/// Attributes which were never added by a recorded move do not aspirate.
+#[derive(Clone, Debug, Default)]
+pub struct AttributeBest {
+    best: HashMap<u64, f32>,
+}
+
+impl<Solution> TabuList<Solution> {
+    /// Identify solutions by their [SolutionKey].
+    pub fn new(tenure: usize) -> Self
+    where
+        Solution: SolutionKey,
+    {
+        Self::with_key(tenure, |solution: &Solution| solution.key())
+    }
+
+    /// Identify solutions by ```key```.
+    pub fn with_key<F: Fn(&Solution) -> u64 + 'static>(tenure: usize, key: F) -> Self {
+        Self {
+            tenure,
+            key: Box::new(key),
+            keys: VecDeque::with_capacity(tenure),
+        }
+    }
+
+    /// Make ```solution``` tabu, and forget the oldest tabu solution if the tenure is exceeded.
+    pub fn insert(&mut self, solution: &Solution) {
+        if self.tenure == 0 {
+            return;
+        }
+        if self.keys.len() == self.tenure {
+            self.keys.pop_front();
+        }
+        self.keys.push_back((self.key)(solution));
+    }
+
+    /// Test whether ```solution``` is tabu.
+    pub fn is_tabu(&self, solution: &Solution) -> bool {
+        self.keys.contains(&(self.key)(solution))
+    }
+
+    /// Return the number of tabu solutions.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Test whether no solution is tabu.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Forget all tabu solutions.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
+
+impl AttributeTabuList {
+    /// Keep attributes tabu for ```tenure``` iterations, unless inserted with a tenure of their own.
+    pub fn new(tenure: usize) -> Self {
+        Self {
+            tenure,
+            iteration: 0,
+            expiry: HashMap::new(),
+        }
+    }
+
+    /// Make ```attribute``` tabu for the default tenure.
+    pub fn insert(&mut self, attribute: u64) {
+        self.insert_with_tenure(attribute, self.tenure);
+    }
+
+    /// Make ```attribute``` tabu for ```tenure``` iterations.
+    pub fn insert_with_tenure(&mut self, attribute: u64, tenure: usize) {
+        if tenure == 0 {
+            return;
+        }
+        self.expiry.insert(attribute, self.iteration + tenure);
+    }
+
+    /// Make the attributes removed by a move tabu, such that they cannot be added again right away.
+    pub fn record(&mut self, attributes: &MoveAttributes) {
+        for attribute in &attributes.removed {
+            self.insert(*attribute);
+        }
+    }
+
+    /// Test whether ```attribute``` is tabu.
+    pub fn is_tabu(&self, attribute: u64) -> bool {
+        self.expiry
+            .get(&attribute)
+            .is_some_and(|expiry| *expiry > self.iteration)
+    }
+
+    /// Test whether a move is tabu, i.e. whether it adds a tabu attribute.
+    pub fn is_tabu_move(&self, attributes: &MoveAttributes) -> bool {
+        attributes
+            .added
+            .iter()
+            .any(|attribute| self.is_tabu(*attribute))
+    }
+
+    /// Test whether a move may be made, i.e. whether it is not tabu, or ```aspiration``` overrides its tabu status.
+    pub fn is_allowed(
+        &self,
+        attributes: &MoveAttributes,
+        objective: f32,
+        best: f32,
+        aspiration: &dyn Aspiration,
+    ) -> bool {
+        !self.is_tabu_move(attributes) || aspiration.aspirates(attributes, objective, best)
+    }
+
+    /// Advance by one iteration, and forget the attributes whose tenure expired.
+    pub fn step(&mut self) {
+        self.iteration += 1;
+        let iteration = self.iteration;
+        self.expiry.retain(|_, expiry| *expiry > iteration);
+    }
+
+    /// Return the number of tabu attributes.
+    pub fn len(&self) -> usize {
+        self.expiry.len()
+    }
+
+    /// Test whether no attribute is tabu.
+    pub fn is_empty(&self) -> bool {
+        self.expiry.is_empty()
+    }
+
+    /// Forget all tabu attributes.
+    pub fn clear(&mut self) {
+        self.expiry.clear();
+    }
+}
+
+impl Aspiration for GlobalBest {
+    fn aspirates(&self, _attributes: &MoveAttributes, objective: f32, best: f32) -> bool {
+        is_improvement(objective, best, 0.)
+    }
+}
+
+impl AttributeBest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the best objective led to by a recorded move which added ```attribute```, if any.
+    pub fn best(&self, attribute: u64) -> Option<f32> {
+        self.best.get(&attribute).copied()
+    }
+}
+
+impl Aspiration for AttributeBest {
+    fn aspirates(&self, attributes: &MoveAttributes, objective: f32, _best: f32) -> bool {
+        attributes.added.iter().any(|attribute| {
+            self.best(*attribute)
+                .is_some_and(|best| is_improvement(objective, best, 0.))
+        })
+    }
+
+    fn record(&mut self, attributes: &MoveAttributes, objective: f32) {
+        for attribute in &attributes.added {
+            let best = self.best.entry(*attribute).or_insert(objective);
+            if is_improvement(objective, *best, 0.) {
+                *best = objective;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::{
+        tabu::{
+            Aspiration, AttributeBest, AttributeTabuList, GlobalBest, MoveAttributes, TabuList,
+        },
+        test::*,
+        Evaluate, IncrementalSolution, MoveOperator, Operator, SolutionKey,
+    };
+
+    /// A tour, modified by 2-opt moves which reverse the segment between two positions.
+    struct Tour {
+        order: Vec<usize>,
+    }
+
+    /// Reverse the segment between two positions, exclusive of the first and last city.
+    struct TwoOpt;
+
+    /// An undirected edge between two cities.
+    fn edge(a: usize, b: usize) -> u64 {
+        (a.min(b) as u64) << 32 | a.max(b) as u64
+    }
+
+    impl Evaluate for Tour {
+        fn evaluate(&self) -> f32 {
+            0.
+        }
+    }
+
+    impl IncrementalSolution for Tour {
+        type Move = (usize, usize);
+
+        fn apply_move(&mut self, (i, j): &(usize, usize)) {
+            self.order[*i..=*j].reverse();
+        }
+
+        fn undo_move(&mut self, m: &(usize, usize)) {
+            self.apply_move(m);
+        }
+    }
+
+    impl MoveOperator for TwoOpt {
+        type Solution = Tour;
+
+        fn random_move(&self, _solution: &Tour, _rng: &mut dyn rand::RngCore) -> (usize, usize) {
+            (1, 2)
+        }
+
+        fn move_attributes(&self, tour: &Tour, (i, j): &(usize, usize)) -> MoveAttributes {
+            let order = &tour.order;
+            MoveAttributes {
+                removed: vec![edge(order[i - 1], order[*i]), edge(order[*j], order[j + 1])],
+                added: vec![edge(order[i - 1], order[*j]), edge(order[*i], order[j + 1])],
+            }
+        }
+    }
+
+    #[test]
+    fn equal_solutions_equal_keys() {
+        let solution = Number::new(3, 8.);
+        assert_eq!(solution.key(), solution.clone().key());
+        assert_ne!(solution.key(), Number::new(4, 8.).key());
+    }
+
+    #[test]
+    fn tabu_list() {
+        let numbers = vec![9., 8., 7., 8., 9., 7., 5., 0.];
+        let operator = NeighborsUpUntilN::new(&numbers, 1);
+        let mut tabu = TabuList::new(2);
+
+        // index 2 is the best neighbor of index 3, unless it is tabu
+        tabu.insert(&Number::new(2, numbers[2]));
+        assert!(tabu.is_tabu(&Number::new(2, numbers[2])));
+        let neighbor = operator
+            .find_best_neighbor_filtered(Number::new(3, numbers[3]), &|x| !tabu.is_tabu(x))
+            .unwrap();
+        assert_eq!(neighbor.index(), 4);
+
+        // the oldest solution is forgotten once the tenure is exceeded
+        tabu.insert(&Number::new(5, numbers[5]));
+        tabu.insert(&Number::new(6, numbers[6]));
+        assert_eq!(tabu.len(), 2);
+        assert!(!tabu.is_tabu(&Number::new(2, numbers[2])));
+
+        // a closure overrides the key, here identifying solutions by their objective
+        let mut tabu = TabuList::with_key(1, |x: &Number| x.evaluate().to_bits() as u64);
+        tabu.insert(&Number::new(2, 7.));
+        assert!(tabu.is_tabu(&Number::new(5, 7.)));
+    }
+
+    #[test]
+    fn attribute_tabu_list() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut tour = Tour {
+            order: vec![0, 1, 2, 3],
+        };
+        let mut tabu = AttributeTabuList::new(2);
+
+        // removes the edges 0-1 and 2-3
+        let m = TwoOpt.random_move(&tour, &mut rng);
+        let attributes = TwoOpt.move_attributes(&tour, &m);
+        assert!(!tabu.is_tabu_move(&attributes));
+        tour.apply_move(&m);
+        tabu.record(&attributes);
+        tabu.step();
+
+        // reversing again re-adds the removed edges, which is tabu for the tenure
+        let reverse = TwoOpt.move_attributes(&tour, &m);
+        assert_eq!(reverse.added, attributes.removed);
+        assert!(tabu.is_tabu(edge(1, 0)));
+        assert!(tabu.is_tabu_move(&reverse));
+        tabu.step();
+        assert!(tabu.is_empty());
+        assert!(!tabu.is_tabu_move(&reverse));
+
+        // a tenure of its own
+        tabu.insert_with_tenure(edge(0, 2), 3);
+        tabu.step();
+        tabu.step();
+        assert!(tabu.is_tabu(edge(0, 2)));
+        tabu.step();
+        assert!(!tabu.is_tabu(edge(0, 2)));
+    }
+
+    #[test]
+    fn aspiration_criteria() {
+        let mut tabu = AttributeTabuList::new(3);
+        let mut attribute_best = AttributeBest::new();
+
+        // adding edge 0-2 led to an objective of 5, and removing it again makes it tabu
+        let added = MoveAttributes {
+            removed: vec![edge(0, 1)],
+            added: vec![edge(0, 2)],
+        };
+        attribute_best.record(&added, 5.);
+        attribute_best.record(&added, 6.);
+        assert_eq!(attribute_best.best(edge(0, 2)), Some(5.));
+        tabu.record(&MoveAttributes {
+            removed: vec![edge(0, 2)],
+            added: vec![edge(0, 1)],
+        });
+        tabu.step();
+
+        // re-adding it leads to an objective of 4, which beats the attribute's best, but not the global best of 3
+        let readd = MoveAttributes {
+            removed: vec![edge(1, 2)],
+            added: vec![edge(0, 2)],
+        };
+        let (objective, best) = (4., 3.);
+        assert!(tabu.is_tabu_move(&readd));
+        assert!(!tabu.is_allowed(&readd, objective, best, &GlobalBest));
+        assert!(tabu.is_allowed(&readd, objective, best, &attribute_best));
+
+        // only a new global best aspirates by global best
+        assert!(GlobalBest.aspirates(&readd, 2., best));
+        assert!(!attribute_best.aspirates(&readd, 5., best));
+        let unrecorded = MoveAttributes {
+            removed: vec![],
+            added: vec![edge(1, 3)],
+        };
+        assert!(!attribute_best.aspirates(&unrecorded, 2., best));
+    }
+}
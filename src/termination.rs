@@ -1,14 +1,36 @@
 //! All types required to model termination criteria
 
 use std::{
-    cell::RefCell,
-    ops::{Add, AddAssign},
-    time::{Duration, SystemTime},
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    ops::AddAssign,
+    rc::Rc,
+};
+
+use crate::{algorithms::sa::CoolingSchedule, ProposalEvaluation};
+#[cfg(feature = "std-time")]
+use std::{
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
 };
 
 /// Models a type representing a heuristic's termination criteria.
 pub trait TerminationCriteria<Solution> {
     fn terminate(&self, solution: &Solution) -> bool;
+
+    /// Return the fraction of the budget used so far, between 0 and 1, if the criterium is bounded.
+    fn progress(&self) -> Option<f32> {
+        None
+    }
+
+    /// Receive the evaluation of the last proposed candidate.
+    #[allow(unused_variables)]
+    fn feedback(&self, status: ProposalEvaluation) {}
+
+    /// Continue a search paused after ```iterations``` iterations, see [crate::pause::SearchState]. By default, the
+    /// criterium continues with its own state.
+    #[allow(unused_variables)]
+    fn resume(&self, iterations: usize) {}
 }
 
 /// Terminates when at least one termination criterium evaluates to true.
@@ -22,9 +44,71 @@ pub struct IterationTerminator {
     iteration: RefCell<usize>,
 }
 
+/// Terminates once a budget of neighbor evaluations, shared by all operators of a run, is exhausted.
+///
+/// The budget is drawn from by operators wrapped in a [crate::operators::BudgetedNeighborhood], see
+/// [BudgetTerminator::budget].
+pub struct BudgetTerminator {
+    neighbors: usize,
+    budget: Rc<Cell<usize>>,
+}
+
+/// Terminates once the temperature of a (shared) cooling schedule drops below ```t_min```, e.g. of a
+/// [crate::algorithms::sa::SimulatedAnnealing] which cools the same schedule.
+pub struct TemperatureTerminator {
+    schedule: Rc<dyn CoolingSchedule>,
+    t_min: f32,
+}
+
+/// Terminates after ```n``` consecutive candidates have been rejected, i.e. when the incumbent is stuck.
+pub struct NoAcceptTerminator {
+    n: usize,
+    rejections: RefCell<usize>,
+}
+
+/// Terminates when the best objective improved by less than ```min_improvement``` over the last ```window``` iterations.
+///
+/// Optionally, a stall first results in up to ```restarts``` [StallSignal::Restart] signals, which a driver such as
+/// [crate::algorithms::guided_restart::GuidedRestart] interprets as "perturb the best solution and continue".
+pub struct ImprovementRateTerminator {
+    window: usize,
+    min_improvement: f32,
+    restarts_max: usize,
+    restarts: RefCell<usize>,
+    history: RefCell<VecDeque<f32>>,
+}
+
+/// The verdict of an [ImprovementRateTerminator].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StallSignal {
+    /// The search is still improving
+    Continue,
+    /// The search stalled, but restarts are left
+    Restart,
+    /// The search stalled, and no restarts are left
+    Terminate,
+}
+
 /// Terminates after a certain amount of time has passed. This criterium does finish the iteration, however.
+///
+/// The clock starts at the first call to ```terminate``` or ```progress```, i.e. once the search runs,
+/// such that time spent between construction and optimization does not count against the budget.
+/// Alternatively, terminate at a [Deadline] shared with other heuristics, see [TimeTerminator::until].
+#[cfg(feature = "std-time")]
 pub struct TimeTerminator {
-    time_end: SystemTime,
+    computation_time_max: Duration,
+    time_start: RefCell<Option<SystemTime>>,
+    deadline: Option<Deadline>,
+}
+
+/// A fixed point in time, shared by several heuristics such that they all stop by then.
+///
+/// Clones refer to the same point in time. Handing a deadline to every stage of, e.g., a [crate::pipeline::Pipeline]
+/// splits a single time budget among the stages: later stages may use whatever time earlier stages leave.
+#[cfg(feature = "std-time")]
+#[derive(Clone, Debug)]
+pub struct Deadline {
+    window: Arc<(Instant, Instant)>,
 }
 
 /// Terminates when all termination criteria evaluate to true.
@@ -55,13 +139,151 @@ impl IterationTerminator {
     }
 }
 
+impl BudgetTerminator {
+    /// Allow ```neighbors``` neighbor evaluations in total.
+    pub fn new(neighbors: usize) -> Self {
+        Self {
+            neighbors,
+            budget: Rc::new(Cell::new(neighbors)),
+        }
+    }
+
+    /// Return the shared budget, to hand to a [crate::operators::BudgetedNeighborhood].
+    pub fn budget(&self) -> Rc<Cell<usize>> {
+        self.budget.clone()
+    }
+
+    /// Return the number of neighbor evaluations left.
+    pub fn remaining(&self) -> usize {
+        self.budget.get()
+    }
+}
+
+impl TemperatureTerminator {
+    pub fn new<T: CoolingSchedule + 'static>(schedule: Rc<T>, t_min: f32) -> Self {
+        Self { schedule, t_min }
+    }
+}
+
+impl NoAcceptTerminator {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            rejections: RefCell::new(0),
+        }
+    }
+}
+
+impl ImprovementRateTerminator {
+    pub fn new(window: usize, min_improvement: f32) -> Self {
+        Self {
+            window,
+            min_improvement,
+            restarts_max: 0,
+            restarts: RefCell::new(0),
+            history: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    /// Signal a restart on the first ```restarts``` stalls, instead of terminating (default: 0)
+    pub fn restarts(mut self, restarts: usize) -> Self {
+        self.restarts_max = restarts;
+        self
+    }
+
+    /// Return the number of restarts signalled so far.
+    pub fn restarts_signalled(&self) -> usize {
+        *self.restarts.borrow()
+    }
+
+    /// Record the objective of the incumbent, and test whether the search stalled.
+    ///
+    /// After a restart the window starts anew.
+    pub fn signal(&self, objective: f32) -> StallSignal {
+        let mut history = self.history.borrow_mut();
+        let best = history.back().map_or(objective, |x| x.min(objective));
+        history.push_back(best);
+        if history.len() <= self.window {
+            return StallSignal::Continue;
+        }
+
+        let oldest = history.pop_front().expect("history is not empty");
+        if oldest - best >= self.min_improvement {
+            return StallSignal::Continue;
+        }
+
+        history.clear();
+        if *self.restarts.borrow() < self.restarts_max {
+            self.restarts.borrow_mut().add_assign(1);
+            StallSignal::Restart
+        } else {
+            StallSignal::Terminate
+        }
+    }
+}
+
+#[cfg(feature = "std-time")]
 impl TimeTerminator {
     pub fn new(computation_time_max: Duration) -> Self {
-        let now = std::time::SystemTime::now();
         Self {
-            time_end: now.add(computation_time_max),
+            computation_time_max,
+            time_start: RefCell::new(None),
+            deadline: None,
+        }
+    }
+
+    /// Terminate at ```deadline```, regardless of when the search started.
+    pub fn until(deadline: Deadline) -> Self {
+        Self {
+            computation_time_max: deadline.budget(),
+            time_start: RefCell::new(None),
+            deadline: Some(deadline),
         }
     }
+
+    /// Return the time passed since the clock started, and start it if it has not yet.
+    fn elapsed(&self) -> Duration {
+        let time_start = *self
+            .time_start
+            .borrow_mut()
+            .get_or_insert_with(SystemTime::now);
+        time_start.elapsed().unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "std-time")]
+impl Deadline {
+    /// Set the deadline ```budget``` from now.
+    pub fn after(budget: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            window: Arc::new((now, now + budget)),
+        }
+    }
+
+    /// Return the time left until the deadline, which is zero once it has passed.
+    pub fn remaining(&self) -> Duration {
+        self.window.1.saturating_duration_since(Instant::now())
+    }
+
+    /// Test whether the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.window.1
+    }
+
+    /// Return the total time between setting the deadline and the deadline itself.
+    pub fn budget(&self) -> Duration {
+        self.window.1 - self.window.0
+    }
+
+    /// Return the fraction of the budget used so far.
+    fn progress(&self) -> f32 {
+        let budget = self.budget();
+        if budget.is_zero() {
+            return 1.;
+        }
+        (self.window.0.elapsed().as_secs_f32() / budget.as_secs_f32()).min(1.)
+    }
 }
 
 impl Terminator {
@@ -106,9 +328,18 @@ impl<Solution> TerminatorBuilder<Solution> {
     }
 
     /// Add a time limit.
+    #[cfg(feature = "std-time")]
     pub fn computation_time(mut self, computation_time_max: Duration) -> Self {
-        let time_end = std::time::SystemTime::now() + computation_time_max;
-        self.terminators.push(Box::new(TimeTerminator { time_end }));
+        self.terminators
+            .push(Box::new(TimeTerminator::new(computation_time_max)));
+        self
+    }
+
+    /// Add a deadline, which may be shared with other heuristics.
+    #[cfg(feature = "std-time")]
+    pub fn deadline(mut self, deadline: Deadline) -> Self {
+        self.terminators
+            .push(Box::new(TimeTerminator::until(deadline)));
         self
     }
 
@@ -129,28 +360,275 @@ impl<Solution> TerminationCriteria<Solution> for OrTerminator<Solution> {
     fn terminate(&self, solution: &Solution) -> bool {
         self.terminators.iter().any(|x| x.terminate(solution))
     }
+
+    /// The progress of the criterium closest to firing, i.e. the maximum progress of the bounded criteria.
+    fn progress(&self) -> Option<f32> {
+        self.terminators
+            .iter()
+            .filter_map(|x| x.progress())
+            .reduce(f32::max)
+    }
+
+    fn feedback(&self, status: ProposalEvaluation) {
+        self.terminators.iter().for_each(|x| x.feedback(status));
+    }
+
+    fn resume(&self, iterations: usize) {
+        self.terminators.iter().for_each(|x| x.resume(iterations));
+    }
 }
 
 impl<Solution> TerminationCriteria<Solution> for AndTerminator<Solution> {
     fn terminate(&self, solution: &Solution) -> bool {
         self.terminators.iter().all(|x| x.terminate(solution))
     }
+
+    /// The minimum progress of all criteria, as all of them must fire. Unbounded if any criterium is.
+    fn progress(&self) -> Option<f32> {
+        self.terminators
+            .iter()
+            .map(|x| x.progress())
+            .reduce(|x, y| Some(x?.min(y?)))
+            .flatten()
+    }
+
+    fn feedback(&self, status: ProposalEvaluation) {
+        self.terminators.iter().for_each(|x| x.feedback(status));
+    }
+
+    fn resume(&self, iterations: usize) {
+        self.terminators.iter().for_each(|x| x.resume(iterations));
+    }
+}
+
+impl<Solution: crate::Evaluate> TerminationCriteria<Solution> for ImprovementRateTerminator {
+    /// Terminate on [StallSignal::Terminate]. Restarts are not acted upon.
+    fn terminate(&self, solution: &Solution) -> bool {
+        self.signal(solution.evaluate()) == StallSignal::Terminate
+    }
+}
+
+impl<Solution> TerminationCriteria<Solution> for NoAcceptTerminator {
+    fn terminate(&self, _solution: &Solution) -> bool {
+        *self.rejections.borrow() >= self.n
+    }
+
+    /// Count rejections, and reset the count on acceptance.
+    fn feedback(&self, status: ProposalEvaluation) {
+        match status {
+            ProposalEvaluation::Reject => self.rejections.borrow_mut().add_assign(1),
+            _ => {
+                self.rejections.replace(0);
+            }
+        }
+    }
+}
+
+impl<Solution> TerminationCriteria<Solution> for BudgetTerminator {
+    fn terminate(&self, _solution: &Solution) -> bool {
+        self.budget.get() == 0
+    }
+
+    /// The fraction of the budget spent.
+    fn progress(&self) -> Option<f32> {
+        if self.neighbors == 0 {
+            return Some(1.);
+        }
+        Some(1. - self.budget.get() as f32 / self.neighbors as f32)
+    }
+}
+
+impl<Solution> TerminationCriteria<Solution> for TemperatureTerminator {
+    fn terminate(&self, _solution: &Solution) -> bool {
+        self.schedule.temperature() < self.t_min
+    }
 }
 
 impl<Solution> TerminationCriteria<Solution> for IterationTerminator {
     fn terminate(&self, _solution: &Solution) -> bool {
         self.iteration.borrow_mut().add_assign(1);
-        if *self.iteration.borrow() == self.n {
-            true
-        } else {
-            false
-        }
+        *self.iteration.borrow() == self.n
+    }
+
+    /// The fraction of iterations performed.
+    fn progress(&self) -> Option<f32> {
+        Some((*self.iteration.borrow() as f32 / self.n as f32).min(1.))
+    }
+
+    /// Count the iterations performed before the pause.
+    fn resume(&self, iterations: usize) {
+        self.iteration.replace(iterations);
     }
 }
 
+#[cfg(feature = "std-time")]
 impl<Solution> TerminationCriteria<Solution> for TimeTerminator {
     fn terminate(&self, _solution: &Solution) -> bool {
-        let now = std::time::SystemTime::now();
-        now >= self.time_end
+        match &self.deadline {
+            Some(deadline) => deadline.is_expired(),
+            None => self.elapsed() >= self.computation_time_max,
+        }
+    }
+
+    /// The fraction of the computation time passed.
+    fn progress(&self) -> Option<f32> {
+        if let Some(deadline) = &self.deadline {
+            return Some(deadline.progress());
+        }
+        let elapsed = self.elapsed();
+        if self.computation_time_max.is_zero() {
+            return Some(1.);
+        }
+        Some((elapsed.as_secs_f32() / self.computation_time_max.as_secs_f32()).min(1.))
+    }
+}
+
+/// Share a termination criterium, e.g. with a [crate::algorithms::sa::ScheduleByProgress].
+impl<Solution, T: TerminationCriteria<Solution> + ?Sized> TerminationCriteria<Solution> for Rc<T> {
+    fn terminate(&self, solution: &Solution) -> bool {
+        self.as_ref().terminate(solution)
+    }
+
+    fn progress(&self) -> Option<f32> {
+        self.as_ref().progress()
+    }
+
+    fn feedback(&self, status: ProposalEvaluation) {
+        self.as_ref().feedback(status)
+    }
+
+    fn resume(&self, iterations: usize) {
+        self.as_ref().resume(iterations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        termination::{
+            ImprovementRateTerminator, NoAcceptTerminator, StallSignal, TerminationCriteria,
+        },
+        test::Number,
+        ProposalEvaluation,
+    };
+
+    #[test]
+    fn no_accept_terminator() {
+        let solution = Number::new(0, 0.);
+        let terminator: Box<dyn TerminationCriteria<Number>> = Box::new(NoAcceptTerminator::new(3));
+        for _ in 0..2 {
+            terminator.feedback(ProposalEvaluation::Reject);
+            assert!(!terminator.terminate(&solution));
+        }
+        terminator.feedback(ProposalEvaluation::Accept);
+        for _ in 0..2 {
+            terminator.feedback(ProposalEvaluation::Reject);
+            assert!(!terminator.terminate(&solution));
+        }
+        terminator.feedback(ProposalEvaluation::Reject);
+        assert!(terminator.terminate(&solution));
+    }
+
+    #[test]
+    fn improvement_rate_restarts() {
+        let detector = ImprovementRateTerminator::new(2, 1.).restarts(1);
+        // improving by 1 per call
+        for objective in [10., 9., 8., 7.] {
+            assert_eq!(detector.signal(objective), StallSignal::Continue);
+        }
+        assert_eq!(detector.signal(7.), StallSignal::Continue);
+        assert_eq!(detector.signal(7.), StallSignal::Restart);
+        assert_eq!(detector.signal(7.), StallSignal::Continue);
+        assert_eq!(detector.signal(7.), StallSignal::Continue);
+        assert_eq!(detector.signal(7.), StallSignal::Terminate);
+        assert_eq!(detector.restarts_signalled(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std-time")]
+    fn time_terminator_starts_lazily() {
+        use crate::termination::TimeTerminator;
+        use std::time::Duration;
+
+        let solution = Number::new(0, 0.);
+        let terminator = TimeTerminator::new(Duration::from_millis(50));
+        // a delay between construction and optimization does not shorten the budget
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!TerminationCriteria::<Number>::terminate(
+            &terminator,
+            &solution
+        ));
+        assert!(TerminationCriteria::<Number>::progress(&terminator).unwrap() < 1.);
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(TerminationCriteria::<Number>::terminate(
+            &terminator,
+            &solution
+        ));
+    }
+
+    #[cfg(feature = "std-time")]
+    #[test]
+    fn composite_progress() {
+        use std::{rc::Rc, time::Duration};
+
+        use crate::termination::{IterationTerminator, Terminator, TimeTerminator};
+
+        let solution = Number::new(0, 0.);
+        let iterations = Rc::new(IterationTerminator::new(10));
+        let time = Rc::new(TimeTerminator::new(Duration::from_secs(1000)));
+        let any = Terminator::builder()
+            .criterium(iterations.clone())
+            .criterium(time.clone())
+            .build();
+        for _ in 0..4 {
+            assert!(!any.terminate(&solution));
+        }
+        let progress_iterations = TerminationCriteria::<Number>::progress(&iterations).unwrap();
+        let progress_time = TerminationCriteria::<Number>::progress(&time).unwrap();
+        assert_eq!(progress_iterations, 0.4);
+        assert_eq!(any.progress(), Some(progress_iterations.max(progress_time)));
+
+        let all = Terminator::builder::<Number>()
+            .criterium(iterations.clone())
+            .criterium(time)
+            .all()
+            .build();
+        assert!(all.progress().unwrap() < 0.4);
+
+        // unbounded criteria are ignored by any, but make all unbounded
+        let any = Terminator::builder::<Number>()
+            .criterium(iterations.clone())
+            .criterium(NoAcceptTerminator::new(3))
+            .build();
+        assert_eq!(any.progress(), Some(0.4));
+        let all = Terminator::builder::<Number>()
+            .criterium(iterations)
+            .criterium(NoAcceptTerminator::new(3))
+            .all()
+            .build();
+        assert_eq!(all.progress(), None);
+    }
+
+    #[test]
+    fn heuristic_progress() {
+        use rand::SeedableRng;
+
+        use crate::{
+            algorithms::local_search::LocalSearch, selectors::RandomSelector,
+            termination::Terminator, test::NeighborSwap, ImprovingHeuristic,
+        };
+
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let search = LocalSearch::builder()
+            .selector(RandomSelector::new(rng.clone()).option(NeighborSwap::new(&[1., 2.])))
+            .terminator(Terminator::builder().iterations(10).build())
+            .rng(rng)
+            .build();
+        let solution = Number::new(0, 1.);
+        assert_eq!(search.progress(), Some(0.));
+        for _ in 0..5 {
+            search.should_terminate(&solution);
+        }
+        assert_eq!(search.progress(), Some(0.5));
     }
 }
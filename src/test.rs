@@ -1,8 +1,14 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
 use rand::Rng;
 
 use crate::{Evaluate, Operator};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct Number {
     value: f32,
     index: usize,
@@ -19,6 +25,58 @@ pub(crate) struct NeighborSwap {
     numbers: Vec<Number>,
 }
 
+/// Leaves the solution untouched, but counts the number of shakes.
+pub(crate) struct CountingShake {
+    count: Rc<Cell<usize>>,
+}
+
+/// Leaves the solution untouched, but records a random number drawn by every shake.
+pub(crate) struct RecordingShake {
+    draws: Rc<RefCell<Vec<u64>>>,
+}
+
+impl RecordingShake {
+    pub fn new(draws: Rc<RefCell<Vec<u64>>>) -> Self {
+        Self { draws }
+    }
+}
+
+impl Operator for RecordingShake {
+    type Solution = Number;
+
+    fn shake(&self, solution: Number, rng: &mut dyn rand::RngCore) -> Number {
+        self.draws.borrow_mut().push(rng.next_u64());
+        solution
+    }
+}
+
+/// Moves to the next index, where the number is infeasible, i.e. negative, half the time.
+pub(crate) struct HalfInfeasible;
+
+impl Operator for HalfInfeasible {
+    type Solution = Number;
+
+    fn shake(&self, solution: Number, rng: &mut dyn rand::RngCore) -> Number {
+        let value = if rng.gen_bool(0.5) { 1. } else { -1. };
+        Number::new(solution.index + 1, value)
+    }
+}
+
+impl CountingShake {
+    pub fn new(count: Rc<Cell<usize>>) -> Self {
+        Self { count }
+    }
+}
+
+impl Operator for CountingShake {
+    type Solution = Number;
+
+    fn shake(&self, solution: Number, _rng: &mut dyn rand::RngCore) -> Number {
+        self.count.set(self.count.get() + 1);
+        solution
+    }
+}
+
 impl Number {
     pub fn new(index: usize, value: f32) -> Self {
         Self { value, index }
@@ -45,7 +103,7 @@ impl Operator for NeighborSwap {
     fn shake(&self, solution: Number, rng: &mut dyn rand::RngCore) -> Self::Solution {
         let index = solution.index;
         let mut options = vec![];
-        if index as isize - 1 >= 0 {
+        if index >= 1 {
             options.push(self.numbers[index - 1].clone());
         }
         if index + 1 < self.numbers.len() {
@@ -61,7 +119,7 @@ impl Operator for NeighborSwap {
 }
 
 impl NeighborsUpUntilN {
-    pub(crate) fn new(numbers: &Vec<f32>, n: usize) -> Self {
+    pub(crate) fn new(numbers: &[f32], n: usize) -> Self {
         Self {
             index_cursor: None,
             n,
@@ -78,6 +136,33 @@ impl NeighborsUpUntilN {
     }
 }
 
+impl std::hash::Hash for Number {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.value.to_bits().hash(state);
+    }
+}
+
+impl crate::SolutionKey for Number {
+    fn key(&self) -> u64 {
+        crate::hash_key(self)
+    }
+}
+
+/// Numbers are as distant as their indices.
+impl crate::Distance for Number {
+    fn distance(&self, other: &Self) -> f32 {
+        self.index.abs_diff(other.index) as f32
+    }
+}
+
+/// Negative numbers are infeasible.
+impl crate::Constrained for Number {
+    fn is_feasible(&self) -> bool {
+        self.value >= 0.
+    }
+}
+
 impl Evaluate for Number {
     fn evaluate(&self) -> f32 {
         self.value
@@ -107,15 +192,15 @@ impl Iterator for NeighborsUpUntilN {
                     return None;
                 }
 
-                let lb = 0 as isize;
+                let lb = 0;
                 let ub = self.numbers.len() as isize - 1;
                 let n = self.n as isize;
-                let iter = self.iter as isize;
+                let iter = self.iter;
 
                 let index = index_cursor as isize - n * (1 - 2 * iter);
                 if index < lb {
                     self.iter += 1;
-                    return self.next();
+                    self.next()
                 } else if index <= ub {
                     let item = self.numbers[index as usize].clone();
                     self.iter += 1;